@@ -4,7 +4,17 @@
 //! and subcommands for configuration management.
 
 use clap::{Parser, Subcommand};
-use std::path::PathBuf;
+use std::{
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::config::CaptureConfig;
+use crate::export::ExportFormat;
+use crate::import::ImportFormat;
+use crate::storage::Db;
+use crate::types::{parse_offset, Task, TaskSource};
+use crate::ui::app::App;
 
 /// WIMM (Where is my mind) - A terminal-based task management application
 #[derive(Parser, Debug)]
@@ -26,6 +36,14 @@ pub struct Cli {
     #[arg(short, long)]
     pub verbose: bool,
 
+    /// Move the due date of every open task by an offset (e.g. +7d, -2w), then exit
+    #[arg(long, value_name = "OFFSET", allow_hyphen_values = true, value_parser = parse_offset_arg)]
+    pub shift_due: Option<chrono::Duration>,
+
+    /// Move the defer date of every open task by an offset (e.g. +7d, -2w), then exit
+    #[arg(long, value_name = "OFFSET", allow_hyphen_values = true, value_parser = parse_offset_arg)]
+    pub shift_defer: Option<chrono::Duration>,
+
     /// Subcommand to run
     #[command(subcommand)]
     pub command: Option<Commands>,
@@ -41,6 +59,63 @@ pub enum Commands {
     },
     /// Start the interactive TUI (default)
     Run,
+    /// Add a task without starting the TUI
+    Add {
+        /// Title of the new task
+        title: String,
+        /// Due date, written as in the TUI (e.g. tomorrow, friday 3pm, 2d)
+        #[arg(long, value_name = "DATE")]
+        due: Option<String>,
+        /// Defer date, written as in the TUI
+        #[arg(long, value_name = "DATE")]
+        defer: Option<String>,
+        /// Description of the new task
+        #[arg(long, value_name = "TEXT")]
+        desc: Option<String>,
+    },
+    /// Import open issues from a saved issue tracker export, or restore a `wimm export` backup
+    Import {
+        /// Issue tracker the export comes from [default: github with --file, otherwise json]
+        #[arg(long, value_enum)]
+        format: Option<ImportFormat>,
+        /// Repository the issues belong to, as owner/repo, or the backup file to restore
+        source: String,
+        /// Saved JSON export of the repository's issues
+        #[arg(long, value_name = "FILE")]
+        file: Option<PathBuf>,
+        /// Skip issues whose title, description and due date match an existing task
+        #[arg(long)]
+        dedup_by_content: bool,
+    },
+    /// Print tasks without starting the TUI
+    List {
+        /// Only completed tasks
+        #[arg(long, conflicts_with = "pending")]
+        completed: bool,
+        /// Only tasks that are not completed yet
+        #[arg(long)]
+        pending: bool,
+        /// Only tasks due before this date, written as in the TUI (e.g. friday, 2d)
+        #[arg(long, value_name = "DATE")]
+        due_before: Option<String>,
+        /// Only tasks with this tag
+        #[arg(long, value_name = "TAG")]
+        tag: Option<String>,
+        /// Print the tasks as JSON instead of a table
+        #[arg(long)]
+        json: bool,
+    },
+    /// Write every task to stdout or a file, e.g. as a backup
+    Export {
+        /// Format to write the tasks in
+        #[arg(long, value_enum, default_value = "json")]
+        format: ExportFormat,
+        /// File to write to instead of stdout
+        #[arg(short, long, value_name = "FILE")]
+        output: Option<PathBuf>,
+    },
+    /// Show task counts and how long open tasks have been waiting
+    Stats,
 }
 
 /// Configuration subcommands
@@ -87,10 +162,92 @@ impl Cli {
 
     /// Check if we should run the TUI or handle a subcommand
     pub fn should_run_tui(&self) -> bool {
+        if self.shift_due.is_some() || self.shift_defer.is_some() {
+            return false;
+        }
         match &self.command {
             None | Some(Commands::Run) => true,
-            Some(Commands::Config { .. }) => false,
+            Some(
+                Commands::Config { .. }
+                | Commands::Add { .. }
+                | Commands::Import { .. }
+                | Commands::List { .. }
+                | Commands::Export { .. }
+                | Commands::Stats,
+            ) => false,
+        }
+    }
+
+    /// Build the task described by a `wimm add` invocation
+    ///
+    /// The dates come from the `--due` and `--defer` flags, read with
+    /// [`Cli::parse_date_flag`]. The `[capture]` settings prefix the title
+    /// and tag the task so captures are easy to find and triage later.
+    pub fn task_from_add(
+        title: &str,
+        description: Option<&str>,
+        due: Option<SystemTime>,
+        defer_until: Option<SystemTime>,
+        now: SystemTime,
+        capture: &CaptureConfig,
+    ) -> Task {
+        let mut task = Task::new(
+            &format!("{}{title}", capture.title_prefix),
+            TaskSource::Cli,
+            now,
+        );
+        task.description = description.unwrap_or_default().to_string();
+        task.due = due;
+        task.defer_until = defer_until;
+        if let Some(tag) = capture.default_tag.as_ref().filter(|tag| !tag.is_empty()) {
+            task.tags.push(tag.clone());
         }
+        task
+    }
+
+    /// Read the value of a date flag the same way dates typed into the TUI are read
+    pub fn parse_date_flag<D: Db>(
+        app: &App<D>,
+        flag: &str,
+        input: Option<&str>,
+        is_due_date: bool,
+    ) -> Result<Option<SystemTime>, String> {
+        input
+            .map(|input| app.try_parse_date_input(input, is_due_date))
+            .transpose()
+            .map(Option::flatten)
+            .map_err(|e| format!("--{flag}: {e}"))
+    }
+
+    /// Format a `wimm import` invocation reads its source in
+    ///
+    /// Without `--format`, a source given with `--file` is a GitHub
+    /// repository and any other source is a backup to restore. Restoring
+    /// takes no `--file` or `--dedup-by-content`, so those are refused
+    /// rather than ignored.
+    pub fn import_format(
+        format: Option<ImportFormat>,
+        file: Option<&Path>,
+        dedup_by_content: bool,
+    ) -> Result<ImportFormat, String> {
+        let format = format.unwrap_or(if file.is_some() {
+            ImportFormat::Github
+        } else {
+            ImportFormat::Json
+        });
+        if format == ImportFormat::Json {
+            if file.is_some() {
+                return Err("--file doesn't apply when restoring a backup; \
+                            pass the backup file as the source"
+                    .to_string());
+            }
+            if dedup_by_content {
+                return Err("--dedup-by-content doesn't apply when restoring a backup; \
+                            tasks already present are matched by ID"
+                    .to_string());
+            }
+        }
+        Ok(format)
     }
 
     /// Check if any configuration changes were requested
@@ -99,9 +256,15 @@ impl Cli {
     }
 }
 
+/// Parse a `--shift-due`/`--shift-defer` offset such as `+7d` or `-2w`
+fn parse_offset_arg(input: &str) -> Result<chrono::Duration, String> {
+    parse_offset(input).ok_or_else(|| format!("expected an offset like +7d or -2w, got '{input}'"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::AppState;
     use clap::CommandFactory;
 
     #[test]
@@ -115,6 +278,8 @@ mod tests {
         let cli = Cli {
             config: None,
             verbose: false,
+            shift_due: None,
+            shift_defer: None,
             command: None,
         };
         assert!(!cli.has_config_changes());
@@ -125,6 +290,8 @@ mod tests {
         let cli = Cli {
             config: None,
             verbose: false,
+            shift_due: None,
+            shift_defer: None,
             command: None,
         };
         assert!(cli.should_run_tui());
@@ -132,6 +299,8 @@ mod tests {
         let cli = Cli {
             config: None,
             verbose: false,
+            shift_due: None,
+            shift_defer: None,
             command: Some(Commands::Run),
         };
         assert!(cli.should_run_tui());
@@ -139,6 +308,8 @@ mod tests {
         let cli = Cli {
             config: None,
             verbose: false,
+            shift_due: None,
+            shift_defer: None,
             command: Some(Commands::Config {
                 action: ConfigAction::Show,
             }),
@@ -176,6 +347,207 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_shift_due_flag_parses_offsets() {
+        let cli = Cli::try_parse_from(["wimm", "--shift-due", "-2w"]).unwrap();
+        assert_eq!(cli.shift_due, Some(chrono::Duration::days(-14)));
+        assert!(!cli.should_run_tui());
+        assert!(Cli::try_parse_from(["wimm", "--shift-defer", "soon"]).is_err());
+    }
+
+    #[test]
+    fn test_add_command_creates_cli_task() {
+        let cli = Cli::try_parse_from(["wimm", "add", "Buy milk"]).unwrap();
+        assert!(!cli.should_run_tui());
+
+        let Some(Commands::Add { title, due, .. }) = cli.command else {
+            panic!("Expected add command");
+        };
+        assert_eq!(due, None);
+        let capture = CaptureConfig::default();
+        let task = Cli::task_from_add(&title, None, None, None, SystemTime::now(), &capture);
+        assert_eq!(task.title, "Buy milk");
+        assert_eq!(task.source, TaskSource::Cli);
+        assert!(task.tags.is_empty());
+    }
+
+    #[test]
+    fn test_add_command_flags() {
+        let cli = Cli::try_parse_from([
+            "wimm",
+            "add",
+            "Pay rent",
+            "--due",
+            "friday 3pm",
+            "--defer",
+            "2d",
+            "--desc",
+            "Transfer, not cheque",
+        ])
+        .unwrap();
+        let Some(Commands::Add {
+            title,
+            due,
+            defer,
+            desc,
+        }) = cli.command
+        else {
+            panic!("Expected add command");
+        };
+        assert_eq!(title, "Pay rent");
+        assert_eq!(due.as_deref(), Some("friday 3pm"));
+        assert_eq!(defer.as_deref(), Some("2d"));
+        assert_eq!(desc.as_deref(), Some("Transfer, not cheque"));
+
+        let app = App::new(AppState::default());
+        let due = Cli::parse_date_flag(&app, "due", due.as_deref(), true).unwrap();
+        let defer_until = Cli::parse_date_flag(&app, "defer", defer.as_deref(), false).unwrap();
+        let now = SystemTime::now();
+        let task = Cli::task_from_add(
+            &title,
+            desc.as_deref(),
+            due,
+            defer_until,
+            now,
+            &CaptureConfig::default(),
+        );
+        assert_eq!(task.description, "Transfer, not cheque");
+        assert!(task.due.is_some_and(|due| due > now));
+        assert!(task.defer_until.is_some_and(|defer| defer > now));
+        assert_eq!(task.due, app.parse_date_input("friday 3pm", true));
+
+        let error = Cli::parse_date_flag(&app, "due", Some("not a date"), true).unwrap_err();
+        assert!(error.starts_with("--due: "), "{error}");
+        assert_eq!(Cli::parse_date_flag(&app, "due", None, true), Ok(None));
+    }
+
+    #[test]
+    fn test_add_command_applies_capture_settings() {
+        let capture = CaptureConfig {
+            default_tag: Some("@inbox".to_string()),
+            title_prefix: "Inbox: ".to_string(),
+        };
+        let task = Cli::task_from_add("Buy milk", None, None, None, SystemTime::now(), &capture);
+        assert_eq!(task.title, "Inbox: Buy milk");
+        assert_eq!(task.tags, vec!["@inbox"]);
+    }
+
+    #[test]
+    fn test_stats_command_parses() {
+        let cli = Cli::try_parse_from(["wimm", "stats"]).unwrap();
+        assert!(!cli.should_run_tui());
+        assert!(matches!(cli.command, Some(Commands::Stats)));
+    }
+
+    #[test]
+    fn test_import_command_parses() {
+        let cli = Cli::try_parse_from(["wimm", "import", "wimm-io/wimm", "--file", "issues.json"])
+            .unwrap();
+        assert!(!cli.should_run_tui());
+
+        let Some(Commands::Import {
+            format,
+            source,
+            file,
+            dedup_by_content,
+        }) = cli.command
+        else {
+            panic!("Expected import command");
+        };
+        assert_eq!(format, None);
+        assert_eq!(source, "wimm-io/wimm");
+        assert_eq!(file, Some(PathBuf::from("issues.json")));
+        assert!(!dedup_by_content);
+
+        let cli = Cli::try_parse_from(["wimm", "import", "--format", "gitlab", "o/r"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Import {
+                format: Some(ImportFormat::Gitlab),
+                file: None,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_import_format_from_flags() {
+        let file = Some(Path::new("issues.json"));
+        assert_eq!(
+            Cli::import_format(None, file, true),
+            Ok(ImportFormat::Github)
+        );
+        assert_eq!(
+            Cli::import_format(None, None, false),
+            Ok(ImportFormat::Json)
+        );
+        assert_eq!(
+            Cli::import_format(Some(ImportFormat::Gitlab), None, false),
+            Ok(ImportFormat::Gitlab)
+        );
+
+        // Flags that only apply to issues are refused when restoring
+        let json = Some(ImportFormat::Json);
+        assert!(Cli::import_format(json, file, false)
+            .unwrap_err()
+            .contains("--file"));
+        assert!(Cli::import_format(json, None, true)
+            .unwrap_err()
+            .contains("--dedup-by-content"));
+        assert!(Cli::import_format(None, None, true).is_err());
+    }
+
+    #[test]
+    fn test_list_command_parses() {
+        let cli = Cli::try_parse_from([
+            "wimm",
+            "list",
+            "--pending",
+            "--due-before",
+            "friday",
+            "--tag",
+            "home",
+            "--json",
+        ])
+        .unwrap();
+        assert!(!cli.should_run_tui());
+        let Some(Commands::List {
+            completed,
+            pending,
+            due_before,
+            tag,
+            json,
+        }) = cli.command
+        else {
+            panic!("Expected list command");
+        };
+        assert!(!completed && pending && json);
+        assert_eq!(due_before.as_deref(), Some("friday"));
+        assert_eq!(tag.as_deref(), Some("home"));
+
+        assert!(Cli::try_parse_from(["wimm", "list", "--completed", "--pending"]).is_err());
+    }
+
+    #[test]
+    fn test_export_command_parses() {
+        let cli = Cli::try_parse_from(["wimm", "export", "--format", "json"]).unwrap();
+        assert!(!cli.should_run_tui());
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Export {
+                format: ExportFormat::Json,
+                output: None,
+            })
+        ));
+
+        let cli = Cli::try_parse_from(["wimm", "export", "-o", "backup.json"]).unwrap();
+        let Some(Commands::Export { output, .. }) = cli.command else {
+            panic!("Expected export command");
+        };
+        assert_eq!(output, Some(PathBuf::from("backup.json")));
+        assert!(Cli::try_parse_from(["wimm", "export", "--format", "csv"]).is_err());
+    }
+
     #[test]
     fn test_config_set_with_key_value() {
         // Test that we can parse config set with key-value format
@@ -6,9 +6,18 @@
 use chrono::{DateTime, Local, NaiveTime, TimeZone};
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, fs, path::PathBuf};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
 use thiserror::Error;
 
+use crate::{
+    storage::{restrict_to_owner, write_file_atomically},
+    types::{TaskStatus, View},
+};
+
 /// Configuration-related errors
 #[derive(Error, Debug)]
 pub enum ConfigError {
@@ -22,6 +31,8 @@ pub enum ConfigError {
     NoConfigDir,
     #[error("Invalid time format: {0}")]
     InvalidTime(String),
+    #[error("Invalid date format '{0}' (use strftime fields like %Y-%m-%d or %b %d)")]
+    InvalidDateFormat(String),
 }
 
 /// Color scheme configuration
@@ -63,6 +74,199 @@ impl Default for ColorScheme {
     }
 }
 
+/// Prefix of Normal mode bindings that are typed after the leader key
+pub const LEADER_PREFIX: &str = "<leader>";
+
+/// Something a key can be bound to in a [`Keymap`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    NewTaskBelow,
+    NewTaskAbove,
+    EditTask,
+    OpenDetail,
+    /// Close overlays in Normal mode, cancel the edit in Insert mode
+    Escape,
+    ToggleAbout,
+    ToggleHelp,
+    ToggleScratch,
+    EditScratch,
+    ToggleFocus,
+    CycleSort,
+    ReverseSort,
+    CellPopup,
+    ClearFilters,
+    MoveDown,
+    MoveUp,
+    MoveTop,
+    MoveBottom,
+    NextUrgent,
+    PreviousUrgent,
+    ToggleComplete,
+    CycleStatus,
+    CyclePriority,
+    ToggleTimer,
+    ToggleTagFilter,
+    ToggleSomeday,
+    Demote,
+    ToggleMark,
+    DeleteTasks,
+    DueFromDefer,
+    DeferFromDue,
+    Undo,
+    Redo,
+    CommandMode,
+    Search,
+    NextMatch,
+    PreviousMatch,
+    /// Save the task being edited (or advance, see `editing.enter_behavior`)
+    Confirm,
+    NextField,
+    PrevField,
+}
+
+impl Action {
+    /// Look up an action by the name used in keymaps (e.g. `"move_down"`)
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "quit" => Action::Quit,
+            "new_task" | "new_task_below" => Action::NewTaskBelow,
+            "new_task_above" => Action::NewTaskAbove,
+            "edit_task" => Action::EditTask,
+            "open_detail" => Action::OpenDetail,
+            "escape" => Action::Escape,
+            "about" => Action::ToggleAbout,
+            "help" => Action::ToggleHelp,
+            "toggle_scratch" => Action::ToggleScratch,
+            "edit_scratch" => Action::EditScratch,
+            "toggle_focus" => Action::ToggleFocus,
+            "cycle_sort" => Action::CycleSort,
+            "reverse_sort" => Action::ReverseSort,
+            "cell_popup" => Action::CellPopup,
+            "clear_filters" => Action::ClearFilters,
+            "move_down" => Action::MoveDown,
+            "move_up" => Action::MoveUp,
+            "move_top" => Action::MoveTop,
+            "move_bottom" => Action::MoveBottom,
+            "next_urgent" => Action::NextUrgent,
+            "previous_urgent" => Action::PreviousUrgent,
+            "complete_task" => Action::ToggleComplete,
+            "cycle_status" => Action::CycleStatus,
+            "cycle_priority" => Action::CyclePriority,
+            "toggle_timer" => Action::ToggleTimer,
+            "toggle_tag_filter" => Action::ToggleTagFilter,
+            "toggle_someday" => Action::ToggleSomeday,
+            "demote" => Action::Demote,
+            "toggle_mark" => Action::ToggleMark,
+            "delete_task" => Action::DeleteTasks,
+            "due_from_defer" => Action::DueFromDefer,
+            "defer_from_due" => Action::DeferFromDue,
+            "undo" => Action::Undo,
+            "redo" => Action::Redo,
+            "command" => Action::CommandMode,
+            "search" => Action::Search,
+            "next_match" => Action::NextMatch,
+            "previous_match" => Action::PreviousMatch,
+            "confirm" => Action::Confirm,
+            "next_field" => Action::NextField,
+            "prev_field" => Action::PrevField,
+            _ => return None,
+        })
+    }
+}
+
+/// Normal mode bindings used for keys a keymap leaves out
+///
+/// Two-character keys such as `]d` are sequences: the first key waits for
+/// the second.
+pub const DEFAULT_NORMAL_BINDINGS: &[(&str, &str)] = &[
+    ("q", "quit"),
+    ("o", "new_task_below"),
+    ("O", "new_task_above"),
+    ("i", "edit_task"),
+    ("Enter", "open_detail"),
+    ("Esc", "escape"),
+    ("?", "about"),
+    ("h", "help"),
+    ("P", "toggle_scratch"),
+    ("I", "edit_scratch"),
+    ("F", "toggle_focus"),
+    ("S", "cycle_sort"),
+    ("r", "reverse_sort"),
+    ("K", "cell_popup"),
+    ("\\", "clear_filters"),
+    ("j", "move_down"),
+    ("k", "move_up"),
+    ("g", "move_top"),
+    ("G", "move_bottom"),
+    ("]d", "next_urgent"),
+    ("[d", "previous_urgent"),
+    ("!", "complete_task"),
+    ("c", "cycle_status"),
+    ("p", "cycle_priority"),
+    ("T", "toggle_timer"),
+    ("t", "toggle_tag_filter"),
+    ("s", "toggle_someday"),
+    (">", "demote"),
+    ("x", "toggle_mark"),
+    ("D", "delete_task"),
+    ("=d", "due_from_defer"),
+    ("=f", "defer_from_due"),
+    ("u", "undo"),
+    (":", "command"),
+    ("/", "search"),
+    ("n", "next_match"),
+    ("N", "previous_match"),
+];
+
+/// Insert mode bindings used for keys a keymap leaves out
+pub const DEFAULT_INSERT_BINDINGS: &[(&str, &str)] = &[
+    ("Esc", "escape"),
+    ("Enter", "confirm"),
+    ("Tab", "next_field"),
+    ("Shift+Tab", "prev_field"),
+];
+
+/// Normal mode bindings of the built-in keymaps, by keymap name, as older
+/// versions wrote them into every config file
+///
+/// Keys were not dispatched through the keymap then, so these copies went
+/// unused; see [`Keymap::migrate_legacy`].
+const LEGACY_NORMAL_BINDINGS: &[(&str, &[(&str, &str)])] = &[
+    (
+        "default",
+        &[
+            ("q", "quit"),
+            ("?", "help"),
+            ("n", "new_task"),
+            ("e", "edit_task"),
+            ("d", "delete_task"),
+            ("c", "complete_task"),
+            ("j", "move_down"),
+            ("k", "move_up"),
+            ("Enter", "edit_task"),
+            ("Esc", "escape"),
+        ],
+    ),
+    (
+        "vi",
+        &[
+            ("q", "quit"),
+            ("?", "help"),
+            ("i", "new_task"),
+            ("e", "edit_task"),
+            ("dd", "delete_task"),
+            ("x", "complete_task"),
+            ("j", "move_down"),
+            ("k", "move_up"),
+            ("Enter", "edit_task"),
+            ("Esc", "escape"),
+            ("gg", "move_top"),
+            ("G", "move_bottom"),
+        ],
+    ),
+];
+
 /// Keymap configuration for different modes
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Keymap {
@@ -72,38 +276,154 @@ pub struct Keymap {
     pub normal: HashMap<String, String>,
     /// Insert mode key bindings
     pub insert: HashMap<String, String>,
+    /// Key that starts a `<leader>` sequence in Normal mode (empty disables them)
+    #[serde(default = "default_leader")]
+    pub leader: String,
+}
+
+fn default_leader() -> String {
+    " ".to_string()
+}
+
+/// Leader sequences bound in the built-in keymaps
+fn insert_leader_bindings(normal: &mut HashMap<String, String>) {
+    for (key, action) in [
+        ("s", "cycle_sort"),
+        ("r", "reverse_sort"),
+        ("p", "toggle_scratch"),
+        ("f", "toggle_focus"),
+        ("c", "clear_filters"),
+    ] {
+        normal.insert(format!("{LEADER_PREFIX}{key}"), action.to_string());
+    }
+}
+
+impl Keymap {
+    /// The Normal mode action for `key` (e.g. `"j"`, `"Enter"` or `"gg"`)
+    ///
+    /// Keys the keymap doesn't bind fall back to [`DEFAULT_NORMAL_BINDINGS`].
+    /// A binding to an unknown action name does nothing, so a stale config
+    /// never breaks input.
+    pub fn normal_action(&self, key: &str) -> Option<Action> {
+        lookup(&self.normal, DEFAULT_NORMAL_BINDINGS, key)
+    }
+
+    /// The Insert mode action for `key`, falling back to [`DEFAULT_INSERT_BINDINGS`]
+    pub fn insert_action(&self, key: &str) -> Option<Action> {
+        lookup(&self.insert, DEFAULT_INSERT_BINDINGS, key)
+    }
+
+    /// Whether `key` starts a two-key Normal mode sequence such as `gg` or `]d`
+    pub fn starts_sequence(&self, key: char) -> bool {
+        let starts = |binding: &str| {
+            let mut chars = binding.chars();
+            chars.next() == Some(key) && chars.next().is_some() && chars.next().is_none()
+        };
+        self.normal.keys().any(|binding| starts(binding))
+            || DEFAULT_NORMAL_BINDINGS
+                .iter()
+                .any(|(binding, _)| starts(binding))
+    }
+
+    /// Update a keymap copied from an older built-in keymap to the current `builtin`
+    ///
+    /// An untouched copy is replaced with `builtin` outright. Otherwise each
+    /// binding that still holds the old built-in action takes `builtin`'s
+    /// action for that key, or falls back to the defaults if `builtin` leaves
+    /// the key out, while bindings the user changed or added are kept.
+    pub fn migrate_legacy(&mut self, builtin: &Keymap) {
+        let Some((_, legacy)) = LEGACY_NORMAL_BINDINGS
+            .iter()
+            .find(|(name, _)| *name == builtin.name)
+        else {
+            return;
+        };
+        if self.normal == bindings(legacy) {
+            self.normal = builtin.normal.clone();
+            return;
+        }
+        for (key, action) in legacy.iter() {
+            if self.normal.get(*key).map(String::as_str) != Some(action) {
+                continue;
+            }
+            match builtin.normal.get(*key) {
+                Some(current) => self.normal.insert(key.to_string(), current.clone()),
+                None => self.normal.remove(*key),
+            };
+        }
+    }
+
+    /// The leader key, or `None` when leader sequences are turned off
+    pub fn leader_key(&self) -> Option<char> {
+        let mut chars = self.leader.chars();
+        match (chars.next(), chars.next()) {
+            (Some(key), None) => Some(key),
+            _ => None,
+        }
+    }
+
+    /// Leader sequences as (key typed after the leader, action), ordered by key
+    pub fn leader_bindings(&self) -> Vec<(&str, &str)> {
+        let mut bindings: Vec<(&str, &str)> = self
+            .normal
+            .iter()
+            .filter_map(|(key, action)| {
+                let key = key.strip_prefix(LEADER_PREFIX)?;
+                (key.chars().count() == 1).then_some((key, action.as_str()))
+            })
+            .collect();
+        bindings.sort();
+        bindings
+    }
+
+    /// The action bound to the leader key followed by `key`
+    pub fn leader_action(&self, key: char) -> Option<Action> {
+        self.normal
+            .get(&format!("{LEADER_PREFIX}{key}"))
+            .and_then(|name| Action::from_name(name))
+    }
+}
+
+fn lookup(
+    bindings: &HashMap<String, String>,
+    defaults: &[(&str, &str)],
+    key: &str,
+) -> Option<Action> {
+    match bindings.get(key) {
+        Some(name) => Action::from_name(name),
+        None => defaults
+            .iter()
+            .find(|(default, _)| *default == key)
+            .and_then(|(_, name)| Action::from_name(name)),
+    }
+}
+
+/// Owned copies of built-in bindings, for building a keymap
+fn bindings(defaults: &[(&str, &str)]) -> HashMap<String, String> {
+    defaults
+        .iter()
+        .map(|(key, action)| (key.to_string(), action.to_string()))
+        .collect()
 }
 
 impl Default for Keymap {
     fn default() -> Self {
-        let mut normal = HashMap::new();
-        normal.insert("q".to_string(), "quit".to_string());
-        normal.insert("?".to_string(), "help".to_string());
-        normal.insert("n".to_string(), "new_task".to_string());
-        normal.insert("e".to_string(), "edit_task".to_string());
-        normal.insert("d".to_string(), "delete_task".to_string());
-        normal.insert("c".to_string(), "complete_task".to_string());
-        normal.insert("j".to_string(), "move_down".to_string());
-        normal.insert("k".to_string(), "move_up".to_string());
-        normal.insert("Enter".to_string(), "edit_task".to_string());
-        normal.insert("Esc".to_string(), "escape".to_string());
-
-        let mut insert = HashMap::new();
-        insert.insert("Esc".to_string(), "escape".to_string());
-        insert.insert("Enter".to_string(), "confirm".to_string());
-        insert.insert("Tab".to_string(), "next_field".to_string());
-        insert.insert("Shift+Tab".to_string(), "prev_field".to_string());
+        let mut normal = bindings(DEFAULT_NORMAL_BINDINGS);
+        insert_leader_bindings(&mut normal);
+        let insert = bindings(DEFAULT_INSERT_BINDINGS);
 
         Self {
             name: "default".to_string(),
             normal,
             insert,
+            leader: default_leader(),
         }
     }
 }
 
 /// Time-related default settings
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
 pub struct TimeDefaults {
     /// Default hour (0-23) to use for defer dates when only date is specified
     pub defer_hour: u32,
@@ -111,6 +431,12 @@ pub struct TimeDefaults {
     pub due_hour: u32,
     /// Default timezone (use system timezone if None)
     pub timezone: Option<String>,
+    /// Hour (0-23) the "morning" date keyword resolves to
+    pub morning_hour: u32,
+    /// Hour (0-23) the "afternoon" date keyword resolves to
+    pub afternoon_hour: u32,
+    /// Hour (0-23) the "evening" date keyword resolves to
+    pub evening_hour: u32,
 }
 
 impl Default for TimeDefaults {
@@ -119,6 +445,9 @@ impl Default for TimeDefaults {
             defer_hour: 9,  // 9 AM
             due_hour: 17,   // 5 PM
             timezone: None, // Use system timezone
+            morning_hour: 9,
+            afternoon_hour: 14,
+            evening_hour: 19,
         }
     }
 }
@@ -155,6 +484,375 @@ impl TimeDefaults {
     }
 }
 
+/// User interface preferences
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct UiConfig {
+    /// Glyph shown next to tasks whose defer date is still in the future
+    pub defer_glyph: String,
+    /// Maximum characters of the description shown in the list (0 shows it all)
+    pub desc_preview_chars: usize,
+    /// Message shown when the today view has nothing actionable (empty disables it)
+    pub today_clear_message: String,
+    /// Show a signed days-until-due number next to the due date
+    pub show_days_column: bool,
+    /// How multi-tag tasks are placed when the list is grouped by tag
+    pub tag_grouping: TagGrouping,
+    /// Marker ending text clipped to fit a column (`K` shows the full cell)
+    pub truncation_indicator: String,
+    /// View the task list opens in
+    pub default_view: View,
+    /// Briefly highlight a task's row when it is completed
+    pub completion_feedback: bool,
+    /// Fields Tab cycles through in Insert mode, in order (empty means all)
+    pub edit_fields: Vec<EditField>,
+    /// Cancel an unanswered y/n prompt after this many seconds (0 waits forever)
+    pub confirm_timeout_secs: u64,
+    /// Flag tasks that have been waiting this many days or more (0 disables)
+    pub waiting_nudge_days: u64,
+    /// Statuses the status cycle key steps through, in order
+    pub status_cycle: Vec<TaskStatus>,
+    /// Most changes `u` can undo (0 disables undo)
+    pub undo_depth: usize,
+    /// Show when each task was created
+    pub show_created_column: bool,
+    /// How the created column shows creation time
+    pub created_format: CreatedFormat,
+    /// Re-filter and re-sort the list as time passes, not only after keypresses
+    pub live_resort: bool,
+    /// Leave tasks out of the list while deferred, rather than dimming them
+    pub hide_deferred: bool,
+    /// Keep completed tasks out of every view but `:done`, the log of finished work
+    pub done_log: bool,
+    /// Rows kept visible above and below the cursor when scrolling (like vim's `scrolloff`)
+    pub scroll_off: usize,
+    /// Show bold, italics, bullets and links in the detail pane's description and notes
+    pub render_markdown: bool,
+    /// strftime-style format for dates shown in the list and detail pane
+    pub date_format: String,
+}
+
+impl Default for UiConfig {
+    fn default() -> Self {
+        Self {
+            defer_glyph: "⏸".to_string(),
+            desc_preview_chars: 60,
+            today_clear_message: "All clear for today 🎉".to_string(),
+            show_days_column: false,
+            tag_grouping: TagGrouping::Each,
+            truncation_indicator: "…".to_string(),
+            default_view: View::All,
+            completion_feedback: true,
+            edit_fields: EditField::ALL.to_vec(),
+            confirm_timeout_secs: 0,
+            waiting_nudge_days: 7,
+            status_cycle: vec![TaskStatus::Todo, TaskStatus::InProgress, TaskStatus::Done],
+            undo_depth: 10,
+            show_created_column: true,
+            created_format: CreatedFormat::Hybrid,
+            live_resort: false,
+            hide_deferred: false,
+            done_log: false,
+            scroll_off: 0,
+            render_markdown: false,
+            date_format: "%Y-%m-%d".to_string(),
+        }
+    }
+}
+
+/// Check that `format` is a usable strftime-style date format
+///
+/// chrono panics when displaying a date with an invalid format, so the
+/// format is tried on a known date instead of trusting it until render time.
+pub fn validate_date_format(format: &str) -> Result<(), ConfigError> {
+    use std::fmt::Write;
+
+    let sample = DateTime::<Local>::from(std::time::SystemTime::UNIX_EPOCH);
+    let mut out = String::new();
+    if format.is_empty() || write!(out, "{}", sample.format(format)).is_err() {
+        return Err(ConfigError::InvalidDateFormat(format.to_string()));
+    }
+    Ok(())
+}
+
+/// A task field that can be edited in Insert mode
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EditField {
+    Title,
+    Description,
+    Due,
+    DeferUntil,
+    /// Comma-separated tags
+    Tags,
+    Notes,
+}
+
+impl EditField {
+    /// Every editable field, in the default Tab order
+    pub const ALL: [EditField; 6] = [
+        EditField::Title,
+        EditField::Description,
+        EditField::Due,
+        EditField::DeferUntil,
+        EditField::Tags,
+        EditField::Notes,
+    ];
+
+    /// Name shown in the status bar while the field is being edited
+    pub fn label(self) -> &'static str {
+        match self {
+            EditField::Title => "Title",
+            EditField::Description => "Description",
+            EditField::Due => "Due Date",
+            EditField::DeferUntil => "Defer Until",
+            EditField::Tags => "Tags",
+            EditField::Notes => "Notes",
+        }
+    }
+}
+
+/// Which tag group a task with several tags is listed under
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum TagGrouping {
+    /// Under every one of its tags
+    #[default]
+    Each,
+    /// Only under its first tag
+    First,
+}
+
+/// How the created column shows when a task was created
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CreatedFormat {
+    /// Relative for the last day ("2h ago"), a date after that
+    #[default]
+    Hybrid,
+    /// Always relative ("2h ago", "3d ago")
+    Relative,
+    /// Always a date ("2024-01-15")
+    Absolute,
+}
+
+/// Background maintenance settings
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct MaintenanceConfig {
+    /// Minutes between automatic checkpoints during a session (0 disables them)
+    pub checkpoint_min: u64,
+    /// Rewrite the database on launch to reclaim disk space
+    pub compact_on_start: bool,
+    /// Flag open tasks untouched for this many days for the `:review` view (0 disables it)
+    pub stale_review_days: u64,
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            checkpoint_min: 10,
+            compact_on_start: false,
+            stale_review_days: 0,
+        }
+    }
+}
+
+/// Where the cursor goes after a task is marked complete
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AfterComplete {
+    /// Keep the cursor on the completed task
+    Stay,
+    /// Move the cursor to the next incomplete task below
+    Next,
+    /// Leave the cursor on the same row
+    #[default]
+    None,
+}
+
+/// Where the cursor goes when a view or filter change hides the task under it
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FilterCursor {
+    /// Stay on the same task, or move to the nearest task still shown
+    #[default]
+    Nearest,
+    /// Always jump back to the first task
+    First,
+}
+
+/// Interaction behavior settings
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct BehaviorConfig {
+    /// Cursor movement after completing a task
+    pub after_complete: AfterComplete,
+    /// Select a field's text when editing starts so typing replaces it
+    pub select_on_edit: bool,
+    /// Cursor movement after the view or filter changes
+    pub filter_cursor: FilterCursor,
+    /// Flush storage and write a backup when quitting, rather than exiting instantly
+    pub quit_flush: bool,
+    /// Completing a task also completes its open subtasks, instead of refusing
+    pub complete_cascades_subtasks: bool,
+    /// What keys other than y/n/Esc do while a y/n prompt is shown
+    pub confirm_other_keys: ConfirmOtherKeys,
+}
+
+impl Default for BehaviorConfig {
+    fn default() -> Self {
+        Self {
+            after_complete: AfterComplete::default(),
+            select_on_edit: false,
+            filter_cursor: FilterCursor::default(),
+            quit_flush: true,
+            complete_cascades_subtasks: false,
+            confirm_other_keys: ConfirmOtherKeys::default(),
+        }
+    }
+}
+
+/// How a y/n prompt treats keys that don't answer it
+///
+/// Either way the key itself is never acted on, so motions can't move the
+/// cursor and change what the prompt applies to.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfirmOtherKeys {
+    /// Dismiss the prompt
+    #[default]
+    Cancel,
+    /// Keep waiting for y, n or Esc
+    Ignore,
+}
+
+/// What Enter does while editing a task in Insert mode
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EnterBehavior {
+    /// Save the task and leave Insert mode
+    #[default]
+    Save,
+    /// Move to the next field; Enter on the last field saves
+    NextField,
+    /// Like `next_field` for tasks created with `o`/`O`, until Enter on an empty field saves
+    NextFieldWhenNew,
+}
+
+/// Insert mode editing settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct EditingConfig {
+    /// What Enter does while editing a task
+    pub enter_behavior: EnterBehavior,
+}
+
+/// How tasks captured with `wimm add` are marked for later processing
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct CaptureConfig {
+    /// Tag added to every captured task, e.g. `@inbox` (none when unset)
+    pub default_tag: Option<String>,
+    /// Text put in front of every captured task's title
+    pub title_prefix: String,
+}
+
+/// Where tasks, the scratchpad and time entries are kept
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageBackend {
+    /// The Sled embedded database, `tasks.db` in the data directory
+    #[default]
+    Sled,
+    /// One JSON file that can be edited by hand, see [`crate::storage::JsonFileStorage`]
+    Json,
+}
+
+impl StorageBackend {
+    /// Name of the backend as written in the config file
+    pub fn name(self) -> &'static str {
+        match self {
+            StorageBackend::Sled => "sled",
+            StorageBackend::Json => "json",
+        }
+    }
+
+    /// Look up a backend by the name used in the config file
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "sled" => Some(StorageBackend::Sled),
+            "json" => Some(StorageBackend::Json),
+            _ => None,
+        }
+    }
+}
+
+/// How task data is kept on disk
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct StorageConfig {
+    /// Create the database, its backups and this file readable by you only
+    pub private_files: bool,
+    /// Which backend keeps the tasks
+    pub backend: StorageBackend,
+    /// File used by the json backend (`tasks.json` in the data directory when unset)
+    pub json_path: Option<PathBuf>,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            private_files: true,
+            backend: StorageBackend::Sled,
+            json_path: None,
+        }
+    }
+}
+
+/// Reminders shown in the status bar as tasks come due
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct NotificationsConfig {
+    /// Remind about open tasks as their due dates approach
+    pub enabled: bool,
+    /// Minutes before the due date to remind, unless the task sets its own lead
+    pub lead_min: u64,
+}
+
+/// Per-view changes to the task list columns; unset columns keep the default
+///
+/// The defaults come from `[ui]`: the created and days columns follow
+/// `show_created_column` and `show_days_column`, the tags and project columns
+/// appear once some task has a tag or project, and the rest are always shown.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct ColumnOverrides {
+    pub description: Option<bool>,
+    pub priority: Option<bool>,
+    pub created: Option<bool>,
+    pub due: Option<bool>,
+    pub days: Option<bool>,
+    pub defer_until: Option<bool>,
+    pub tags: Option<bool>,
+    pub project: Option<bool>,
+}
+
+/// Which optional task list columns are shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnSet {
+    pub description: bool,
+    pub priority: bool,
+    pub created: bool,
+    pub due: bool,
+    pub days: bool,
+    pub defer_until: bool,
+    /// Shown only while some task has a tag
+    pub tags: bool,
+    /// Shown only while some task has a project
+    pub project: bool,
+}
+
 /// Main configuration structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -168,6 +866,34 @@ pub struct Config {
     pub color_schemes: Vec<ColorScheme>,
     /// Available keymaps
     pub keymaps: Vec<Keymap>,
+    /// User interface preferences
+    #[serde(default)]
+    pub ui: UiConfig,
+    /// Background maintenance settings
+    #[serde(default)]
+    pub maintenance: MaintenanceConfig,
+    /// Interaction behavior settings
+    #[serde(default)]
+    pub behavior: BehaviorConfig,
+    /// Insert mode editing settings
+    #[serde(default)]
+    pub editing: EditingConfig,
+    /// Quick-capture settings for `wimm add`
+    #[serde(default)]
+    pub capture: CaptureConfig,
+    /// Due date reminders
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
+    /// On-disk storage settings
+    #[serde(default)]
+    pub storage: StorageConfig,
+    /// Task list column overrides for individual views, e.g. `[columns.someday]`
+    #[serde(default)]
+    pub columns: HashMap<View, ColumnOverrides>,
+    /// Problems found while loading, e.g. an invalid `ui.date_format` that
+    /// fell back to the default; never saved
+    #[serde(skip)]
+    pub warnings: Vec<String>,
 }
 
 impl Default for Config {
@@ -217,10 +943,11 @@ impl Default for Config {
         vi_normal.insert("x".to_string(), "complete_task".to_string());
         vi_normal.insert("j".to_string(), "move_down".to_string());
         vi_normal.insert("k".to_string(), "move_up".to_string());
-        vi_normal.insert("Enter".to_string(), "edit_task".to_string());
+        vi_normal.insert("Enter".to_string(), "open_detail".to_string());
         vi_normal.insert("Esc".to_string(), "escape".to_string());
         vi_normal.insert("gg".to_string(), "move_top".to_string());
         vi_normal.insert("G".to_string(), "move_bottom".to_string());
+        insert_leader_bindings(&mut vi_normal);
 
         let mut vi_insert = HashMap::new();
         vi_insert.insert("Esc".to_string(), "escape".to_string());
@@ -233,6 +960,7 @@ impl Default for Config {
             name: "vi".to_string(),
             normal: vi_normal,
             insert: vi_insert,
+            leader: default_leader(),
         });
 
         Self {
@@ -241,18 +969,40 @@ impl Default for Config {
             time: TimeDefaults::default(),
             color_schemes,
             keymaps,
+            ui: UiConfig::default(),
+            maintenance: MaintenanceConfig::default(),
+            behavior: BehaviorConfig::default(),
+            editing: EditingConfig::default(),
+            capture: CaptureConfig::default(),
+            notifications: NotificationsConfig::default(),
+            storage: StorageConfig::default(),
+            columns: HashMap::new(),
+            warnings: Vec::new(),
         }
     }
 }
 
 impl Config {
+    /// The task list columns for `view`: its `[columns]` override layered over `[ui]`
+    pub fn columns_for(&self, view: View) -> ColumnSet {
+        let overrides = self.columns.get(&view).cloned().unwrap_or_default();
+        ColumnSet {
+            description: overrides.description.unwrap_or(true),
+            priority: overrides.priority.unwrap_or(true),
+            created: overrides.created.unwrap_or(self.ui.show_created_column),
+            due: overrides.due.unwrap_or(true),
+            days: overrides.days.unwrap_or(self.ui.show_days_column),
+            defer_until: overrides.defer_until.unwrap_or(true),
+            tags: overrides.tags.unwrap_or(true),
+            project: overrides.project.unwrap_or(true),
+        }
+    }
+
     /// Load configuration from the standard config file location
     pub fn load() -> Result<Self, ConfigError> {
         let config_path = Self::config_path()?;
         if config_path.exists() {
-            let content = fs::read_to_string(&config_path)?;
-            let config: Config = toml::from_str(&content)?;
-            Ok(config)
+            Self::load_from(&config_path)
         } else {
             // Create default config and save it
             let config = Config::default();
@@ -261,17 +1011,55 @@ impl Config {
         }
     }
 
+    /// Load the configuration file at `config_path`
+    ///
+    /// Keymaps written by older versions are brought up to date with the
+    /// built-in keymaps they were copied from, see [`Keymap::migrate_legacy`].
+    /// An invalid `ui.date_format` falls back to the default rather than
+    /// discarding the rest of the file, and is reported in
+    /// [`Config::warnings`] for the caller to show.
+    pub fn load_from(config_path: &Path) -> Result<Self, ConfigError> {
+        let content = fs::read_to_string(config_path)?;
+        let mut config: Config = toml::from_str(&content)?;
+        if let Err(e) = validate_date_format(&config.ui.date_format) {
+            let fallback = UiConfig::default().date_format;
+            config.warnings.push(format!("{e}. Using {fallback}."));
+            config.ui.date_format = fallback;
+        }
+        config.migrate_keymaps();
+        Ok(config)
+    }
+
+    /// Migrate the active keymap and the saved keymap list, see [`Keymap::migrate_legacy`]
+    fn migrate_keymaps(&mut self) {
+        let builtins = Config::default().keymaps;
+        for keymap in std::iter::once(&mut self.keymap).chain(self.keymaps.iter_mut()) {
+            if let Some(builtin) = builtins.iter().find(|b| b.name == keymap.name) {
+                keymap.migrate_legacy(builtin);
+            }
+        }
+    }
+
     /// Save configuration to the standard config file location
     pub fn save(&self) -> Result<(), ConfigError> {
-        let config_path = Self::config_path()?;
+        self.save_to(&Self::config_path()?)
+    }
 
+    /// Save configuration to `config_path`, creating its directory if needed
+    ///
+    /// With `storage.private_files`, the file and the directory holding it
+    /// are made readable by their owner only; the file is created that way.
+    pub fn save_to(&self, config_path: &Path) -> Result<(), ConfigError> {
         // Ensure the config directory exists
         if let Some(parent) = config_path.parent() {
             fs::create_dir_all(parent)?;
+            if self.storage.private_files {
+                restrict_to_owner(parent)?;
+            }
         }
 
         let content = toml::to_string_pretty(self)?;
-        fs::write(config_path, content)?;
+        write_file_atomically(config_path, content.as_bytes(), self.storage.private_files)?;
         Ok(())
     }
 
@@ -334,6 +1122,49 @@ impl Config {
 mod tests {
     use super::*;
 
+    #[cfg(unix)]
+    #[test]
+    fn test_save_to_makes_config_private() {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let dir = temp_dir.path().join("wimm");
+        let path = dir.join("config.toml");
+
+        Config::default().save_to(&path).unwrap();
+        assert_eq!(mode(&dir), 0o700);
+        assert_eq!(mode(&path), 0o600);
+
+        // Opting out leaves permissions to the umask
+        let mut config = Config::default();
+        config.storage.private_files = false;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        config.save_to(&path).unwrap();
+        assert_eq!(mode(&path), 0o644);
+        let loaded: Config = toml::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert!(!loaded.storage.private_files);
+    }
+
+    #[test]
+    fn test_storage_backend_from_config() {
+        assert_eq!(Config::default().storage.backend, StorageBackend::Sled);
+        let storage: StorageConfig =
+            toml::from_str("backend = \"json\"\njson_path = \"/home/me/notes/tasks.json\"\n")
+                .unwrap();
+        assert_eq!(storage.backend, StorageBackend::Json);
+        assert_eq!(
+            storage.json_path,
+            Some(PathBuf::from("/home/me/notes/tasks.json"))
+        );
+        assert!(storage.private_files);
+        assert_eq!(
+            StorageBackend::from_name("json"),
+            Some(StorageBackend::Json)
+        );
+        assert_eq!(StorageBackend::from_name("csv"), None);
+        assert!(toml::from_str::<StorageConfig>("backend = \"csv\"\n").is_err());
+    }
+
     #[test]
     fn test_color_scheme_default() {
         let colors = ColorScheme::default();
@@ -432,5 +1263,199 @@ mod tests {
         let deserialized: Config = toml::from_str(&serialized).unwrap();
         assert_eq!(deserialized.colors.name, config.colors.name);
         assert_eq!(deserialized.keymap.name, config.keymap.name);
+        assert_eq!(deserialized.ui, config.ui);
+    }
+
+    #[test]
+    fn test_config_without_ui_section_uses_defaults() {
+        let mut value = toml::Value::try_from(Config::default()).unwrap();
+        value.as_table_mut().unwrap().remove("ui");
+        let config: Config = toml::from_str(&toml::to_string(&value).unwrap()).unwrap();
+        assert_eq!(config.ui, UiConfig::default());
+    }
+
+    #[test]
+    fn test_ui_config_partial_section() {
+        let mut value = toml::Value::try_from(Config::default()).unwrap();
+        value
+            .as_table_mut()
+            .unwrap()
+            .insert("ui".to_string(), toml::Value::Table(Default::default()));
+        value["ui"]
+            .as_table_mut()
+            .unwrap()
+            .insert("defer_glyph".to_string(), "z".into());
+        let config: Config = toml::from_str(&toml::to_string(&value).unwrap()).unwrap();
+        assert_eq!(config.ui.defer_glyph, "z");
+    }
+
+    #[test]
+    fn test_behavior_after_complete_parses_lowercase() {
+        let mut value = toml::Value::try_from(Config::default()).unwrap();
+        value["behavior"]
+            .as_table_mut()
+            .unwrap()
+            .insert("after_complete".to_string(), "next".into());
+        let config: Config = toml::from_str(&toml::to_string(&value).unwrap()).unwrap();
+        assert_eq!(config.behavior.after_complete, AfterComplete::Next);
+        assert_eq!(
+            Config::default().behavior.after_complete,
+            AfterComplete::None
+        );
+    }
+
+    #[test]
+    fn test_default_view_is_validated() {
+        let with_view = |view: &str| {
+            let mut value = toml::Value::try_from(Config::default()).unwrap();
+            value["ui"]
+                .as_table_mut()
+                .unwrap()
+                .insert("default_view".to_string(), view.into());
+            toml::from_str::<Config>(&toml::to_string(&value).unwrap())
+        };
+
+        assert_eq!(with_view("today").unwrap().ui.default_view, View::Today);
+        assert_eq!(Config::default().ui.default_view, View::All);
+        assert!(with_view("tomorrow").is_err());
+    }
+
+    #[test]
+    fn test_keymap_actions_fall_back_to_defaults() {
+        let mut keymap = Keymap::default();
+        assert_eq!(keymap.normal_action("j"), Some(Action::MoveDown));
+        assert_eq!(keymap.normal_action("]d"), Some(Action::NextUrgent));
+        assert_eq!(keymap.insert_action("Shift+Tab"), Some(Action::PrevField));
+
+        keymap.normal.clear();
+        keymap.normal.insert("j".to_string(), "move_up".to_string());
+        keymap
+            .normal
+            .insert("k".to_string(), "no_such_action".to_string());
+        assert_eq!(keymap.normal_action("j"), Some(Action::MoveUp));
+        assert_eq!(keymap.normal_action("k"), None);
+        assert_eq!(keymap.normal_action("G"), Some(Action::MoveBottom));
+        assert_eq!(keymap.normal_action("Z"), None);
+
+        assert!(keymap.starts_sequence(']'));
+        assert!(!keymap.starts_sequence('g'));
+        keymap
+            .normal
+            .insert("gg".to_string(), "move_top".to_string());
+        assert!(keymap.starts_sequence('g'));
+        assert_eq!(
+            Action::from_name("new_task"),
+            Action::from_name("new_task_below")
+        );
+    }
+
+    #[test]
+    fn test_leader_sequences_resolve() {
+        let mut keymap = Keymap::default();
+        assert_eq!(keymap.leader_key(), Some(' '));
+        assert_eq!(keymap.leader_action('s'), Some(Action::CycleSort));
+        assert_eq!(keymap.leader_action('z'), None);
+
+        keymap
+            .normal
+            .insert("<leader>e".to_string(), "edit_task".to_string());
+        // Only single follow-up keys count as leader sequences
+        keymap
+            .normal
+            .insert("<leader>ab".to_string(), "move_top".to_string());
+        let keys: Vec<&str> = keymap.leader_bindings().iter().map(|(k, _)| *k).collect();
+        assert_eq!(keys, vec!["c", "e", "f", "p", "r", "s"]);
+
+        keymap.leader = String::new();
+        assert_eq!(keymap.leader_key(), None);
+        keymap.leader = "ab".to_string();
+        assert_eq!(keymap.leader_key(), None);
+
+        // Keymaps saved before leaders existed get the default leader
+        let old: Keymap =
+            toml::from_str("name = \"old\"\n[normal]\nq = \"quit\"\n[insert]\n").unwrap();
+        assert_eq!(old.leader_key(), Some(' '));
+        assert!(old.leader_bindings().is_empty());
+    }
+
+    #[test]
+    fn test_legacy_config_keeps_built_in_keys() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.toml");
+        fs::write(&path, include_str!("../tests/fixtures/legacy_config.toml")).unwrap();
+
+        let config = Config::load_from(&path).unwrap();
+        let keymap = &config.keymap;
+        assert_eq!(keymap.normal_action("n"), Some(Action::NextMatch));
+        assert_eq!(keymap.normal_action("c"), Some(Action::CycleStatus));
+        assert_eq!(keymap.normal_action("?"), Some(Action::ToggleAbout));
+        assert_eq!(keymap.normal_action("d"), None);
+        assert_eq!(keymap.normal_action("Enter"), Some(Action::OpenDetail));
+        assert_eq!(keymap.leader_action('s'), Some(Action::CycleSort));
+        assert_eq!(config.keymaps, Config::default().keymaps);
+
+        // Keys the user rebound are kept; untouched legacy ones still move on
+        let mut custom = Config::default().keymap;
+        custom.normal = bindings(&[("n", "new_task"), ("c", "toggle_mark"), ("z", "undo")]);
+        custom.migrate_legacy(&Keymap::default());
+        assert_eq!(custom.normal_action("n"), Some(Action::NextMatch));
+        assert_eq!(custom.normal_action("c"), Some(Action::ToggleMark));
+        assert_eq!(custom.normal_action("z"), Some(Action::Undo));
+    }
+
+    #[test]
+    fn test_date_format_is_validated() {
+        for format in ["%Y-%m-%d", "%d/%m/%Y", "%b %d", "%a %e %b"] {
+            assert!(validate_date_format(format).is_ok(), "{format}");
+        }
+        for format in ["", "%Q", "%Y-%"] {
+            assert!(matches!(
+                validate_date_format(format),
+                Err(ConfigError::InvalidDateFormat(_))
+            ));
+        }
+        assert!(validate_date_format(&Config::default().ui.date_format).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_date_format_keeps_the_rest_of_the_config() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.toml");
+        let mut config = Config::default();
+        config.ui.date_format = "%Q".to_string();
+        config.time.due_hour = 12;
+        config.save_to(&path).unwrap();
+
+        let loaded = Config::load_from(&path).unwrap();
+        assert_eq!(loaded.ui.date_format, "%Y-%m-%d");
+        assert_eq!(loaded.time.due_hour, 12);
+        assert_eq!(loaded.warnings.len(), 1);
+        assert!(loaded.warnings[0].contains("%Q"));
+
+        // Warnings are not written back
+        loaded.save_to(&path).unwrap();
+        assert!(Config::load_from(&path).unwrap().warnings.is_empty());
+    }
+
+    #[test]
+    fn test_view_columns_override_ui_defaults() {
+        let mut config: Config = toml::from_str(&format!(
+            "{}\n[columns.someday]\ndue = false\ndefer_until = false\ncreated = true\n",
+            toml::to_string(&Config::default()).unwrap()
+        ))
+        .unwrap();
+        config.ui.show_created_column = false;
+        config.ui.show_days_column = true;
+
+        let all = config.columns_for(View::All);
+        assert!(all.due && all.defer_until && all.days && !all.created);
+
+        let someday = config.columns_for(View::Someday);
+        assert!(!someday.due && !someday.defer_until);
+        // Unset keys fall back to [ui], set ones win over it
+        assert!(someday.days && someday.created && someday.description);
+
+        let round_trip: Config = toml::from_str(&toml::to_string(&config).unwrap()).unwrap();
+        assert_eq!(round_trip.columns, config.columns);
     }
 }
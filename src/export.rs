@@ -0,0 +1,39 @@
+//! Exporting tasks for backups and other tools
+//!
+//! Backs the `wimm export` command. A JSON export is the full task list as
+//! stored, so `wimm import --format json` can read it back losslessly.
+
+use crate::types::Task;
+
+/// File format to export tasks in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    /// Pretty-printed JSON array of tasks
+    Json,
+}
+
+/// Serialize `tasks` in the given format
+pub fn export_tasks(tasks: &[Task], format: ExportFormat) -> Result<String, serde_json::Error> {
+    match format {
+        ExportFormat::Json => serde_json::to_string_pretty(tasks),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TaskSource;
+    use std::time::SystemTime;
+
+    #[test]
+    fn test_json_export_round_trips() {
+        let mut task = Task::new("Back up photos", TaskSource::Tui, SystemTime::now());
+        task.tags = vec!["home".to_string()];
+        task.due = Some(SystemTime::now());
+
+        let json = export_tasks(&[task.clone()], ExportFormat::Json).unwrap();
+        assert!(json.starts_with("[\n"));
+        let tasks: Vec<Task> = serde_json::from_str(&json).unwrap();
+        assert_eq!(tasks, vec![task]);
+    }
+}
@@ -0,0 +1,271 @@
+//! Query-based filtering of the task list
+//!
+//! A [`Query`] is parsed from the text typed after `:filter` and narrows the
+//! visible tasks on top of the active view. Queries are made of
+//! whitespace-separated tokens:
+//!
+//! - `due:none` - only tasks without a due date
+//! - `due:any` - only tasks with a due date
+//! - `energy:low` / `energy:medium` / `energy:high` - only tasks rated at that energy
+//! - `project:<name>` - only tasks in that project (case-insensitive)
+//! - `tag:<name>` - only tasks with that tag (case-insensitive)
+//! - any other word - case-insensitive match against title or description
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::types::{Energy, Task};
+
+/// Restriction on whether a task has a due date
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DueFilter {
+    /// Tasks without a due date (`due:none`)
+    Unscheduled,
+    /// Tasks with a due date (`due:any`)
+    Scheduled,
+}
+
+/// A parsed task list query; the default query matches every task
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Query {
+    /// Optional due date restriction from a `due:` token
+    pub due: Option<DueFilter>,
+    /// Optional energy restriction from an `energy:` token
+    pub energy: Option<Energy>,
+    /// Optional project restriction from a `project:` token
+    pub project: Option<String>,
+    /// Optional tag restriction from a `tag:` token
+    pub tag: Option<String>,
+    /// Free-text words that must all appear in the title or description
+    pub terms: Vec<String>,
+}
+
+impl Query {
+    /// Parse a query from user input
+    ///
+    /// # Errors
+    /// Returns a message describing the problem if a `due:` or `energy:`
+    /// token has an unknown value.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut query = Query::default();
+        for token in input.split_whitespace() {
+            if let Some(value) = token.strip_prefix("due:") {
+                query.due = Some(match value {
+                    "none" => DueFilter::Unscheduled,
+                    "any" => DueFilter::Scheduled,
+                    _ => {
+                        return Err(format!(
+                            "Unknown due filter '{value}' (use due:none or due:any)"
+                        ))
+                    }
+                });
+            } else if let Some(value) = token.strip_prefix("energy:") {
+                query.energy = Some(Energy::from_name(value).ok_or_else(|| {
+                    format!("Unknown energy '{value}' (use low, medium or high)")
+                })?);
+            } else if let Some(value) = token.strip_prefix("project:") {
+                if value.is_empty() {
+                    return Err("Expected a project name after 'project:'".to_string());
+                }
+                query.project = Some(value.to_string());
+            } else if let Some(value) = token.strip_prefix("tag:") {
+                if value.is_empty() {
+                    return Err("Expected a tag after 'tag:'".to_string());
+                }
+                query.tag = Some(value.to_string());
+            } else {
+                query.terms.push(token.to_lowercase());
+            }
+        }
+        Ok(query)
+    }
+
+    /// Whether this query places no restriction on the task list
+    pub fn is_empty(&self) -> bool {
+        self.due.is_none()
+            && self.energy.is_none()
+            && self.project.is_none()
+            && self.tag.is_none()
+            && self.terms.is_empty()
+    }
+
+    /// Whether a task satisfies every token of the query
+    pub fn matches(&self, task: &Task) -> bool {
+        let due_ok = match self.due {
+            Some(DueFilter::Unscheduled) => !task.is_scheduled(),
+            Some(DueFilter::Scheduled) => task.is_scheduled(),
+            None => true,
+        };
+        let energy_ok = self.energy.is_none() || task.energy == self.energy;
+        let project_ok = self.project.as_ref().map_or(true, |project| {
+            task.project
+                .as_ref()
+                .is_some_and(|p| p.eq_ignore_ascii_case(project))
+        });
+        let tag_ok = self.tag.as_ref().map_or(true, |tag| {
+            task.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+        });
+        due_ok
+            && energy_ok
+            && project_ok
+            && tag_ok
+            && self.terms.iter().all(|term| text_matches(task, term))
+    }
+}
+
+/// Case-insensitive substring match of a lowercase `term` against title or description
+pub fn text_matches(task: &Task, term: &str) -> bool {
+    task.title.to_lowercase().contains(term) || task.description.to_lowercase().contains(term)
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut tokens: Vec<&str> = Vec::new();
+        match self.due {
+            Some(DueFilter::Unscheduled) => tokens.push("due:none"),
+            Some(DueFilter::Scheduled) => tokens.push("due:any"),
+            None => {}
+        }
+        let energy = self.energy.map(|e| format!("energy:{}", e.name()));
+        tokens.extend(energy.as_deref());
+        let project = self.project.as_ref().map(|p| format!("project:{p}"));
+        tokens.extend(project.as_deref());
+        let tag = self.tag.as_ref().map(|t| format!("tag:{t}"));
+        tokens.extend(tag.as_deref());
+        tokens.extend(self.terms.iter().map(String::as_str));
+        write!(f, "{}", tokens.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Priority, TaskSource};
+    use std::time::SystemTime;
+
+    fn create_test_task(id: &str, title: &str) -> Task {
+        Task {
+            id: id.to_string(),
+            title: title.to_string(),
+            description: format!("Description for {title}"),
+            completed: false,
+            created_at: SystemTime::now(),
+            due: None,
+            defer_until: None,
+            someday: false,
+            tags: Vec::new(),
+            energy: None,
+            source: TaskSource::Tui,
+            url: None,
+            notes: String::new(),
+            project: None,
+            waiting_since: None,
+            in_progress: false,
+            subtasks: Vec::new(),
+            defer_until_task: None,
+            recurrence: None,
+            modified_at: SystemTime::UNIX_EPOCH,
+            reminder_lead: None,
+            next_occurrence_id: None,
+            completed_at: None,
+            priority: Priority::Medium,
+        }
+    }
+
+    #[test]
+    fn test_parse_due_tokens() {
+        assert_eq!(
+            Query::parse("due:none").unwrap().due,
+            Some(DueFilter::Unscheduled)
+        );
+        assert_eq!(
+            Query::parse("due:any").unwrap().due,
+            Some(DueFilter::Scheduled)
+        );
+        assert!(Query::parse("due:soon").is_err());
+    }
+
+    #[test]
+    fn test_parse_empty_query() {
+        let query = Query::parse("   ").unwrap();
+        assert!(query.is_empty());
+        assert!(query.matches(&create_test_task("1", "Anything")));
+    }
+
+    #[test]
+    fn test_due_filters_split_scheduled_and_unscheduled() {
+        let unscheduled = create_test_task("1", "Someday");
+        let mut scheduled = create_test_task("2", "Dentist");
+        scheduled.due = Some(SystemTime::now());
+
+        let none = Query::parse("due:none").unwrap();
+        assert!(none.matches(&unscheduled));
+        assert!(!none.matches(&scheduled));
+
+        let any = Query::parse("due:any").unwrap();
+        assert!(!any.matches(&unscheduled));
+        assert!(any.matches(&scheduled));
+    }
+
+    #[test]
+    fn test_terms_match_title_or_description_case_insensitively() {
+        let task = create_test_task("1", "Call Mom");
+        assert!(Query::parse("mom").unwrap().matches(&task));
+        assert!(Query::parse("DESCRIPTION call").unwrap().matches(&task));
+        assert!(!Query::parse("dad").unwrap().matches(&task));
+    }
+
+    #[test]
+    fn test_energy_filter_shows_only_matching_tasks() {
+        let mut low = create_test_task("1", "Sort mail");
+        low.energy = Some(Energy::Low);
+        let mut high = create_test_task("2", "Write proposal");
+        high.energy = Some(Energy::High);
+        let unrated = create_test_task("3", "Unrated");
+
+        let query = Query::parse("energy:low").unwrap();
+        assert!(query.matches(&low));
+        assert!(!query.matches(&high));
+        assert!(!query.matches(&unrated));
+        assert!(Query::parse("energy:tired").is_err());
+    }
+
+    #[test]
+    fn test_project_filter_matches_case_insensitively() {
+        let mut home = create_test_task("1", "Paint fence");
+        home.project = Some("Home".to_string());
+        let mut work = create_test_task("2", "Quarterly report");
+        work.project = Some("work".to_string());
+        let loose = create_test_task("3", "No project");
+
+        let query = Query::parse("project:home").unwrap();
+        assert!(query.matches(&home));
+        assert!(!query.matches(&work));
+        assert!(!query.matches(&loose));
+        assert!(Query::parse("project:").is_err());
+    }
+
+    #[test]
+    fn test_tag_filter_matches_any_tag_case_insensitively() {
+        let mut work = create_test_task("1", "Quarterly report");
+        work.tags = vec!["urgent".to_string(), "Work".to_string()];
+        let mut home = create_test_task("2", "Paint fence");
+        home.tags = vec!["home".to_string()];
+
+        let query = Query::parse("tag:work").unwrap();
+        assert!(query.matches(&work));
+        assert!(!query.matches(&home));
+        assert!(!query.matches(&create_test_task("3", "Untagged")));
+        assert!(Query::parse("tag:").is_err());
+    }
+
+    #[test]
+    fn test_display_round_trips() {
+        let query = Query::parse("report tag:work project:home energy:high due:any").unwrap();
+        assert_eq!(
+            query.to_string(),
+            "due:any energy:high project:home tag:work report"
+        );
+        assert_eq!(Query::parse(&query.to_string()).unwrap(), query);
+    }
+}
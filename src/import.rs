@@ -0,0 +1,368 @@
+//! Importing tasks from other tools
+//!
+//! Issue trackers are read from saved JSON exports, so importing works
+//! offline: the GitHub REST API (or `gh issue list --json
+//! title,body,labels,url,state`) and the GitLab issues API both produce
+//! files this module understands. Only open issues become tasks.
+//!
+//! Backups written by `wimm export --format json` are read back as they are.
+
+use serde::Deserialize;
+use std::{collections::HashSet, time::SystemTime};
+use thiserror::Error;
+
+use crate::{
+    storage::{Db, DbError},
+    types::{Task, TaskSource},
+};
+
+/// Issue tracker an export comes from, or `Json` for a wimm export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ImportFormat {
+    Github,
+    Gitlab,
+    Json,
+}
+
+/// Errors that can occur while importing tasks
+#[derive(Debug, Error)]
+pub enum ImportError {
+    /// The export is not the JSON array of issues (or tasks) the format expects
+    #[error("Invalid export: {0}")]
+    InvalidJson(#[from] serde_json::Error),
+}
+
+/// A GitHub issue, as returned by the REST API or `gh issue list --json`
+#[derive(Debug, Deserialize)]
+struct GithubIssue {
+    title: String,
+    #[serde(default)]
+    body: Option<String>,
+    #[serde(default)]
+    labels: Vec<GithubLabel>,
+    /// `html_url` in the REST API, `url` in the gh CLI output
+    #[serde(alias = "url")]
+    html_url: String,
+    #[serde(default = "default_open")]
+    state: String,
+    /// Present when the "issue" is really a pull request
+    #[serde(default)]
+    pull_request: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubLabel {
+    name: String,
+}
+
+/// A GitLab issue, as returned by the issues API
+#[derive(Debug, Deserialize)]
+struct GitlabIssue {
+    title: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    labels: Vec<String>,
+    web_url: String,
+    #[serde(default = "default_opened")]
+    state: String,
+}
+
+fn default_open() -> String {
+    "open".to_string()
+}
+
+/// GitLab's name for the open state
+fn default_opened() -> String {
+    "opened".to_string()
+}
+
+/// Read the tasks of a `wimm export --format json` backup, unchanged
+pub fn tasks_from_export(json: &str) -> Result<Vec<Task>, ImportError> {
+    Ok(serde_json::from_str(json)?)
+}
+
+/// Save the tasks whose IDs aren't in the store yet, returning how many were saved
+///
+/// Tasks already in the store are left as they are, so restoring a backup
+/// twice, or into a list that has moved on since, adds only what is missing.
+pub fn save_missing_tasks<D: Db>(store: &mut D, tasks: Vec<Task>) -> Result<usize, DbError> {
+    let mut known_ids: HashSet<String> = store
+        .load_tasks()?
+        .into_iter()
+        .map(|task| task.id)
+        .collect();
+    let mut saved = 0;
+    for task in tasks {
+        if known_ids.insert(task.id.clone()) {
+            store.save_task(&task)?;
+            saved += 1;
+        }
+    }
+    Ok(saved)
+}
+
+/// Turn a JSON array of issues into new tasks, skipping closed issues
+///
+/// The title is kept, the body becomes the description, labels become tags
+/// and the issue's web address is stored as the task URL. Tasks are marked
+/// as imported and created at `now`. A `Json` export holds tasks rather than
+/// issues; read it with [`tasks_from_export`] instead.
+pub fn issues_to_tasks(
+    format: ImportFormat,
+    json: &str,
+    now: SystemTime,
+) -> Result<Vec<Task>, ImportError> {
+    let tasks = match format {
+        ImportFormat::Github => serde_json::from_str::<Vec<GithubIssue>>(json)?
+            .into_iter()
+            .filter(|issue| {
+                issue.state.eq_ignore_ascii_case("open") && issue.pull_request.is_none()
+            })
+            .map(|issue| {
+                issue_task(
+                    &issue.title,
+                    issue.body,
+                    issue.labels.into_iter().map(|label| label.name).collect(),
+                    issue.html_url,
+                    now,
+                )
+            })
+            .collect(),
+        ImportFormat::Gitlab => serde_json::from_str::<Vec<GitlabIssue>>(json)?
+            .into_iter()
+            .filter(|issue| issue.state == "opened")
+            .map(|issue| {
+                issue_task(
+                    &issue.title,
+                    issue.description,
+                    issue.labels,
+                    issue.web_url,
+                    now,
+                )
+            })
+            .collect(),
+        ImportFormat::Json => tasks_from_export(json)?,
+    };
+    Ok(tasks)
+}
+
+/// Save imported tasks that aren't in the store yet, returning how many were saved
+///
+/// A task whose URL matches an existing task's is always skipped. With
+/// `dedup_by_content`, a task is also skipped when an existing task (or one
+/// saved earlier in this import) has the same title, description and due
+/// date, so re-importing from a source without stable URLs adds nothing.
+pub fn save_new_tasks<D: Db>(
+    store: &mut D,
+    tasks: Vec<Task>,
+    dedup_by_content: bool,
+) -> Result<usize, DbError> {
+    let existing = store.load_tasks()?;
+    let mut known_urls: HashSet<String> = existing
+        .iter()
+        .filter_map(|task| task.url.clone())
+        .collect();
+    let mut known_content: HashSet<u64> = if dedup_by_content {
+        existing.iter().map(Task::content_hash).collect()
+    } else {
+        HashSet::new()
+    };
+
+    let mut saved = 0;
+    for task in tasks {
+        if task
+            .url
+            .as_ref()
+            .is_some_and(|url| known_urls.contains(url))
+        {
+            continue;
+        }
+        if dedup_by_content && !known_content.insert(task.content_hash()) {
+            continue;
+        }
+        store.save_task(&task)?;
+        known_urls.extend(task.url);
+        saved += 1;
+    }
+    Ok(saved)
+}
+
+fn issue_task(
+    title: &str,
+    body: Option<String>,
+    labels: Vec<String>,
+    url: String,
+    now: SystemTime,
+) -> Task {
+    let mut task = Task::new(title, TaskSource::Import, now);
+    task.description = body.unwrap_or_default();
+    task.tags = labels;
+    task.url = Some(url);
+    task
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+    use std::collections::HashMap;
+
+    const GITHUB_ISSUES: &str = r#"[
+        {
+            "title": "Crash on empty config",
+            "body": "Steps to reproduce:\n1. Delete config",
+            "labels": [{"name": "bug"}, {"name": "good first issue"}],
+            "html_url": "https://github.com/wimm-io/wimm/issues/12",
+            "state": "open"
+        },
+        {
+            "title": "Fixed already",
+            "body": null,
+            "labels": [],
+            "html_url": "https://github.com/wimm-io/wimm/issues/3",
+            "state": "closed"
+        },
+        {
+            "title": "Add a feature",
+            "labels": [],
+            "html_url": "https://github.com/wimm-io/wimm/pull/14",
+            "state": "open",
+            "pull_request": {"url": "https://api.github.com/repos/wimm-io/wimm/pulls/14"}
+        }
+    ]"#;
+
+    #[test]
+    fn test_github_issue_maps_to_task() {
+        let now = SystemTime::now();
+        let tasks = issues_to_tasks(ImportFormat::Github, GITHUB_ISSUES, now).unwrap();
+
+        assert_eq!(tasks.len(), 1);
+        let task = &tasks[0];
+        assert_eq!(task.title, "Crash on empty config");
+        assert_eq!(task.description, "Steps to reproduce:\n1. Delete config");
+        assert_eq!(task.tags, vec!["bug", "good first issue"]);
+        assert_eq!(
+            task.url.as_deref(),
+            Some("https://github.com/wimm-io/wimm/issues/12")
+        );
+        assert_eq!(task.source, TaskSource::Import);
+        assert_eq!(task.created_at, now);
+        assert!(!task.completed);
+    }
+
+    #[test]
+    fn test_github_cli_export() {
+        let json = r#"[{"title": "From gh", "body": "", "labels": [],
+            "url": "https://github.com/o/r/issues/1", "state": "OPEN"}]"#;
+        let tasks = issues_to_tasks(ImportFormat::Github, json, SystemTime::now()).unwrap();
+
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(
+            tasks[0].url.as_deref(),
+            Some("https://github.com/o/r/issues/1")
+        );
+    }
+
+    #[test]
+    fn test_gitlab_issue_maps_to_task() {
+        let json = r#"[
+            {"title": "Open one", "description": "Details", "labels": ["backend"],
+             "web_url": "https://gitlab.com/o/r/-/issues/7", "state": "opened"},
+            {"title": "Done", "description": null, "labels": [],
+             "web_url": "https://gitlab.com/o/r/-/issues/8", "state": "closed"}
+        ]"#;
+        let tasks = issues_to_tasks(ImportFormat::Gitlab, json, SystemTime::now()).unwrap();
+
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].title, "Open one");
+        assert_eq!(tasks[0].description, "Details");
+        assert_eq!(tasks[0].tags, vec!["backend"]);
+        assert_eq!(
+            tasks[0].url.as_deref(),
+            Some("https://gitlab.com/o/r/-/issues/7")
+        );
+    }
+
+    #[test]
+    fn test_gitlab_issue_without_state_is_open() {
+        let json = r#"[{"title": "Stateless", "web_url": "https://gitlab.com/o/r/-/issues/3"}]"#;
+        let tasks = issues_to_tasks(ImportFormat::Gitlab, json, SystemTime::now()).unwrap();
+
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].title, "Stateless");
+    }
+
+    /// Import `json` into `store` the way `wimm import` does
+    fn import(store: &mut MemoryStorage, json: &str, dedup_by_content: bool) -> usize {
+        let tasks = issues_to_tasks(ImportFormat::Gitlab, json, SystemTime::now()).unwrap();
+        save_new_tasks(store, tasks, dedup_by_content).unwrap()
+    }
+
+    #[test]
+    fn test_reimport_with_content_dedup_adds_nothing() {
+        // The same issues exported from a mirror: same content, different URLs
+        let export = |host: &str| {
+            format!(
+                r#"[
+                {{"title": "Open one", "description": "Details", "labels": [],
+                 "web_url": "https://{host}/o/r/-/issues/7", "state": "opened"}},
+                {{"title": "Open two", "description": null, "labels": [],
+                 "web_url": "https://{host}/o/r/-/issues/9", "state": "opened"}}
+            ]"#
+            )
+        };
+        let mut store = MemoryStorage::new(HashMap::new());
+
+        assert_eq!(import(&mut store, &export("gitlab.com"), true), 2);
+        assert_eq!(import(&mut store, &export("gitlab.com"), true), 0);
+        assert_eq!(import(&mut store, &export("mirror.example"), true), 0);
+        assert_eq!(store.load_tasks().unwrap().len(), 2);
+
+        // Without the flag only matching URLs are skipped
+        assert_eq!(import(&mut store, &export("mirror.example"), false), 2);
+        assert_eq!(store.load_tasks().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_content_dedup_within_one_import() {
+        let json = r#"[
+            {"title": "Same", "description": "", "labels": [],
+             "web_url": "https://gitlab.com/o/r/-/issues/1", "state": "opened"},
+            {"title": "Same", "description": "", "labels": [],
+             "web_url": "https://gitlab.com/o/r/-/issues/2", "state": "opened"}
+        ]"#;
+        let mut store = MemoryStorage::new(HashMap::new());
+        assert_eq!(import(&mut store, json, true), 1);
+    }
+
+    #[test]
+    fn test_backup_restores_only_missing_tasks() {
+        let now = SystemTime::now();
+        let kept = Task::new("Kept", TaskSource::Tui, now);
+        let mut changed = Task::new("Renamed since", TaskSource::Tui, now);
+        let mut store = MemoryStorage::new(HashMap::new());
+        store.save_task(&changed).unwrap();
+
+        changed.title = "Old title".to_string();
+        let json = serde_json::to_string(&[kept.clone(), changed.clone()]).unwrap();
+        let tasks = tasks_from_export(&json).unwrap();
+        assert_eq!(tasks, vec![kept.clone(), changed.clone()]);
+
+        assert_eq!(save_missing_tasks(&mut store, tasks.clone()).unwrap(), 1);
+        assert_eq!(save_missing_tasks(&mut store, tasks).unwrap(), 0);
+        assert_eq!(store.get_task(&kept.id).unwrap(), Some(kept));
+        let existing = store.get_task(&changed.id).unwrap().unwrap();
+        assert_eq!(existing.title, "Renamed since");
+    }
+
+    #[test]
+    fn test_invalid_export_is_an_error() {
+        let result = issues_to_tasks(
+            ImportFormat::Github,
+            r#"{"message": "Not Found"}"#,
+            SystemTime::now(),
+        );
+        assert!(matches!(result, Err(ImportError::InvalidJson(_))));
+    }
+}
@@ -4,16 +4,26 @@
 //! It's organized into the following modules:
 //!
 //! - [`types`] - Core data structures for tasks and application state
+//! - [`filter`] - Query parsing and task list filtering
+//! - [`import`] - Importing tasks from issue tracker exports and backups
+//! - [`export`] - Exporting tasks as JSON for backups
+//! - [`list`] - Printing the task list for scripts and status bars
+//! - [`stats`] - Summary statistics such as the age of open tasks
 //! - [`storage`] - Persistent storage abstraction with multiple backends
 //! - [`ui`] - Terminal user interface components and rendering
 //! - [`input`] - Input handling and event processing
-//! - [`time_tracking`] - Time tracking functionality (placeholder for future features)
+//! - [`time_tracking`] - Per-task timers and the time spent on each task
 //! - [`config`] - Configuration management for colors, keymaps, and defaults
 //! - [`cli`] - Command-line interface and argument parsing
 
 pub mod cli;
 pub mod config;
+pub mod export;
+pub mod filter;
+pub mod import;
 pub mod input;
+pub mod list;
+pub mod stats;
 pub mod storage;
 pub mod time_tracking;
 pub mod types;
@@ -0,0 +1,157 @@
+//! Printing the task list outside the TUI
+//!
+//! Backs the `wimm list` command, so shell scripts and status bars can show
+//! tasks without starting the interface. Tag filtering goes through the same
+//! [`Query`] as `:filter` in the TUI.
+
+use std::time::SystemTime;
+
+use crate::filter::Query;
+use crate::types::Task;
+use crate::ui::format_date;
+
+/// Which tasks `wimm list` prints; the default lists every task
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ListFilter {
+    /// Only completed tasks
+    pub completed: bool,
+    /// Only tasks that are not completed yet
+    pub pending: bool,
+    /// Only tasks due strictly before this time
+    pub due_before: Option<SystemTime>,
+    /// Tag and other restrictions shared with the TUI's `:filter`
+    pub query: Query,
+}
+
+impl ListFilter {
+    /// Whether `task` passes every restriction
+    pub fn matches(&self, task: &Task) -> bool {
+        let status_ok = match (self.completed, self.pending) {
+            (true, false) => task.completed,
+            (false, true) => !task.completed,
+            _ => true,
+        };
+        let due_ok = self
+            .due_before
+            .map_or(true, |limit| task.due.is_some_and(|due| due < limit));
+        status_ok && due_ok && self.query.matches(task)
+    }
+}
+
+/// Render tasks as an aligned table, one line per task below a header
+///
+/// IDs are cut to their first 8 characters, which is enough to tell tasks
+/// apart; due dates are shown relative to `now` followed by the date in
+/// `date_format`.
+pub fn render_table(tasks: &[Task], now: SystemTime, date_format: &str) -> Vec<String> {
+    let header = ["ID", "", "TITLE", "DUE", "TAGS"].map(String::from);
+    let rows: Vec<[String; 5]> = std::iter::once(header)
+        .chain(tasks.iter().map(|task| {
+            [
+                task.id.chars().take(8).collect(),
+                if task.completed { "[x]" } else { "[ ]" }.to_string(),
+                task.title.clone(),
+                format_date(task.due, now, Some(date_format)),
+                task.tags.join(", "),
+            ]
+        }))
+        .collect();
+
+    let mut widths = [0; 5];
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    rows.iter()
+        .map(|row| {
+            let cells: Vec<String> = row
+                .iter()
+                .zip(widths)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect();
+            cells.join("  ").trim_end().to_string()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TaskSource;
+    use std::time::Duration;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    fn task(title: &str, completed: bool, due: Option<SystemTime>, tags: &[&str]) -> Task {
+        let mut task = Task::new(title, TaskSource::Cli, SystemTime::UNIX_EPOCH);
+        task.completed = completed;
+        task.due = due;
+        task.tags = tags.iter().map(|t| t.to_string()).collect();
+        task
+    }
+
+    fn titles(tasks: &[Task], filter: &ListFilter) -> Vec<String> {
+        tasks
+            .iter()
+            .filter(|t| filter.matches(t))
+            .map(|t| t.title.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_list_filter() {
+        let now = SystemTime::UNIX_EPOCH + 100 * DAY;
+        let tasks = [
+            task("Soon", false, Some(now + DAY), &["home"]),
+            task("Later", false, Some(now + 10 * DAY), &[]),
+            task("Whenever", false, None, &["Home"]),
+            task("Done", true, Some(now - DAY), &["home"]),
+        ];
+
+        assert_eq!(titles(&tasks, &ListFilter::default()).len(), 4);
+        let pending = ListFilter {
+            pending: true,
+            ..ListFilter::default()
+        };
+        assert_eq!(titles(&tasks, &pending), ["Soon", "Later", "Whenever"]);
+        let completed = ListFilter {
+            completed: true,
+            ..ListFilter::default()
+        };
+        assert_eq!(titles(&tasks, &completed), ["Done"]);
+
+        let due_soon = ListFilter {
+            due_before: Some(now + 2 * DAY),
+            ..ListFilter::default()
+        };
+        assert_eq!(titles(&tasks, &due_soon), ["Soon", "Done"]);
+
+        let home = ListFilter {
+            pending: true,
+            query: Query::parse("tag:home").unwrap(),
+            ..ListFilter::default()
+        };
+        assert_eq!(titles(&tasks, &home), ["Soon", "Whenever"]);
+    }
+
+    #[test]
+    fn test_render_table_aligns_columns() {
+        let now = SystemTime::UNIX_EPOCH + 100 * DAY;
+        let mut first = task("Call the bank", false, None, &["phone", "money"]);
+        first.id = "0123456789abcdef".to_string();
+        let mut second = task("Pay", true, Some(now + 3 * DAY), &[]);
+        second.id = "fedcba98".to_string();
+
+        // Only the year, so the test doesn't depend on the local time zone
+        let lines = render_table(&[first, second], now, "%Y");
+        assert_eq!(
+            lines,
+            vec![
+                "ID             TITLE          DUE           TAGS",
+                "01234567  [ ]  Call the bank  -             phone, money",
+                "fedcba98  [x]  Pay            in 3d (1970)",
+            ]
+        );
+    }
+}
@@ -8,15 +8,30 @@
 //! - Loading existing tasks from storage
 //! - Starting the terminal UI or handling subcommands
 
-use std::{process, sync::OnceLock};
+use std::{
+    path::{Path, PathBuf},
+    process,
+    sync::OnceLock,
+    time::SystemTime,
+};
 
 use directories::ProjectDirs;
 use wimm::{
     cli::{Cli, Commands, ConfigAction},
-    config::Config,
-    storage::{Db, SledStorage},
-    types::AppState,
-    ui::Ui,
+    config::{Config, StorageBackend},
+    export::{export_tasks, ExportFormat},
+    filter::Query,
+    import::{
+        issues_to_tasks, save_missing_tasks, save_new_tasks, tasks_from_export, ImportFormat,
+    },
+    list::{render_table, ListFilter},
+    stats::{age_histogram, render_histogram},
+    storage::{write_file_atomically, Db, JsonFileStorage, SledStorage, Store},
+    types::{format_offset, AppState},
+    ui::{
+        app::{App, DateField},
+        Ui,
+    },
 };
 
 /// Global storage for project directories, computed once and cached
@@ -39,8 +54,16 @@ fn main() {
     // Parse command-line arguments
     let cli = Cli::parse_args();
 
-    // Handle subcommands first
-    if let Some(command) = &cli.command {
+    if cli.shift_due.is_some() || cli.shift_defer.is_some() {
+        if let Err(e) = handle_shift(&cli) {
+            eprintln!("Error: {e}");
+            process::exit(1);
+        }
+        return;
+    }
+
+    // Handle subcommands first; `wimm run` falls through to the TUI
+    if let Some(command) = cli.command.as_ref().filter(|_| !cli.should_run_tui()) {
         if let Err(e) = handle_command(command, &cli) {
             eprintln!("Error: {e}");
             process::exit(1);
@@ -57,19 +80,26 @@ fn main() {
         }
     };
 
-    // Determine where to store the database file
-    // Falls back to current directory if platform directories aren't available
-    let db_path = project_path().map(|pp| pp.data_dir()).unwrap_or_else(|| {
-        eprintln!("Warning: Could not determine project directory. Using current directory.");
-        std::path::Path::new(".")
-    });
-
-    // Initialize the persistent storage backend (Sled embedded database)
-    // Exit with error if database cannot be opened
-    let store = SledStorage::new(db_path.join("tasks.db")).unwrap_or_else(|e| {
-        eprintln!("Error initializing database at {db_path:?}: {e}");
-        process::exit(1);
-    });
+    let mut store = open_store(&config);
+    // Only the database builds up space worth reclaiming
+    let compacted = match &mut store {
+        Store::Sled(sled) if config.maintenance.compact_on_start => Some(sled.compact()),
+        _ => None,
+    };
+    if let Some(compacted) = compacted {
+        match compacted {
+            Ok((before, after)) if cli.verbose => {
+                println!("Compacted database: {before} bytes -> {after} bytes");
+            }
+            Ok(_) => {}
+            Err(e) => {
+                eprintln!("Warning: Could not compact database: {e}");
+                // Carry on with the database on disk, not whatever compaction left open
+                drop(store);
+                store = open_store(&config);
+            }
+        }
+    }
 
     // Load existing tasks from storage and start the UI
     // Even if loading fails, we still start the UI with an empty state
@@ -79,9 +109,7 @@ fn main() {
             let mut state = AppState::new(store);
             state.tasks = tasks;
             state.config = config;
-            Ui::new(state)
-                .run()
-                .unwrap_or_else(|e| eprintln!("Error: {e}"));
+            run_ui(state);
         }
         Err(e) => {
             // Failed to load tasks, but continue with empty state
@@ -89,9 +117,64 @@ fn main() {
             eprintln!("Error loading tasks from database: {e}");
             let mut state = AppState::new(store);
             state.config = config;
-            Ui::new(state)
-                .run()
-                .unwrap_or_else(|e| eprintln!("Error: {e}"));
+            run_ui(state);
+        }
+    }
+}
+
+/// Run the TUI, exiting with an error status if it fails (e.g. stdout is not a terminal)
+fn run_ui(mut state: AppState<Store>) {
+    state.scratch = state.store.load_scratch().unwrap_or_else(|e| {
+        eprintln!("Error loading scratchpad: {e}");
+        String::new()
+    });
+    if let Err(e) = Ui::new(state).run() {
+        eprintln!("Error: {e}");
+        process::exit(1);
+    }
+}
+
+/// Load the configuration for a subcommand, falling back to the defaults
+///
+/// Warnings from loading, such as an invalid date format, go to stderr.
+fn load_config() -> Config {
+    let config = Config::load().unwrap_or_default();
+    for warning in &config.warnings {
+        eprintln!("Warning: {warning}");
+    }
+    config
+}
+
+/// Open the persistent storage backend chosen with `[storage] backend`
+///
+/// Exits with an error if the database cannot be opened.
+fn open_store(config: &Config) -> Store {
+    // Determine where to store the database file
+    // Falls back to current directory if platform directories aren't available
+    let db_path = project_path()
+        .map(|pp| pp.data_dir().to_path_buf())
+        .unwrap_or_else(|| {
+            eprintln!("Warning: Could not determine project directory. Using current directory.");
+            PathBuf::from(".")
+        });
+
+    let private_files = config.storage.private_files;
+    match config.storage.backend {
+        StorageBackend::Sled => Store::Sled(
+            SledStorage::with_options(db_path.join("tasks.db"), private_files).unwrap_or_else(
+                |e| {
+                    eprintln!("Error initializing database at {db_path:?}: {e}");
+                    process::exit(1);
+                },
+            ),
+        ),
+        StorageBackend::Json => {
+            let path = config
+                .storage
+                .json_path
+                .clone()
+                .unwrap_or_else(|| db_path.join("tasks.json"));
+            Store::JsonFile(JsonFileStorage::with_options(path, private_files))
         }
     }
 }
@@ -104,9 +187,176 @@ fn handle_command(command: &Commands, cli: &Cli) -> Result<(), Box<dyn std::erro
             // This should not happen as we check for this case earlier
             unreachable!("Run command should be handled in main function");
         }
+        Commands::Add {
+            title,
+            due,
+            defer,
+            desc,
+        } => {
+            let config = load_config();
+            let mut state = AppState::new(open_store(&config));
+            state.config = config;
+            let mut app = App::new(state);
+            let due = Cli::parse_date_flag(&app, "due", due.as_deref(), true)?;
+            let defer_until = Cli::parse_date_flag(&app, "defer", defer.as_deref(), false)?;
+
+            let task = Cli::task_from_add(
+                title,
+                desc.as_deref(),
+                due,
+                defer_until,
+                SystemTime::now(),
+                &app.state.config.capture,
+            );
+            app.state.store.save_task(&task)?;
+            println!("{}", task.id);
+            Ok(())
+        }
+        Commands::Import {
+            format,
+            source,
+            file,
+            dedup_by_content,
+        } => match Cli::import_format(*format, file.as_deref(), *dedup_by_content)? {
+            ImportFormat::Json => {
+                let tasks = tasks_from_export(&std::fs::read_to_string(source)?)?;
+                let total = tasks.len();
+                let mut store = open_store(&load_config());
+                let imported = save_missing_tasks(&mut store, tasks)?;
+                println!(
+                    "Imported {imported} of {total} tasks from {source} ({} already present)",
+                    total - imported
+                );
+                Ok(())
+            }
+            format => handle_import(format, source, file.as_deref(), *dedup_by_content),
+        },
+        Commands::List {
+            completed,
+            pending,
+            due_before,
+            tag,
+            json,
+        } => {
+            let config = load_config();
+            let mut state = AppState::new(open_store(&config));
+            state.tasks = state.store.load_tasks()?;
+            state.config = config;
+            let app = App::new(state);
+
+            let filter = ListFilter {
+                completed: *completed,
+                pending: *pending,
+                due_before: Cli::parse_date_flag(&app, "due-before", due_before.as_deref(), true)?,
+                query: Query {
+                    tag: tag.clone(),
+                    ..Query::default()
+                },
+            };
+            let tasks: Vec<_> = app
+                .state
+                .tasks
+                .iter()
+                .filter(|task| filter.matches(task))
+                .cloned()
+                .collect();
+            if *json {
+                println!("{}", export_tasks(&tasks, ExportFormat::Json)?);
+            } else {
+                let date_format = &app.state.config.ui.date_format;
+                for line in render_table(&tasks, SystemTime::now(), date_format) {
+                    println!("{line}");
+                }
+            }
+            Ok(())
+        }
+        Commands::Export { format, output } => {
+            let config = load_config();
+            let tasks = open_store(&config).load_tasks()?;
+            let exported = export_tasks(&tasks, *format)?;
+            match output {
+                Some(path) => {
+                    let contents = exported + "\n";
+                    let private = config.storage.private_files;
+                    write_file_atomically(path, contents.as_bytes(), private)?;
+                    eprintln!("Exported {} tasks to {}", tasks.len(), path.display());
+                }
+                None => println!("{exported}"),
+            }
+            Ok(())
+        }
+        Commands::Stats => {
+            let tasks = open_store(&load_config()).load_tasks()?;
+            let completed = tasks.iter().filter(|t| t.completed).count();
+            println!(
+                "{} tasks, {} open, {completed} completed",
+                tasks.len(),
+                tasks.len() - completed
+            );
+            println!();
+            println!("Age of open tasks:");
+            for line in render_histogram(&age_histogram(&tasks, SystemTime::now())) {
+                println!("  {line}");
+            }
+            Ok(())
+        }
     }
 }
 
+/// Move the due and/or defer dates of every open task by the requested offsets
+fn handle_shift(cli: &Cli) -> Result<(), Box<dyn std::error::Error>> {
+    let config = load_config();
+    let store = open_store(&config);
+    let mut state = AppState::new(store);
+    state.tasks = state.store.load_tasks()?;
+    state.config = config;
+    let mut app = App::new(state);
+    let indices: Vec<usize> = (0..app.state.tasks.len()).collect();
+
+    for (field, offset) in [
+        (DateField::Due, cli.shift_due),
+        (DateField::Defer, cli.shift_defer),
+    ] {
+        let Some(offset) = offset else { continue };
+        let count = app.shift_dates(&indices, field, offset)?;
+        let noun = if count == 1 { "date" } else { "dates" };
+        println!(
+            "Moved {count} {} {noun} by {}",
+            field.name(),
+            format_offset(offset)
+        );
+    }
+    Ok(())
+}
+
+/// Import open issues from a saved export, skipping issues imported before
+fn handle_import(
+    format: ImportFormat,
+    repo: &str,
+    file: Option<&Path>,
+    dedup_by_content: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if repo.split('/').filter(|part| !part.is_empty()).count() < 2 {
+        return Err(format!("Repository must look like owner/repo, got '{repo}'").into());
+    }
+    let Some(file) = file else {
+        return Err(format!(
+            "Fetching issues for {repo} over the network is not supported; \
+             save them to a JSON file (e.g. `gh issue list -R {repo} --json \
+             title,body,labels,url,state > issues.json`) and pass --file"
+        )
+        .into());
+    };
+
+    let json = std::fs::read_to_string(file)?;
+    let tasks = issues_to_tasks(format, &json, SystemTime::now())?;
+
+    let mut store = open_store(&load_config());
+    let imported = save_new_tasks(&mut store, tasks, dedup_by_content)?;
+    println!("Imported {imported} issues from {repo}");
+    Ok(())
+}
+
 /// Handle configuration subcommands
 fn handle_config_command(
     action: &ConfigAction,
@@ -114,7 +364,7 @@ fn handle_config_command(
 ) -> Result<(), Box<dyn std::error::Error>> {
     match action {
         ConfigAction::Show => {
-            let config = Config::load().unwrap_or_default();
+            let config = load_config();
             println!("Current configuration:");
             println!("  Color scheme: {}", config.colors.name);
             println!("  Keymap: {}", config.keymap.name);
@@ -125,9 +375,10 @@ fn handle_config_command(
             } else {
                 println!("  Timezone: (system default)");
             }
+            println!("  Storage backend: {}", config.storage.backend.name());
         }
         ConfigAction::ListColors => {
-            let config = Config::load().unwrap_or_default();
+            let config = load_config();
             println!("Available color schemes:");
             for scheme in config.list_color_schemes() {
                 let marker = if scheme == config.colors.name {
@@ -139,7 +390,7 @@ fn handle_config_command(
             }
         }
         ConfigAction::ListKeymaps => {
-            let config = Config::load().unwrap_or_default();
+            let config = load_config();
             println!("Available keymaps:");
             for keymap in config.list_keymaps() {
                 let marker = if keymap == config.keymap.name {
@@ -158,7 +409,12 @@ fn handle_config_command(
             defer_hour,
             due_hour,
         } => {
-            let mut config = Config::load().unwrap_or_default();
+            // Saving defaults over a file that failed to load would lose the user's settings
+            let mut config = Config::load()
+                .map_err(|e| format!("Could not load configuration, so not changing it: {e}"))?;
+            for warning in &config.warnings {
+                eprintln!("Warning: {warning}");
+            }
             let mut changes_made = false;
 
             // Handle key-value pairs (original format)
@@ -209,8 +465,14 @@ fn handle_config_command(
                         println!("Configuration updated: {k} = {v}");
                         changes_made = true;
                     }
+                    "storage-backend" => {
+                        config.storage.backend = StorageBackend::from_name(v)
+                            .ok_or("Storage backend must be sled or json")?;
+                        println!("Configuration updated: {k} = {v}");
+                        changes_made = true;
+                    }
                     _ => {
-                        return Err(format!("Unknown configuration key: {k}. Available keys: color-scheme, keymap, defer-hour, due-hour, timezone").into());
+                        return Err(format!("Unknown configuration key: {k}. Available keys: color-scheme, keymap, defer-hour, due-hour, timezone, storage-backend").into());
                     }
                 }
             }
@@ -0,0 +1,129 @@
+//! Summary statistics about the task list
+//!
+//! Backs the `wimm stats` command: task counts and how long open tasks have
+//! been sitting around, so neglected work is easy to spot.
+
+use std::time::{Duration, SystemTime};
+
+use crate::types::Task;
+
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Age buckets as (label, tasks younger than this); the last bucket is open-ended
+const AGE_BUCKETS: [(&str, Duration); 4] = [
+    ("<1d", DAY),
+    ("1-7d", Duration::from_secs(7 * 24 * 60 * 60)),
+    ("1-4w", Duration::from_secs(30 * 24 * 60 * 60)),
+    (">1mo", Duration::MAX),
+];
+
+/// Widest bar drawn by [`render_histogram`]
+const MAX_BAR_WIDTH: usize = 40;
+
+/// Count open tasks by how long ago they were created
+///
+/// Every bucket is returned, in order from youngest to oldest, even when
+/// empty. A bucket includes its lower bound, so a task exactly 7 days old
+/// counts as "1-4w". Tasks created in the future count as "<1d".
+pub fn age_histogram(tasks: &[Task], now: SystemTime) -> Vec<(&'static str, usize)> {
+    let mut counts = vec![0; AGE_BUCKETS.len()];
+    for task in tasks.iter().filter(|t| !t.completed) {
+        let age = now.duration_since(task.created_at).unwrap_or_default();
+        let bucket = AGE_BUCKETS
+            .iter()
+            .position(|(_, limit)| age < *limit)
+            .unwrap_or(AGE_BUCKETS.len() - 1);
+        counts[bucket] += 1;
+    }
+    AGE_BUCKETS
+        .iter()
+        .zip(counts)
+        .map(|((label, _), count)| (*label, count))
+        .collect()
+}
+
+/// Render a histogram as one line per bucket: label, bar of `#`, count
+///
+/// Bars are scaled so the largest bucket is at most 40 characters wide; any
+/// non-empty bucket gets at least one `#`.
+pub fn render_histogram(histogram: &[(&str, usize)]) -> Vec<String> {
+    let largest = histogram.iter().map(|(_, n)| *n).max().unwrap_or(0);
+    let label_width = histogram.iter().map(|(l, _)| l.len()).max().unwrap_or(0);
+    histogram
+        .iter()
+        .map(|(label, count)| {
+            let width = if largest <= MAX_BAR_WIDTH {
+                *count
+            } else {
+                (count * MAX_BAR_WIDTH).div_ceil(largest)
+            };
+            format!(
+                "{label:<label_width$} {:<MAX_BAR_WIDTH$} {count}",
+                "#".repeat(width)
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TaskSource;
+
+    fn task_aged(now: SystemTime, age: Duration) -> Task {
+        Task::new("Task", TaskSource::Tui, now - age)
+    }
+
+    fn counts(tasks: &[Task], now: SystemTime) -> Vec<usize> {
+        age_histogram(tasks, now)
+            .into_iter()
+            .map(|(_, n)| n)
+            .collect()
+    }
+
+    #[test]
+    fn test_age_buckets_at_boundaries() {
+        let now = SystemTime::UNIX_EPOCH + 1000 * DAY;
+        let second = Duration::from_secs(1);
+        let cases = [
+            (Duration::ZERO, 0),
+            (DAY - second, 0),
+            (DAY, 1),
+            (7 * DAY - second, 1),
+            (7 * DAY, 2),
+            (30 * DAY - second, 2),
+            (30 * DAY, 3),
+            (400 * DAY, 3),
+        ];
+        for (age, bucket) in cases {
+            let mut expected = vec![0; 4];
+            expected[bucket] = 1;
+            assert_eq!(counts(&[task_aged(now, age)], now), expected, "age {age:?}");
+        }
+    }
+
+    #[test]
+    fn test_age_histogram_skips_completed_and_keeps_empty_buckets() {
+        let now = SystemTime::UNIX_EPOCH + 1000 * DAY;
+        let mut done = task_aged(now, 40 * DAY);
+        done.completed = true;
+        let future = Task::new("Clock skew", TaskSource::Tui, now + DAY);
+
+        let histogram = age_histogram(&[done, future, task_aged(now, 2 * DAY)], now);
+        assert_eq!(
+            histogram,
+            vec![("<1d", 1), ("1-7d", 1), ("1-4w", 0), (">1mo", 0)]
+        );
+    }
+
+    #[test]
+    fn test_render_histogram_scales_bars() {
+        let lines = render_histogram(&[("<1d", 2), ("1-7d", 0), (">1mo", 1)]);
+        assert_eq!(lines[0], format!("<1d  ##{} 2", " ".repeat(38)));
+        assert_eq!(lines[1], format!("1-7d {} 0", " ".repeat(40)));
+
+        let lines = render_histogram(&[("a", 400), ("b", 1)]);
+        assert!(lines[0].starts_with(&format!("a {} ", "#".repeat(40))));
+        assert!(lines[1].starts_with("b # "));
+    }
+}
@@ -7,15 +7,23 @@
 //! The module includes:
 //! - [`Db`] trait defining the storage interface
 //! - [`SledStorage`] for persistent storage using the Sled embedded database
+//! - [`JsonFileStorage`] for tasks kept in one JSON file you can edit by hand
 //! - [`MemoryStorage`] for in-memory storage (testing and development)
 //! - [`DbError`] for comprehensive error handling
 
-use std::{collections::HashMap, path::Path};
+use std::{
+    collections::HashMap,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
+use serde::{Deserialize, Serialize};
 use sled::open;
 use thiserror::Error;
 
-use crate::types::Task;
+use crate::{time_tracking::TimeEntry, types::Task};
 
 /// Comprehensive error types for database operations
 ///
@@ -46,16 +54,33 @@ pub enum DbError {
 pub trait Db {
     /// Load all tasks from storage
     ///
-    /// Returns a vector of all stored tasks. For empty storage,
-    /// returns an empty vector rather than an error.
+    /// Returns a vector of all stored tasks, oldest first (by `created_at`,
+    /// then `id`), so the list comes up in the same order on every launch.
+    /// For empty storage, returns an empty vector rather than an error.
     fn load_tasks(&self) -> Result<Vec<Task>, DbError>;
 
+    /// Load a single task by ID
+    ///
+    /// Returns `Ok(None)` when no task has that ID. The default scans
+    /// [`Db::load_tasks`]; backends with keyed lookups should override it.
+    fn get_task(&self, id: &str) -> Result<Option<Task>, DbError> {
+        Ok(self.load_tasks()?.into_iter().find(|task| task.id == id))
+    }
+
     /// Save or update a task in storage
     ///
     /// If a task with the same ID already exists, it will be overwritten.
     /// The task ID serves as the primary key for storage operations.
     fn save_task(&mut self, task: &Task) -> Result<(), DbError>;
 
+    /// Save or update several tasks at once
+    ///
+    /// Backends that can write atomically should either store every task or
+    /// none of them; the default saves them one at a time.
+    fn save_tasks(&mut self, tasks: &[Task]) -> Result<(), DbError> {
+        tasks.iter().try_for_each(|task| self.save_task(task))
+    }
+
     /// Remove a task from storage by ID
     ///
     /// Returns an error if the task ID doesn't exist in storage.
@@ -65,6 +90,72 @@ pub trait Db {
     ///
     /// This operation is irreversible and will permanently delete all stored tasks.
     fn clear(&mut self) -> Result<(), DbError>;
+
+    /// Flush pending writes and take a lightweight backup, if the backend supports it
+    ///
+    /// Called periodically during long sessions to guard against crashes.
+    /// Backends without durable state can rely on the default no-op.
+    fn checkpoint(&mut self) -> Result<(), DbError> {
+        Ok(())
+    }
+
+    /// Where the backend keeps its data on disk, if anywhere
+    fn location(&self) -> Option<&Path> {
+        None
+    }
+
+    /// Load the free-text scratchpad kept alongside the tasks
+    ///
+    /// Empty until something is saved with [`Db::save_scratch`].
+    fn load_scratch(&self) -> Result<String, DbError> {
+        Ok(String::new())
+    }
+
+    /// Replace the scratchpad text
+    ///
+    /// Backends that can't keep a scratchpad rely on the default, which
+    /// reports an error rather than losing the text silently.
+    fn save_scratch(&mut self, _text: &str) -> Result<(), DbError> {
+        Err(DbError::OperationFailed(
+            "this storage backend has no scratchpad".to_string(),
+        ))
+    }
+
+    /// Save or update a time-tracking entry
+    ///
+    /// Entries are keyed by their `id`, so saving one again replaces it.
+    /// Backends that can't keep time entries report an error by default.
+    fn save_time_entry(&mut self, _entry: &TimeEntry) -> Result<(), DbError> {
+        Err(DbError::OperationFailed(
+            "this storage backend has no time entries".to_string(),
+        ))
+    }
+
+    /// Load every time-tracking entry recorded for a task, oldest first
+    ///
+    /// Returns an empty vector when the task has none.
+    fn load_time_entries(&self, _task_id: &str) -> Result<Vec<TimeEntry>, DbError> {
+        Ok(Vec::new())
+    }
+}
+
+/// Sled tree holding data that isn't a task, such as the scratchpad
+const META_TREE: &str = "meta";
+
+/// Key of the scratchpad text in [`META_TREE`]
+const SCRATCH_KEY: &str = "scratch";
+
+/// Sled tree holding time-tracking entries, see [`time_entry_key`]
+const TIME_ENTRIES_TREE: &str = "time_entries";
+
+/// Sled trees other than the default one holding the tasks
+const SIDE_TREES: [&str; 2] = [META_TREE, TIME_ENTRIES_TREE];
+
+/// Key of a time entry in [`TIME_ENTRIES_TREE`]: the task ID, a NUL, the entry ID
+///
+/// Grouping by task ID lets a task's entries be loaded with one prefix scan.
+fn time_entry_key(task_id: &str, entry_id: &str) -> Vec<u8> {
+    [task_id.as_bytes(), b"\0", entry_id.as_bytes()].concat()
 }
 
 /// Persistent storage implementation using the Sled embedded database
@@ -82,6 +173,10 @@ pub trait Db {
 pub struct SledStorage {
     /// The underlying Sled database instance
     inner: sled::Db,
+    /// Location of the database on disk, used to place backups alongside it
+    path: PathBuf,
+    /// Keep the database and its backups readable by their owner only
+    private_files: bool,
 }
 
 impl SledStorage {
@@ -94,12 +189,342 @@ impl SledStorage {
     /// # Arguments
     /// * `path` - File system path where the database should be stored
     ///
+    /// The database is made private to the current user, see
+    /// [`SledStorage::with_options`].
+    ///
     /// # Errors
     /// Returns `DbError::ConnectionError` if the database cannot be opened,
     /// typically due to permission issues or invalid paths.
     pub fn new<P: AsRef<Path>>(path: P) -> Result<Self, DbError> {
-        let db = open(path).map_err(|e| DbError::ConnectionError(e.to_string()))?;
-        Ok(Self { inner: db })
+        Self::with_options(path, true)
+    }
+
+    /// Open the database at `path`, optionally restricting it to its owner
+    ///
+    /// With `private_files`, the database directory and its files, and the
+    /// backups written next to it, get user-only permissions (`0700`/`0600`
+    /// on Unix) since tasks can contain sensitive details.
+    pub fn with_options<P: AsRef<Path>>(path: P, private_files: bool) -> Result<Self, DbError> {
+        let db = open(&path).map_err(|e| DbError::ConnectionError(e.to_string()))?;
+        let storage = Self {
+            inner: db,
+            path: path.as_ref().to_path_buf(),
+            private_files,
+        };
+        storage
+            .restrict_files()
+            .map_err(|e| DbError::ConnectionError(e.to_string()))?;
+        Ok(storage)
+    }
+
+    /// Give the database directory and the files in it user-only permissions
+    fn restrict_files(&self) -> std::io::Result<()> {
+        if !self.private_files {
+            return Ok(());
+        }
+        restrict_to_owner(&self.path)?;
+        for entry in fs::read_dir(&self.path)? {
+            restrict_to_owner(&entry?.path())?;
+        }
+        Ok(())
+    }
+
+    /// Path of the JSON backup written by [`Db::checkpoint`]
+    ///
+    /// The backup sits next to the database, e.g. `tasks.db` → `tasks.db.backup.json`.
+    pub fn backup_path(&self) -> PathBuf {
+        let mut name = self.path.as_os_str().to_os_string();
+        name.push(".backup.json");
+        PathBuf::from(name)
+    }
+
+    /// Path next to the database with `suffix` appended, e.g. `tasks.db.compact-new`
+    fn sibling_path(&self, suffix: &str) -> PathBuf {
+        let mut name = self.path.as_os_str().to_os_string();
+        name.push(suffix);
+        PathBuf::from(name)
+    }
+
+    /// Rewrite the database into fresh files to reclaim space held by old data
+    ///
+    /// Sled has no in-place compaction, so the live entries are copied into a
+    /// new database next to the old one, which replaces it only once the copy
+    /// is complete and flushed. Returns the size on disk in bytes before and
+    /// after.
+    ///
+    /// # Errors
+    /// On failure the original database is kept (or put back) and reopened.
+    /// Only if even that fails is the store left unusable, and it should be
+    /// dropped and opened again.
+    pub fn compact(&mut self) -> Result<(u64, u64), DbError> {
+        let op_failed = |e: sled::Error| DbError::OperationFailed(e.to_string());
+
+        self.inner.flush().map_err(op_failed)?;
+        let before = self.inner.size_on_disk().map_err(op_failed)?;
+        let entries = self
+            .inner
+            .iter()
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(op_failed)?;
+        let side_entries = SIDE_TREES
+            .iter()
+            .map(|name| {
+                self.inner
+                    .open_tree(name)
+                    .map_err(op_failed)?
+                    .iter()
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(op_failed)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let fresh_path = self.sibling_path(".compact-new");
+        let old_path = self.sibling_path(".compact-old");
+        if let Err(e) = write_copy(&fresh_path, entries, side_entries) {
+            let _ = fs::remove_dir_all(&fresh_path);
+            return Err(e);
+        }
+        if let Err(e) = self.swap_in(&fresh_path, &old_path) {
+            let _ = fs::remove_dir_all(&fresh_path);
+            return Err(e);
+        }
+        // The compacted database is in place; a leftover old copy is removed next time
+        let _ = fs::remove_dir_all(&old_path);
+
+        let after = self.inner.size_on_disk().map_err(op_failed)?;
+        Ok((before, after))
+    }
+
+    /// Move the database aside to `old_path` and the copy at `fresh_path` into its place
+    ///
+    /// If either move fails, or the copy won't open, the original database
+    /// is put back and reopened before the error is returned.
+    fn swap_in(&mut self, fresh_path: &Path, old_path: &Path) -> Result<(), DbError> {
+        let io_failed = |e: std::io::Error| DbError::OperationFailed(e.to_string());
+        let connection_failed = |e: sled::Error| DbError::ConnectionError(e.to_string());
+        if old_path.exists() {
+            fs::remove_dir_all(old_path).map_err(io_failed)?;
+        }
+
+        // Release the current files so the directory can be moved
+        self.inner = sled::Config::new()
+            .temporary(true)
+            .open()
+            .map_err(connection_failed)?;
+        let swapped = fs::rename(&self.path, old_path).and_then(|()| {
+            fs::rename(fresh_path, &self.path).map_err(|e| {
+                let _ = fs::rename(old_path, &self.path);
+                e
+            })
+        });
+        let error = match (swapped, open(&self.path)) {
+            (Ok(()), Ok(db)) => {
+                self.inner = db;
+                return self.restrict_files().map_err(io_failed);
+            }
+            (Err(e), Ok(db)) => {
+                self.inner = db;
+                return Err(io_failed(e));
+            }
+            (Err(e), Err(_)) => io_failed(e),
+            (Ok(()), Err(e)) => {
+                // The copy is in place but won't open: put the original back
+                let _ = fs::rename(&self.path, fresh_path);
+                let _ = fs::rename(old_path, &self.path);
+                connection_failed(e)
+            }
+        };
+        self.inner = open(&self.path).map_err(connection_failed)?;
+        Err(error)
+    }
+}
+
+/// Write a new database at `path` holding `entries` and the `side_entries` of each of [`SIDE_TREES`]
+///
+/// Anything left at `path` by an earlier, interrupted compaction is removed
+/// first. The database is flushed and closed before returning.
+fn write_copy(
+    path: &Path,
+    entries: Vec<(sled::IVec, sled::IVec)>,
+    side_entries: Vec<Vec<(sled::IVec, sled::IVec)>>,
+) -> Result<(), DbError> {
+    let op_failed = |e: sled::Error| DbError::OperationFailed(e.to_string());
+    if path.exists() {
+        fs::remove_dir_all(path).map_err(|e| DbError::OperationFailed(e.to_string()))?;
+    }
+    let fresh = open(path).map_err(|e| DbError::ConnectionError(e.to_string()))?;
+    for (key, value) in entries {
+        fresh.insert(key, value).map_err(op_failed)?;
+    }
+    for (name, entries) in SIDE_TREES.iter().zip(side_entries) {
+        let tree = fresh.open_tree(name).map_err(op_failed)?;
+        for (key, value) in entries {
+            tree.insert(key, value).map_err(op_failed)?;
+        }
+    }
+    fresh.flush().map_err(op_failed)?;
+    Ok(())
+}
+
+/// Limit `path` to its owner: `0700` for directories, `0600` for files
+///
+/// Does nothing on platforms without Unix permissions.
+pub fn restrict_to_owner(path: &Path) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = if path.is_dir() { 0o700 } else { 0o600 };
+        fs::set_permissions(path, fs::Permissions::from_mode(mode))?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+/// Tells apart the temporary files of writers in the same process
+static TEMP_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Replace the file at `path` with `contents` in one step
+///
+/// The contents go to a temporary file next to `path`, which is synced and
+/// then renamed over it, so readers (and a crash part-way) see either the
+/// old file or the new one, never a truncated mix. With `private`, the new
+/// file is created readable by its owner only (`0600` on Unix), so its
+/// contents are never exposed to other users, even briefly. The directory
+/// holding `path` is created if needed.
+pub fn write_file_atomically(path: &Path, contents: &[u8], private: bool) -> std::io::Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    let mut temp_name = path.as_os_str().to_os_string();
+    temp_name.push(format!(
+        ".{}.{}.tmp",
+        std::process::id(),
+        TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let temp_path = PathBuf::from(temp_name);
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    if private {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    #[cfg(not(unix))]
+    let _ = private;
+
+    let written = (|| {
+        let mut file = options.open(&temp_path)?;
+        file.write_all(contents)?;
+        file.sync_all()?;
+        fs::rename(&temp_path, path)
+    })();
+    if written.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    written
+}
+
+/// Storage in a single JSON file that can be edited by hand
+///
+/// The file is a pretty-printed JSON object holding the tasks, oldest first,
+/// the scratchpad text and the time-tracking entries:
+///
+/// ```json
+/// {
+///   "tasks": [
+///     {
+///       "id": "51b7941b-a4ad-4699-bb84-2cb468ead770",
+///       "title": "Pay rent",
+///       "description": "",
+///       ...
+///     }
+///   ],
+///   "scratch": "Call the landlord about the boiler",
+///   "time_entries": []
+/// }
+/// ```
+///
+/// Each task is written as in `wimm export --format json`, and a plain
+/// array of tasks, such as an export or a database backup, is read as a
+/// file with no scratchpad or time entries. Missing keys are empty.
+///
+/// The file is read on every load, so edits made in an editor show up
+/// without reopening the storage, and a missing file is an empty list. Every
+/// change rewrites the whole file: the new list goes to a temporary file next
+/// to it, which is then renamed over the old one. Readers (and a crash
+/// part-way) see either the old list or the new one, never a mix; two
+/// writers racing each other can still lose one of their changes.
+#[derive(Debug)]
+pub struct JsonFileStorage {
+    /// The task file
+    path: PathBuf,
+    /// Keep the task file readable by its owner only
+    private_files: bool,
+}
+
+/// Everything a [`JsonFileStorage`] file holds
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct JsonDocument {
+    tasks: Vec<Task>,
+    scratch: String,
+    time_entries: Vec<TimeEntry>,
+}
+
+impl JsonFileStorage {
+    /// Use the task file at `path`, made private to the current user
+    ///
+    /// Nothing is read or created until the tasks are first loaded or saved.
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self::with_options(path, true)
+    }
+
+    /// Use the task file at `path`, optionally restricting it to its owner
+    pub fn with_options<P: AsRef<Path>>(path: P, private_files: bool) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            private_files,
+        }
+    }
+
+    /// Read the whole file; a missing file is an empty document
+    fn read_document(&self) -> Result<JsonDocument, DbError> {
+        let json = match fs::read_to_string(&self.path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(JsonDocument::default())
+            }
+            Err(e) => return Err(DbError::OperationFailed(e.to_string())),
+        };
+        let value: serde_json::Value = serde_json::from_str(&json)?;
+        if value.is_array() {
+            return Ok(JsonDocument {
+                tasks: serde_json::from_value(value)?,
+                ..JsonDocument::default()
+            });
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Read the file, change it with `f`, and replace the file with the result
+    fn update<F>(&mut self, f: F) -> Result<(), DbError>
+    where
+        F: FnOnce(&mut JsonDocument) -> Result<(), DbError>,
+    {
+        let mut document = self.read_document()?;
+        f(&mut document)?;
+        document.tasks = sort_loaded(document.tasks);
+        self.write_document(&document)
+    }
+
+    /// Atomically replace the file with `document`
+    fn write_document(&self, document: &JsonDocument) -> Result<(), DbError> {
+        let mut json = serde_json::to_vec_pretty(document)?;
+        json.push(b'\n');
+        write_file_atomically(&self.path, &json, self.private_files)
+            .map_err(|e| DbError::OperationFailed(e.to_string()))
     }
 }
 
@@ -111,10 +536,14 @@ impl SledStorage {
 ///
 /// This implementation is thread-safe when used within a single thread,
 /// but does not provide any synchronization for concurrent access.
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone)]
 pub struct MemoryStorage {
     /// Internal hashmap storing tasks by ID
     tasks: HashMap<String, Task>,
+    /// Scratchpad text
+    scratch: String,
+    /// Time-tracking entries by task ID, in the order they were first saved
+    time_entries: HashMap<String, Vec<TimeEntry>>,
 }
 
 impl MemoryStorage {
@@ -123,14 +552,32 @@ impl MemoryStorage {
     /// # Arguments
     /// * `tasks` - Initial tasks to populate the storage with
     pub fn new(tasks: HashMap<String, Task>) -> Self {
-        Self { tasks }
+        Self {
+            tasks,
+            scratch: String::new(),
+            time_entries: HashMap::new(),
+        }
     }
 }
 
+/// Put loaded tasks in a stable order that doesn't depend on the backend
+fn sort_loaded(mut tasks: Vec<Task>) -> Vec<Task> {
+    tasks.sort_by(|a, b| {
+        a.created_at
+            .cmp(&b.created_at)
+            .then_with(|| a.id.cmp(&b.id))
+    });
+    tasks
+}
+
 impl Db for MemoryStorage {
     fn load_tasks(&self) -> Result<Vec<Task>, DbError> {
         // Convert hashmap values to vector, cloning each task
-        Ok(self.tasks.values().cloned().collect())
+        Ok(sort_loaded(self.tasks.values().cloned().collect()))
+    }
+
+    fn get_task(&self, id: &str) -> Result<Option<Task>, DbError> {
+        Ok(self.tasks.get(id).cloned())
     }
 
     fn save_task(&mut self, task: &Task) -> Result<(), DbError> {
@@ -152,6 +599,30 @@ impl Db for MemoryStorage {
         self.tasks.clear();
         Ok(())
     }
+
+    fn load_scratch(&self) -> Result<String, DbError> {
+        Ok(self.scratch.clone())
+    }
+
+    fn save_scratch(&mut self, text: &str) -> Result<(), DbError> {
+        self.scratch = text.to_string();
+        Ok(())
+    }
+
+    fn save_time_entry(&mut self, entry: &TimeEntry) -> Result<(), DbError> {
+        let entries = self.time_entries.entry(entry.task_id.clone()).or_default();
+        match entries.iter_mut().find(|e| e.id == entry.id) {
+            Some(existing) => *existing = entry.clone(),
+            None => entries.push(entry.clone()),
+        }
+        Ok(())
+    }
+
+    fn load_time_entries(&self, task_id: &str) -> Result<Vec<TimeEntry>, DbError> {
+        let mut entries = self.time_entries.get(task_id).cloned().unwrap_or_default();
+        entries.sort_by_key(|e| e.start_time);
+        Ok(entries)
+    }
 }
 
 impl Db for SledStorage {
@@ -169,6 +640,18 @@ impl Db for SledStorage {
             .iter()
             .map(|v| serde_json::from_slice(v).map_err(DbError::from))
             .collect::<Result<Vec<Task>, _>>()
+            .map(sort_loaded)
+    }
+
+    fn get_task(&self, id: &str) -> Result<Option<Task>, DbError> {
+        // Look the task up by its key instead of scanning the whole tree
+        let value = self
+            .inner
+            .get(id)
+            .map_err(|e| DbError::OperationFailed(e.to_string()))?;
+        value
+            .map(|v| serde_json::from_slice(&v).map_err(DbError::from))
+            .transpose()
     }
 
     fn save_task(&mut self, task: &Task) -> Result<(), DbError> {
@@ -181,6 +664,17 @@ impl Db for SledStorage {
         Ok(())
     }
 
+    fn save_tasks(&mut self, tasks: &[Task]) -> Result<(), DbError> {
+        // Apply every insert as one batch so a crash can't leave half of them
+        let mut batch = sled::Batch::default();
+        for task in tasks {
+            batch.insert(task.id.as_bytes(), serde_json::to_vec(task)?);
+        }
+        self.inner
+            .apply_batch(batch)
+            .map_err(|e| DbError::OperationFailed(e.to_string()))
+    }
+
     fn delete_task(&mut self, task_id: &str) -> Result<(), DbError> {
         // Remove from database and verify the key existed
         self.inner
@@ -197,6 +691,219 @@ impl Db for SledStorage {
             .map_err(|e| DbError::OperationFailed(e.to_string()))?;
         Ok(())
     }
+
+    fn location(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+
+    fn load_scratch(&self) -> Result<String, DbError> {
+        let op_failed = |e: sled::Error| DbError::OperationFailed(e.to_string());
+        let meta = self.inner.open_tree(META_TREE).map_err(op_failed)?;
+        match meta.get(SCRATCH_KEY).map_err(op_failed)? {
+            Some(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            None => Ok(String::new()),
+        }
+    }
+
+    fn save_scratch(&mut self, text: &str) -> Result<(), DbError> {
+        let op_failed = |e: sled::Error| DbError::OperationFailed(e.to_string());
+        let meta = self.inner.open_tree(META_TREE).map_err(op_failed)?;
+        meta.insert(SCRATCH_KEY, serde_json::to_vec(text)?)
+            .map_err(op_failed)?;
+        Ok(())
+    }
+
+    fn save_time_entry(&mut self, entry: &TimeEntry) -> Result<(), DbError> {
+        let op_failed = |e: sled::Error| DbError::OperationFailed(e.to_string());
+        let tree = self.inner.open_tree(TIME_ENTRIES_TREE).map_err(op_failed)?;
+        tree.insert(
+            time_entry_key(&entry.task_id, &entry.id),
+            serde_json::to_vec(entry)?,
+        )
+        .map_err(op_failed)?;
+        Ok(())
+    }
+
+    fn load_time_entries(&self, task_id: &str) -> Result<Vec<TimeEntry>, DbError> {
+        let op_failed = |e: sled::Error| DbError::OperationFailed(e.to_string());
+        let tree = self.inner.open_tree(TIME_ENTRIES_TREE).map_err(op_failed)?;
+        let mut entries = tree
+            .scan_prefix(time_entry_key(task_id, ""))
+            .values()
+            .map(|v| Ok(serde_json::from_slice::<TimeEntry>(&v.map_err(op_failed)?)?))
+            .collect::<Result<Vec<_>, DbError>>()?;
+        entries.sort_by_key(|e| e.start_time);
+        Ok(entries)
+    }
+
+    fn checkpoint(&mut self) -> Result<(), DbError> {
+        // Make sure everything written so far has reached disk
+        self.inner
+            .flush()
+            .map_err(|e| DbError::OperationFailed(e.to_string()))?;
+
+        // Write a human-readable snapshot that survives database corruption,
+        // replacing the previous one only once it is complete
+        let backup = serde_json::to_vec_pretty(&self.load_tasks()?)?;
+        write_file_atomically(&self.backup_path(), &backup, self.private_files)
+            .map_err(|e| DbError::OperationFailed(e.to_string()))
+    }
+}
+
+impl Db for JsonFileStorage {
+    fn load_tasks(&self) -> Result<Vec<Task>, DbError> {
+        Ok(sort_loaded(self.read_document()?.tasks))
+    }
+
+    fn save_task(&mut self, task: &Task) -> Result<(), DbError> {
+        self.save_tasks(std::slice::from_ref(task))
+    }
+
+    fn save_tasks(&mut self, tasks: &[Task]) -> Result<(), DbError> {
+        // One rewrite for the whole batch, so either every task lands or none
+        self.update(|document| {
+            let stored = &mut document.tasks;
+            for task in tasks {
+                match stored.iter_mut().find(|t| t.id == task.id) {
+                    Some(existing) => *existing = task.clone(),
+                    None => stored.push(task.clone()),
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn delete_task(&mut self, task_id: &str) -> Result<(), DbError> {
+        self.update(|document| {
+            let index = document
+                .tasks
+                .iter()
+                .position(|t| t.id == task_id)
+                .ok_or_else(|| DbError::NotFound(task_id.to_string()))?;
+            document.tasks.remove(index);
+            Ok(())
+        })
+    }
+
+    fn clear(&mut self) -> Result<(), DbError> {
+        self.update(|document| {
+            document.tasks.clear();
+            Ok(())
+        })
+    }
+
+    fn location(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+
+    fn load_scratch(&self) -> Result<String, DbError> {
+        Ok(self.read_document()?.scratch)
+    }
+
+    fn save_scratch(&mut self, text: &str) -> Result<(), DbError> {
+        self.update(|document| {
+            document.scratch = text.to_string();
+            Ok(())
+        })
+    }
+
+    fn save_time_entry(&mut self, entry: &TimeEntry) -> Result<(), DbError> {
+        self.update(|document| {
+            let entries = &mut document.time_entries;
+            match entries.iter_mut().find(|e| e.id == entry.id) {
+                Some(existing) => *existing = entry.clone(),
+                None => entries.push(entry.clone()),
+            }
+            Ok(())
+        })
+    }
+
+    fn load_time_entries(&self, task_id: &str) -> Result<Vec<TimeEntry>, DbError> {
+        let mut entries: Vec<TimeEntry> = self
+            .read_document()?
+            .time_entries
+            .into_iter()
+            .filter(|e| e.task_id == task_id)
+            .collect();
+        entries.sort_by_key(|e| e.start_time);
+        Ok(entries)
+    }
+}
+
+/// The storage backend chosen with `[storage] backend`
+///
+/// Lets the binary pick a backend at startup while the rest of the
+/// application stays generic over [`Db`].
+#[derive(Debug)]
+pub enum Store {
+    Sled(SledStorage),
+    JsonFile(JsonFileStorage),
+}
+
+impl Store {
+    fn backend(&self) -> &dyn Db {
+        match self {
+            Store::Sled(storage) => storage,
+            Store::JsonFile(storage) => storage,
+        }
+    }
+
+    fn backend_mut(&mut self) -> &mut dyn Db {
+        match self {
+            Store::Sled(storage) => storage,
+            Store::JsonFile(storage) => storage,
+        }
+    }
+}
+
+impl Db for Store {
+    fn load_tasks(&self) -> Result<Vec<Task>, DbError> {
+        self.backend().load_tasks()
+    }
+
+    fn get_task(&self, id: &str) -> Result<Option<Task>, DbError> {
+        self.backend().get_task(id)
+    }
+
+    fn save_task(&mut self, task: &Task) -> Result<(), DbError> {
+        self.backend_mut().save_task(task)
+    }
+
+    fn save_tasks(&mut self, tasks: &[Task]) -> Result<(), DbError> {
+        self.backend_mut().save_tasks(tasks)
+    }
+
+    fn delete_task(&mut self, task_id: &str) -> Result<(), DbError> {
+        self.backend_mut().delete_task(task_id)
+    }
+
+    fn clear(&mut self) -> Result<(), DbError> {
+        self.backend_mut().clear()
+    }
+
+    fn checkpoint(&mut self) -> Result<(), DbError> {
+        self.backend_mut().checkpoint()
+    }
+
+    fn location(&self) -> Option<&Path> {
+        self.backend().location()
+    }
+
+    fn load_scratch(&self) -> Result<String, DbError> {
+        self.backend().load_scratch()
+    }
+
+    fn save_scratch(&mut self, text: &str) -> Result<(), DbError> {
+        self.backend_mut().save_scratch(text)
+    }
+
+    fn save_time_entry(&mut self, entry: &TimeEntry) -> Result<(), DbError> {
+        self.backend_mut().save_time_entry(entry)
+    }
+
+    fn load_time_entries(&self, task_id: &str) -> Result<Vec<TimeEntry>, DbError> {
+        self.backend().load_time_entries(task_id)
+    }
 }
 
 /// Convert JSON serialization errors to database errors
@@ -213,8 +920,9 @@ impl From<serde_json::Error> for DbError {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::{Priority, TaskSource};
     use std::collections::HashMap;
-    use std::time::SystemTime;
+    use std::time::{Duration, SystemTime};
     use tempfile::TempDir;
 
     fn create_test_task(id: &str, title: &str) -> Task {
@@ -226,9 +934,34 @@ mod tests {
             created_at: SystemTime::now(),
             due: None,
             defer_until: None,
+            someday: false,
+            tags: Vec::new(),
+            energy: None,
+            source: TaskSource::Tui,
+            url: None,
+            notes: String::new(),
+            project: None,
+            waiting_since: None,
+            in_progress: false,
+            subtasks: Vec::new(),
+            defer_until_task: None,
+            recurrence: None,
+            modified_at: SystemTime::UNIX_EPOCH,
+            reminder_lead: None,
+            next_occurrence_id: None,
+            completed_at: None,
+            priority: Priority::Medium,
         }
     }
 
+    /// A finished time entry for `task_id` starting `start` minutes after the epoch
+    fn time_entry(task_id: &str, start: u64, minutes: u64) -> TimeEntry {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(start * 60);
+        let mut entry = TimeEntry::starting_at(task_id.to_string(), start);
+        entry.stop_at(start + Duration::from_secs(minutes * 60));
+        entry
+    }
+
     #[test]
     fn test_db_error_display() {
         let connection_error = DbError::ConnectionError("connection failed".to_string());
@@ -304,6 +1037,48 @@ mod tests {
                 .any(|t| t.id == "2" && t.title == "Task 2"));
         }
 
+        #[test]
+        fn test_memory_storage_load_order_is_stable() {
+            let base = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(1000);
+            let mut storage = MemoryStorage::new(HashMap::new());
+            for (id, offset) in [("c", 5), ("b", 0), ("e", 9), ("a", 5), ("d", 1)] {
+                let mut task = create_test_task(id, id);
+                task.created_at = base + std::time::Duration::from_secs(offset);
+                storage.save_task(&task).unwrap();
+            }
+
+            let ids = |tasks: Vec<Task>| tasks.into_iter().map(|t| t.id).collect::<Vec<_>>();
+            let first = ids(storage.load_tasks().unwrap());
+            // Oldest first, ties broken by ID
+            assert_eq!(first, vec!["b", "d", "a", "c", "e"]);
+            for _ in 0..10 {
+                assert_eq!(ids(storage.load_tasks().unwrap()), first);
+            }
+        }
+
+        #[test]
+        fn test_memory_storage_save_tasks() {
+            let mut storage = MemoryStorage::new(HashMap::new());
+            storage.save_task(&create_test_task("1", "Old")).unwrap();
+            storage
+                .save_tasks(&[create_test_task("1", "New"), create_test_task("2", "Added")])
+                .unwrap();
+
+            let tasks = storage.load_tasks().unwrap();
+            assert_eq!(tasks.len(), 2);
+            assert!(tasks.iter().any(|t| t.id == "1" && t.title == "New"));
+        }
+
+        #[test]
+        fn test_memory_storage_get_task() {
+            let mut storage = MemoryStorage::new(HashMap::new());
+            storage.save_task(&create_test_task("1", "Task 1")).unwrap();
+
+            let task = storage.get_task("1").unwrap().unwrap();
+            assert_eq!(task.title, "Task 1");
+            assert!(storage.get_task("missing").unwrap().is_none());
+        }
+
         #[test]
         fn test_memory_storage_save_task() {
             let mut storage = MemoryStorage::new(HashMap::new());
@@ -377,6 +1152,24 @@ mod tests {
             let loaded_tasks = storage.load_tasks().unwrap();
             assert!(loaded_tasks.is_empty());
         }
+
+        #[test]
+        fn test_memory_storage_time_entries() {
+            let mut storage = MemoryStorage::new(HashMap::new());
+            assert!(storage.load_time_entries("1").unwrap().is_empty());
+
+            let late = time_entry("1", 60, 5);
+            let mut early = time_entry("1", 0, 10);
+            storage.save_time_entry(&late).unwrap();
+            storage.save_time_entry(&early).unwrap();
+            storage.save_time_entry(&time_entry("2", 30, 1)).unwrap();
+
+            // Saving an entry again replaces it
+            early.stop_at(early.start_time + Duration::from_secs(20 * 60));
+            storage.save_time_entry(&early).unwrap();
+            assert_eq!(storage.load_time_entries("1").unwrap(), vec![early, late]);
+            assert_eq!(storage.load_time_entries("2").unwrap().len(), 1);
+        }
     }
 
     mod sled_storage_tests {
@@ -391,6 +1184,57 @@ mod tests {
             assert!(storage.is_ok());
         }
 
+        #[cfg(unix)]
+        fn mode(path: &Path) -> u32 {
+            use std::os::unix::fs::PermissionsExt;
+            fs::metadata(path).unwrap().permissions().mode() & 0o777
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn test_sled_storage_files_are_private() {
+            let temp_dir = TempDir::new().unwrap();
+            let db_path = temp_dir.path().join("test.db");
+
+            let mut storage = SledStorage::new(&db_path).unwrap();
+            assert_eq!(mode(&db_path), 0o700);
+            assert_eq!(mode(&db_path.join("db")), 0o600);
+
+            storage.checkpoint().unwrap();
+            assert_eq!(mode(&storage.backup_path()), 0o600);
+
+            storage.compact().unwrap();
+            assert_eq!(mode(&db_path), 0o700);
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn test_write_file_atomically_creates_private_files() {
+            use std::os::unix::fs::PermissionsExt;
+            let temp_dir = TempDir::new().unwrap();
+            let path = temp_dir.path().join("tasks.json");
+            fs::write(&path, "[]").unwrap();
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+            // The replacement is private from the start, not chmodded afterwards
+            write_file_atomically(&path, b"[1]", true).unwrap();
+            assert_eq!(mode(&path), 0o600);
+            assert_eq!(fs::read_to_string(&path).unwrap(), "[1]");
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn test_sled_storage_private_files_opt_out() {
+            use std::os::unix::fs::PermissionsExt;
+            let temp_dir = TempDir::new().unwrap();
+            let db_path = temp_dir.path().join("test.db");
+            fs::create_dir(&db_path).unwrap();
+            fs::set_permissions(&db_path, fs::Permissions::from_mode(0o755)).unwrap();
+
+            let _storage = SledStorage::with_options(&db_path, false).unwrap();
+            assert_eq!(mode(&db_path), 0o755);
+        }
+
         #[test]
         fn test_sled_storage_new_invalid_path() {
             // Try to create database in a non-existent directory
@@ -419,6 +1263,182 @@ mod tests {
             assert_eq!(loaded_tasks[0].title, "Test Task");
         }
 
+        #[test]
+        fn test_sled_storage_save_tasks_in_one_batch() {
+            let temp_dir = TempDir::new().unwrap();
+            let db_path = temp_dir.path().join("test.db");
+
+            let mut storage = SledStorage::new(&db_path).unwrap();
+            storage.save_task(&create_test_task("1", "Old")).unwrap();
+            storage.save_task(&create_test_task("2", "Kept")).unwrap();
+            storage
+                .save_tasks(&[create_test_task("1", "New"), create_test_task("3", "Added")])
+                .unwrap();
+            storage.save_tasks(&[]).unwrap();
+            storage.inner.flush().unwrap();
+            drop(storage);
+
+            let tasks = SledStorage::new(&db_path).unwrap().load_tasks().unwrap();
+            let mut titles: Vec<(&str, &str)> = tasks
+                .iter()
+                .map(|t| (t.id.as_str(), t.title.as_str()))
+                .collect();
+            titles.sort();
+            assert_eq!(titles, vec![("1", "New"), ("2", "Kept"), ("3", "Added")]);
+        }
+
+        #[test]
+        fn test_sled_storage_get_task() {
+            let temp_dir = TempDir::new().unwrap();
+            let db_path = temp_dir.path().join("test.db");
+
+            let mut storage = SledStorage::new(&db_path).unwrap();
+            storage.save_task(&create_test_task("1", "Task 1")).unwrap();
+            storage.save_task(&create_test_task("2", "Task 2")).unwrap();
+
+            let task = storage.get_task("2").unwrap().unwrap();
+            assert_eq!(task.title, "Task 2");
+            assert!(storage.get_task("missing").unwrap().is_none());
+        }
+
+        #[test]
+        fn test_sled_storage_compact_keeps_tasks() {
+            let temp_dir = TempDir::new().unwrap();
+            let db_path = temp_dir.path().join("test.db");
+
+            let mut storage = SledStorage::new(&db_path).unwrap();
+            for i in 0..50 {
+                let task = create_test_task(&format!("task{i}"), "Churn");
+                storage.save_task(&task).unwrap();
+                if i % 2 == 0 {
+                    storage.delete_task(&task.id).unwrap();
+                }
+            }
+
+            let (before, after) = storage.compact().unwrap();
+            assert!(before > 0);
+            assert!(after > 0);
+            assert_eq!(storage.load_tasks().unwrap().len(), 25);
+
+            // Still usable, and the compacted data survives a reopen
+            storage
+                .save_task(&create_test_task("new", "After compaction"))
+                .unwrap();
+            drop(storage);
+            let reopened = SledStorage::new(&db_path).unwrap();
+            assert_eq!(reopened.load_tasks().unwrap().len(), 26);
+        }
+
+        #[test]
+        fn test_sled_storage_failed_compact_keeps_the_database() {
+            let temp_dir = TempDir::new().unwrap();
+            let db_path = temp_dir.path().join("test.db");
+            let mut storage = SledStorage::new(&db_path).unwrap();
+            storage.save_task(&create_test_task("1", "Before")).unwrap();
+
+            // A file where the compacted copy should go makes the copy fail
+            fs::write(temp_dir.path().join("test.db.compact-new"), "in the way").unwrap();
+            assert!(storage.compact().is_err());
+
+            // Writes still reach the real database
+            storage.save_task(&create_test_task("2", "After")).unwrap();
+            assert_eq!(storage.load_tasks().unwrap().len(), 2);
+            storage.inner.flush().unwrap();
+            drop(storage);
+            let mut reopened = SledStorage::new(&db_path).unwrap();
+            assert_eq!(reopened.load_tasks().unwrap().len(), 2);
+
+            // Once the way is clear, compaction works again
+            fs::remove_file(temp_dir.path().join("test.db.compact-new")).unwrap();
+            reopened.compact().unwrap();
+            assert_eq!(reopened.load_tasks().unwrap().len(), 2);
+            let leftovers: Vec<_> = fs::read_dir(temp_dir.path())
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name())
+                .filter(|name| name != "test.db")
+                .collect();
+            assert!(leftovers.is_empty(), "{leftovers:?}");
+        }
+
+        #[test]
+        fn test_sled_storage_scratch_persists_across_reopen() {
+            let temp_dir = TempDir::new().unwrap();
+            let db_path = temp_dir.path().join("test.db");
+
+            let mut storage = SledStorage::new(&db_path).unwrap();
+            assert_eq!(storage.load_scratch().unwrap(), "");
+            storage.save_task(&create_test_task("1", "Task")).unwrap();
+            storage.save_scratch("Ideas:\nlearn sled").unwrap();
+            // The scratchpad is not a task and survives clearing them
+            assert_eq!(storage.load_tasks().unwrap().len(), 1);
+            storage.clear().unwrap();
+            drop(storage);
+
+            let mut reopened = SledStorage::new(&db_path).unwrap();
+            assert_eq!(reopened.load_scratch().unwrap(), "Ideas:\nlearn sled");
+            reopened.compact().unwrap();
+            assert_eq!(reopened.load_scratch().unwrap(), "Ideas:\nlearn sled");
+        }
+
+        #[test]
+        fn test_sled_storage_time_entries_round_trip() {
+            let temp_dir = TempDir::new().unwrap();
+            let db_path = temp_dir.path().join("test.db");
+
+            let mut storage = SledStorage::new(&db_path).unwrap();
+            let late = time_entry("task", 60, 5);
+            let mut early = time_entry("task", 0, 10);
+            // A task ID that starts with another's mustn't share its entries
+            let other = time_entry("task2", 30, 1);
+            for entry in [&late, &early, &other] {
+                storage.save_time_entry(entry).unwrap();
+            }
+            early.stop_at(early.start_time + Duration::from_secs(20 * 60));
+            storage.save_time_entry(&early).unwrap();
+
+            // Time entries aren't tasks and survive clearing them
+            storage.save_task(&create_test_task("1", "Task")).unwrap();
+            assert_eq!(storage.load_tasks().unwrap().len(), 1);
+            storage.clear().unwrap();
+            assert_eq!(
+                storage.load_time_entries("task").unwrap(),
+                vec![early.clone(), late.clone()]
+            );
+            assert_eq!(storage.load_time_entries("task2").unwrap(), vec![other]);
+            assert!(storage.load_time_entries("missing").unwrap().is_empty());
+
+            storage.compact().unwrap();
+            assert_eq!(
+                storage.load_time_entries("task").unwrap(),
+                vec![early, late]
+            );
+        }
+
+        #[test]
+        fn test_sled_storage_location() {
+            let temp_dir = TempDir::new().unwrap();
+            let db_path = temp_dir.path().join("test.db");
+
+            let storage = SledStorage::new(&db_path).unwrap();
+            assert_eq!(storage.location(), Some(db_path.as_path()));
+            assert_eq!(MemoryStorage::new(HashMap::new()).location(), None);
+        }
+
+        #[test]
+        fn test_sled_storage_round_trips_notes() {
+            let temp_dir = TempDir::new().unwrap();
+            let db_path = temp_dir.path().join("test.db");
+
+            let mut storage = SledStorage::new(&db_path).unwrap();
+            let mut task = create_test_task("test123", "Test Task");
+            task.notes = "First note\nSecond note".to_string();
+
+            storage.save_task(&task).unwrap();
+
+            let loaded_tasks = storage.load_tasks().unwrap();
+            assert_eq!(loaded_tasks[0].notes, "First note\nSecond note");
+        }
+
         #[test]
         fn test_sled_storage_multiple_tasks() {
             let temp_dir = TempDir::new().unwrap();
@@ -517,6 +1537,57 @@ mod tests {
             }
         }
 
+        #[test]
+        fn test_sled_storage_checkpoint_writes_backup() {
+            let temp_dir = TempDir::new().unwrap();
+            let db_path = temp_dir.path().join("test.db");
+
+            let mut storage = SledStorage::new(&db_path).unwrap();
+            storage
+                .save_task(&create_test_task("backed_up", "Backed Up"))
+                .unwrap();
+            storage.checkpoint().unwrap();
+
+            let backup_path = temp_dir.path().join("test.db.backup.json");
+            assert_eq!(storage.backup_path(), backup_path);
+            let backup: Vec<Task> =
+                serde_json::from_slice(&std::fs::read(backup_path).unwrap()).unwrap();
+            assert_eq!(backup.len(), 1);
+            assert_eq!(backup[0].id, "backed_up");
+        }
+
+        #[test]
+        fn test_sled_storage_checkpoint_replaces_backup_whole() {
+            let temp_dir = TempDir::new().unwrap();
+            let mut storage = SledStorage::new(temp_dir.path().join("test.db")).unwrap();
+            storage.save_task(&create_test_task("1", "First")).unwrap();
+            storage.checkpoint().unwrap();
+            storage.save_task(&create_test_task("2", "Second")).unwrap();
+            storage.checkpoint().unwrap();
+
+            let backup: Vec<Task> =
+                serde_json::from_slice(&std::fs::read(storage.backup_path()).unwrap()).unwrap();
+            assert_eq!(backup.len(), 2);
+            let names: Vec<_> = std::fs::read_dir(temp_dir.path())
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name())
+                .collect();
+            assert_eq!(names.len(), 2, "temporary files left behind: {names:?}");
+        }
+
+        #[test]
+        fn test_write_file_atomically_keeps_the_old_file_on_failure() {
+            let temp_dir = TempDir::new().unwrap();
+            // A directory in the way makes the final rename fail
+            let path = temp_dir.path().join("backup.json");
+            std::fs::create_dir(&path).unwrap();
+            std::fs::write(path.join("kept"), "old").unwrap();
+
+            assert!(write_file_atomically(&path, b"new", true).is_err());
+            assert_eq!(std::fs::read_to_string(path.join("kept")).unwrap(), "old");
+            assert_eq!(std::fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+        }
+
         #[test]
         fn test_sled_storage_overwrite_task() {
             let temp_dir = TempDir::new().unwrap();
@@ -536,4 +1607,164 @@ mod tests {
             assert!(loaded_tasks[0].completed);
         }
     }
+
+    mod json_file_storage_tests {
+        use super::*;
+
+        #[test]
+        fn test_json_file_storage_round_trip() {
+            let temp_dir = TempDir::new().unwrap();
+            let path = temp_dir.path().join("nested").join("tasks.json");
+            let mut storage = JsonFileStorage::new(&path);
+            assert!(storage.load_tasks().unwrap().is_empty());
+            assert!(!path.exists());
+
+            let mut first = create_test_task("1", "First");
+            let second = create_test_task("2", "Second");
+            storage
+                .save_tasks(&[first.clone(), second.clone()])
+                .unwrap();
+            first.completed = true;
+            storage.save_task(&first).unwrap();
+
+            // Another instance sees the same tasks, kept under "tasks" in the file
+            let reopened = JsonFileStorage::new(&path);
+            assert_eq!(
+                reopened.load_tasks().unwrap(),
+                vec![first.clone(), second.clone()]
+            );
+            assert_eq!(reopened.get_task("1").unwrap(), Some(first.clone()));
+            assert_eq!(reopened.location(), Some(path.as_path()));
+            let json = fs::read_to_string(&path).unwrap();
+            assert!(json.starts_with("{\n  \"tasks\": [\n    {\n      \"id\": \"1\""));
+            let from_file: serde_json::Value = serde_json::from_str(&json).unwrap();
+            let from_file: Vec<Task> = serde_json::from_value(from_file["tasks"].clone()).unwrap();
+            assert_eq!(from_file, vec![first, second]);
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                let mode = fs::metadata(&path).unwrap().permissions().mode();
+                assert_eq!(mode & 0o777, 0o600);
+            }
+
+            storage.delete_task("1").unwrap();
+            assert!(matches!(
+                storage.delete_task("1"),
+                Err(DbError::NotFound(id)) if id == "1"
+            ));
+            assert_eq!(storage.load_tasks().unwrap().len(), 1);
+            storage.clear().unwrap();
+            assert!(storage.load_tasks().unwrap().is_empty());
+        }
+
+        #[test]
+        fn test_json_file_storage_keeps_scratch_and_time_entries() {
+            let temp_dir = TempDir::new().unwrap();
+            let path = temp_dir.path().join("tasks.json");
+            let mut storage = JsonFileStorage::new(&path);
+            assert_eq!(storage.load_scratch().unwrap(), "");
+
+            storage.save_task(&create_test_task("1", "Report")).unwrap();
+            storage.save_scratch("Call the landlord").unwrap();
+            let mut entry = time_entry("1", 0, 25);
+            storage.save_time_entry(&time_entry("1", 60, 10)).unwrap();
+            storage.save_time_entry(&entry).unwrap();
+            entry.duration = Some(Duration::from_secs(30 * 60));
+            storage.save_time_entry(&entry).unwrap();
+            storage.save_time_entry(&time_entry("2", 0, 5)).unwrap();
+
+            // Task changes and clearing leave the other keys alone
+            storage.save_task(&create_test_task("2", "Email")).unwrap();
+            storage.clear().unwrap();
+            let reopened = JsonFileStorage::new(&path);
+            assert_eq!(reopened.load_scratch().unwrap(), "Call the landlord");
+            let entries = reopened.load_time_entries("1").unwrap();
+            assert_eq!(entries.len(), 2);
+            assert_eq!(entries[0], entry);
+            assert_eq!(reopened.load_time_entries("2").unwrap().len(), 1);
+        }
+
+        #[test]
+        fn test_json_file_storage_reads_a_task_array() {
+            let temp_dir = TempDir::new().unwrap();
+            let path = temp_dir.path().join("tasks.json");
+            let tasks = vec![create_test_task("1", "Exported")];
+            fs::write(&path, serde_json::to_string(&tasks).unwrap()).unwrap();
+
+            let mut storage = JsonFileStorage::new(&path);
+            assert_eq!(storage.load_tasks().unwrap(), tasks);
+            assert_eq!(storage.load_scratch().unwrap(), "");
+
+            // The first change writes the full document
+            storage.save_scratch("notes").unwrap();
+            assert_eq!(storage.load_tasks().unwrap(), tasks);
+            assert!(fs::read_to_string(&path).unwrap().starts_with("{"));
+        }
+
+        #[test]
+        fn test_json_file_storage_picks_up_hand_edits() {
+            let temp_dir = TempDir::new().unwrap();
+            let path = temp_dir.path().join("tasks.json");
+            let mut storage = JsonFileStorage::new(&path);
+            storage
+                .save_task(&create_test_task("1", "Typo titel"))
+                .unwrap();
+
+            let edited = fs::read_to_string(&path).unwrap().replace("titel", "title");
+            fs::write(&path, edited).unwrap();
+            assert_eq!(storage.load_tasks().unwrap()[0].title, "Typo title");
+
+            // Changes keep the edit rather than writing back an old copy
+            storage.save_task(&create_test_task("2", "New")).unwrap();
+            assert_eq!(storage.get_task("1").unwrap().unwrap().title, "Typo title");
+
+            fs::write(&path, "[{\"id\": \"1\",").unwrap();
+            assert!(matches!(storage.load_tasks(), Err(DbError::SerdeError(_))));
+        }
+
+        #[test]
+        fn test_json_file_storage_readers_never_see_partial_writes() {
+            let temp_dir = TempDir::new().unwrap();
+            let path = temp_dir.path().join("tasks.json");
+            JsonFileStorage::new(&path)
+                .save_task(&create_test_task("seed", "Seed"))
+                .unwrap();
+
+            let writers: Vec<_> = (0..4)
+                .map(|writer| {
+                    let path = path.clone();
+                    std::thread::spawn(move || {
+                        let mut storage = JsonFileStorage::new(&path);
+                        for i in 0..25 {
+                            let id = format!("{writer}-{i}");
+                            storage.save_task(&create_test_task(&id, &id)).unwrap();
+                        }
+                    })
+                })
+                .collect();
+            let reader = {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    let storage = JsonFileStorage::new(&path);
+                    for _ in 0..200 {
+                        // Racing writers may drop each other's tasks, but a
+                        // reader always gets a whole file
+                        assert!(!storage.load_tasks().unwrap().is_empty());
+                    }
+                })
+            };
+            for writer in writers {
+                writer.join().unwrap();
+            }
+            reader.join().unwrap();
+
+            assert!(JsonFileStorage::new(&path).load_tasks().is_ok());
+            let leftovers: Vec<_> = fs::read_dir(temp_dir.path())
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name())
+                .filter(|name| name != "tasks.json")
+                .collect();
+            assert!(leftovers.is_empty(), "{leftovers:?}");
+        }
+    }
 }
@@ -5,57 +5,69 @@
 //! - Track total time spent on tasks across multiple sessions
 //! - View time tracking history and statistics
 //!
-//! **Note**: This module is currently a placeholder with stub implementations.
-//! The actual time tracking functionality will be implemented in future development cycles.
+//! One timer runs at a time: starting a timer stops the one already running.
+//!
+//! Finished entries can be kept across restarts with
+//! [`Db::save_time_entry`](crate::storage::Db::save_time_entry).
 //!
 //! ## Future Features
-//! - Persistent time tracking storage
-//! - Multiple concurrent timers
 //! - Time reporting and analytics
 //! - Integration with task completion workflows
 
 use std::time::{Duration, SystemTime};
 
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
 /// Main time tracking coordinator
 ///
-/// This struct will manage all time tracking operations including:
-/// - Active timer state
-/// - Time entry persistence
-/// - Timer start/stop operations
-/// - Time calculation and reporting
-///
-/// Currently contains only placeholder methods marked with `todo!()`.
-pub struct TimeTracker;
-
-impl Default for TimeTracker {
-    fn default() -> Self {
-        Self::new()
-    }
+/// Holds the finished time entries and the one timer that may be running.
+/// The `*_at` variants of the timer methods take the current time, so
+/// callers with their own clock (and tests) control it.
+#[derive(Debug, Default, Clone)]
+pub struct TimeTracker {
+    /// Finished timing sessions, oldest first
+    entries: Vec<TimeEntry>,
+    /// The session being timed right now, if any
+    active: Option<TimeEntry>,
 }
 
 impl TimeTracker {
     pub fn new() -> Self {
-        Self
+        Self::default()
     }
 
     /// Start a timer for the specified task
     ///
-    /// This will begin tracking time for the given task ID. If a timer is already
-    /// running for another task, it should be stopped before starting the new one.
+    /// This will begin tracking time for the given task ID. A timer already
+    /// running, for this task or another, is stopped and recorded first.
     ///
     /// # Arguments
     /// * `task_id` - The unique identifier of the task to start timing
     ///
     /// # Errors
-    /// Returns an error if the timer cannot be started (e.g., invalid task ID)
-    pub fn start_timer(&mut self, _task_id: &str) -> Result<(), String> {
-        todo!("Implement starting a timer for a task")
+    /// Returns an error if the task ID is empty
+    pub fn start_timer(&mut self, task_id: &str) -> Result<(), String> {
+        self.start_timer_at(task_id, SystemTime::now())
+    }
+
+    /// [`TimeTracker::start_timer`] with the current time given as `now`
+    pub fn start_timer_at(&mut self, task_id: &str, now: SystemTime) -> Result<(), String> {
+        if task_id.is_empty() {
+            return Err("Can't time a task without an ID".to_string());
+        }
+        if let Some(mut running) = self.active.take() {
+            running.stop_at(now);
+            self.entries.push(running);
+        }
+        self.active = Some(TimeEntry::starting_at(task_id.to_string(), now));
+        Ok(())
     }
 
     /// Stop the timer for the specified task and return elapsed time
     ///
-    /// This stops the active timer for the given task and returns the duration
-    /// of this timing session. The time entry should be persisted for future reference.
+    /// This stops the active timer for the given task, records the finished
+    /// entry and returns the duration of this timing session.
     ///
     /// # Arguments
     /// * `task_id` - The unique identifier of the task to stop timing
@@ -65,33 +77,61 @@ impl TimeTracker {
     ///
     /// # Errors
     /// Returns an error if no timer is running for the specified task
-    pub fn stop_timer(&mut self, _task_id: &str) -> Result<Duration, String> {
-        todo!("Implement stopping a timer and returning elapsed time")
+    pub fn stop_timer(&mut self, task_id: &str) -> Result<Duration, String> {
+        self.stop_timer_at(task_id, SystemTime::now())
+    }
+
+    /// [`TimeTracker::stop_timer`] with the current time given as `now`
+    pub fn stop_timer_at(&mut self, task_id: &str, now: SystemTime) -> Result<Duration, String> {
+        let mut entry = match self.active.take() {
+            Some(entry) if entry.task_id == task_id => entry,
+            other => {
+                self.active = other;
+                return Err(format!("No timer is running for task '{task_id}'"));
+            }
+        };
+        entry.stop_at(now);
+        let duration = entry.duration.unwrap_or_default();
+        self.entries.push(entry);
+        Ok(duration)
     }
 
     /// Get the total accumulated time spent on a task across all sessions
     ///
-    /// This calculates the sum of all completed time entries for the specified task,
-    /// providing a comprehensive view of time investment.
+    /// This sums every finished time entry for the specified task; a timer
+    /// still running doesn't count until it is stopped.
     ///
     /// # Arguments
     /// * `task_id` - The unique identifier of the task to query
     ///
     /// # Returns
     /// Total duration spent on the task across all timing sessions
-    pub fn get_total_time(&self, _task_id: &str) -> Duration {
-        todo!("Implement getting total time spent on a task")
+    pub fn get_total_time(&self, task_id: &str) -> Duration {
+        self.entries
+            .iter()
+            .filter(|entry| entry.task_id == task_id)
+            .filter_map(|entry| entry.duration)
+            .sum()
     }
 
     /// Get the task ID of the currently active timer, if any
     ///
-    /// This allows the UI to display which task is currently being timed
-    /// and prevents starting multiple concurrent timers.
+    /// This allows the UI to display which task is currently being timed.
     ///
     /// # Returns
     /// The task ID of the active timer, or None if no timer is running
     pub fn get_active_timer(&self) -> Option<String> {
-        todo!("Implement getting the currently active timer task ID")
+        self.active.as_ref().map(|entry| entry.task_id.clone())
+    }
+
+    /// The running timer's entry, e.g. to show how long it has been running
+    pub fn active_entry(&self) -> Option<&TimeEntry> {
+        self.active.as_ref()
+    }
+
+    /// Finished timing sessions, oldest first
+    pub fn entries(&self) -> &[TimeEntry] {
+        &self.entries
     }
 }
 
@@ -102,8 +142,10 @@ impl TimeTracker {
 /// the calculated duration.
 ///
 /// Time entries form the building blocks of time tracking history and analytics.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct TimeEntry {
+    /// Identifies this entry in storage; saving it again replaces it
+    pub id: String,
     /// The unique identifier of the task being timed
     pub task_id: String,
     /// When this timing session began
@@ -123,9 +165,15 @@ impl TimeEntry {
     /// # Arguments
     /// * `task_id` - The unique identifier of the task being timed
     pub fn new(task_id: String) -> Self {
+        Self::starting_at(task_id, SystemTime::now())
+    }
+
+    /// Create a new active time entry that started at `start_time`
+    pub fn starting_at(task_id: String, start_time: SystemTime) -> Self {
         Self {
+            id: Uuid::new_v4().to_string(),
             task_id,
-            start_time: SystemTime::now(),
+            start_time,
             end_time: None,
             duration: None,
         }
@@ -137,9 +185,15 @@ impl TimeEntry {
     /// and calculating the total duration of the timing session. Once stopped,
     /// the entry represents a complete work session.
     pub fn stop(&mut self) {
-        let now = SystemTime::now();
-        self.end_time = Some(now);
-        self.duration = now.duration_since(self.start_time).ok();
+        self.stop_at(SystemTime::now());
+    }
+
+    /// Stop this time entry at `end_time`
+    ///
+    /// An end before the start (the clock went backwards) counts as no time.
+    pub fn stop_at(&mut self, end_time: SystemTime) {
+        self.end_time = Some(end_time);
+        self.duration = Some(end_time.duration_since(self.start_time).unwrap_or_default());
     }
 }
 
@@ -156,36 +210,79 @@ mod tests {
 
     #[test]
     fn test_time_tracker_default() {
-        let _tracker = TimeTracker;
-        // Test passes if creation succeeds without panic
+        let tracker = TimeTracker::default();
+        assert_eq!(tracker.get_active_timer(), None);
+        assert_eq!(tracker.get_total_time("any"), Duration::ZERO);
     }
 
     #[test]
-    #[should_panic(expected = "not yet implemented")]
-    fn test_time_tracker_start_timer_panics() {
+    fn test_start_and_stop_timer_records_duration() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
         let mut tracker = TimeTracker::new();
-        let _ = tracker.start_timer("test_task");
+        tracker.start_timer_at("write", start).unwrap();
+        assert_eq!(tracker.get_active_timer().as_deref(), Some("write"));
+
+        let elapsed = tracker
+            .stop_timer_at("write", start + Duration::from_secs(90))
+            .unwrap();
+        assert_eq!(elapsed, Duration::from_secs(90));
+        assert_eq!(tracker.get_active_timer(), None);
+        assert_eq!(tracker.get_total_time("write"), Duration::from_secs(90));
     }
 
     #[test]
-    #[should_panic(expected = "not yet implemented")]
-    fn test_time_tracker_stop_timer_panics() {
+    fn test_start_timer_stops_the_running_one() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
+        let minute = Duration::from_secs(60);
         let mut tracker = TimeTracker::new();
-        let _ = tracker.stop_timer("test_task");
+        tracker.start_timer_at("write", start).unwrap();
+        tracker.start_timer_at("email", start + 5 * minute).unwrap();
+        assert_eq!(tracker.get_active_timer().as_deref(), Some("email"));
+        assert_eq!(tracker.get_total_time("write"), 5 * minute);
+
+        // Restarting the same task closes its session and opens a new one
+        tracker.start_timer_at("email", start + 7 * minute).unwrap();
+        tracker.stop_timer_at("email", start + 10 * minute).unwrap();
+        assert_eq!(tracker.get_total_time("email"), 5 * minute);
     }
 
     #[test]
-    #[should_panic(expected = "not yet implemented")]
-    fn test_time_tracker_get_total_time_panics() {
-        let tracker = TimeTracker::new();
-        let _ = tracker.get_total_time("test_task");
+    fn test_total_time_sums_sessions_per_task() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
+        let minute = Duration::from_secs(60);
+        let mut tracker = TimeTracker::new();
+        for (task, from, to) in [("a", 0, 10), ("b", 10, 15), ("a", 20, 25)] {
+            tracker.start_timer_at(task, start + from * minute).unwrap();
+            tracker.stop_timer_at(task, start + to * minute).unwrap();
+        }
+        assert_eq!(tracker.get_total_time("a"), 15 * minute);
+        assert_eq!(tracker.get_total_time("b"), 5 * minute);
+        assert_eq!(tracker.get_total_time("c"), Duration::ZERO);
+
+        // A running timer doesn't count yet
+        tracker.start_timer_at("b", start + 30 * minute).unwrap();
+        assert_eq!(tracker.get_total_time("b"), 5 * minute);
+    }
+
+    #[test]
+    fn test_stop_timer_errors_without_a_matching_timer() {
+        let mut tracker = TimeTracker::new();
+        assert!(tracker.stop_timer("idle").is_err());
+
+        tracker.start_timer("running").unwrap();
+        assert!(tracker.stop_timer("other").is_err());
+        // The running timer is left alone
+        assert_eq!(tracker.get_active_timer().as_deref(), Some("running"));
+        assert!(tracker.stop_timer("running").is_ok());
+        assert!(tracker.start_timer("").is_err());
     }
 
     #[test]
-    #[should_panic(expected = "not yet implemented")]
-    fn test_time_tracker_get_active_timer_panics() {
-        let tracker = TimeTracker::new();
-        let _ = tracker.get_active_timer();
+    fn test_time_entry_stop_before_start_counts_as_zero() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
+        let mut entry = TimeEntry::starting_at("clock".to_string(), start);
+        entry.stop_at(start - Duration::from_secs(5));
+        assert_eq!(entry.duration, Some(Duration::ZERO));
     }
 
     #[test]
@@ -198,6 +295,7 @@ mod tests {
         assert!(entry.duration.is_none());
         // start_time should be approximately now, but we can't test exact equality
         assert!(entry.start_time <= SystemTime::now());
+        assert_ne!(entry.id, TimeEntry::new(task_id).id);
     }
 
     #[test]
@@ -3,11 +3,18 @@
 //! This module defines the fundamental data structures used throughout the application,
 //! including tasks, application state, and operational modes.
 
+use chrono::{DateTime, Days, Local, Months, TimeZone};
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, time::SystemTime};
+use std::{
+    collections::HashMap,
+    hash::{Hash, Hasher},
+    time::{Duration, SystemTime},
+};
+use uuid::Uuid;
 
 use crate::{
     config::Config,
+    filter::Query,
     storage::{Db, MemoryStorage},
 };
 
@@ -16,34 +23,720 @@ use crate::{
 /// The application operates in different modes similar to vim:
 /// - Normal mode: Navigate and execute commands
 /// - Insert mode: Input text for creating/editing tasks
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// - Command mode: Type `:` commands such as `:today` or `:someday`
+/// - Scratch mode: Type into the scratchpad
+/// - Search mode: Type text to search task titles and descriptions for
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub enum Mode {
     /// Default mode for navigation and command execution
+    #[default]
     Normal,
     /// Text input mode for creating and editing task content
     Insert,
+    /// Command-line mode entered with `:`
+    Command,
+    /// Free-text editing of the scratchpad
+    Scratch,
+    /// Typing a search entered with `/`
+    Search,
+}
+
+/// A named filter over the task list
+///
+/// Views decide which tasks are shown in the task list without changing
+/// the tasks themselves. They are selected with `:` commands.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum View {
+    /// Every task, regardless of status
+    #[default]
+    All,
+    /// Open tasks that are due today or overdue and not deferred
+    Today,
+    /// Tasks parked on the someday/maybe list for periodic review
+    Someday,
+    /// Open tasks without a due date, which are easy to forget
+    Unscheduled,
+    /// Open tasks that are deferred, to look ahead at what is coming back
+    Deferred,
+    /// Open tasks flagged for review after going untouched for too long
+    ///
+    /// Which tasks are flagged depends on `[maintenance] stale_review_days`,
+    /// so the app narrows this view further; see [`Task::is_stale`].
+    Review,
+    /// Completed tasks, most recently finished first
+    ///
+    /// With `[ui] done_log`, this is the only view completed tasks appear in.
+    Done,
+}
+
+impl View {
+    /// Command name used to select this view (e.g. `:today`)
+    pub fn name(&self) -> &'static str {
+        match self {
+            View::All => "all",
+            View::Today => "today",
+            View::Someday => "someday",
+            View::Unscheduled => "unscheduled",
+            View::Deferred => "deferred",
+            View::Review => "review",
+            View::Done => "done",
+        }
+    }
+
+    /// Look up a view by its command name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "all" => Some(View::All),
+            "today" => Some(View::Today),
+            "someday" => Some(View::Someday),
+            "unscheduled" => Some(View::Unscheduled),
+            "deferred" => Some(View::Deferred),
+            "review" => Some(View::Review),
+            "done" => Some(View::Done),
+            _ => None,
+        }
+    }
+
+    /// Whether the given task belongs in this view at time `now`
+    pub fn includes(&self, task: &Task, now: SystemTime) -> bool {
+        match self {
+            View::All => true,
+            View::Today => task.is_due_today(now) && !task.is_deferred(now),
+            View::Someday => task.someday,
+            View::Unscheduled => !task.completed && !task.someday && !task.is_scheduled(),
+            View::Deferred => !task.completed && task.is_deferred(now),
+            View::Review => !task.completed,
+            View::Done => task.completed,
+        }
+    }
+}
+
+/// Date urgency a quick filter narrows the task list to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Urgency {
+    /// Past the due date
+    Overdue,
+    /// Due before the end of today, including overdue
+    Today,
+    /// Due within the next seven days, including overdue
+    Week,
+}
+
+impl Urgency {
+    /// Label shown in the task list title
+    pub fn name(&self) -> &'static str {
+        match self {
+            Urgency::Overdue => "overdue",
+            Urgency::Today => "due today",
+            Urgency::Week => "due this week",
+        }
+    }
+
+    /// Whether `task` is at least this urgent at `now`
+    pub fn includes(&self, task: &Task, now: SystemTime) -> bool {
+        match self {
+            Urgency::Overdue => task.is_overdue(now),
+            Urgency::Today => task.is_due_today(now),
+            Urgency::Week => task.is_due_this_week(now),
+        }
+    }
+}
+
+/// Field the task list is ordered by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortKey {
+    /// The order tasks were added in
+    #[default]
+    Manual,
+    /// Due date, with unscheduled tasks last
+    Due,
+    /// Creation time
+    Created,
+    /// Title, ignoring case
+    Title,
+    /// Priority, most important first
+    Priority,
+    /// Completion time, most recent first, with open tasks last
+    Completed,
+    /// Last change, most recent first
+    Modified,
+}
+
+impl SortKey {
+    /// Name shown in the task list title
+    pub fn name(&self) -> &'static str {
+        match self {
+            SortKey::Manual => "manual",
+            SortKey::Due => "due",
+            SortKey::Created => "created",
+            SortKey::Title => "title",
+            SortKey::Priority => "priority",
+            SortKey::Completed => "completed",
+            SortKey::Modified => "modified",
+        }
+    }
+
+    /// The key after this one in the sort cycle
+    pub fn next(self) -> Self {
+        match self {
+            SortKey::Manual => SortKey::Due,
+            SortKey::Due => SortKey::Created,
+            SortKey::Created => SortKey::Title,
+            SortKey::Title => SortKey::Priority,
+            SortKey::Priority => SortKey::Completed,
+            SortKey::Completed => SortKey::Modified,
+            SortKey::Modified => SortKey::Manual,
+        }
+    }
+}
+
+/// How much energy a task takes, for GTD-style filtering by available energy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Energy {
+    Low,
+    Medium,
+    High,
+}
+
+impl Energy {
+    /// Name used in commands and queries (e.g. `:energy low`)
+    pub fn name(&self) -> &'static str {
+        match self {
+            Energy::Low => "low",
+            Energy::Medium => "medium",
+            Energy::High => "high",
+        }
+    }
+
+    /// Look up an energy level by name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "low" => Some(Energy::Low),
+            "medium" => Some(Energy::Medium),
+            "high" => Some(Energy::High),
+            _ => None,
+        }
+    }
+
+    /// Compact indicator shown in the task list
+    pub fn glyph(&self) -> &'static str {
+        match self {
+            Energy::Low => "▁",
+            Energy::Medium => "▄",
+            Energy::High => "█",
+        }
+    }
+}
+
+/// How important a task is, stepped through with the priority cycle key
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Priority {
+    Low,
+    #[default]
+    Medium,
+    High,
+}
+
+impl Priority {
+    /// Name used in commands and queries (e.g. `:priority high`)
+    pub fn name(&self) -> &'static str {
+        match self {
+            Priority::Low => "low",
+            Priority::Medium => "medium",
+            Priority::High => "high",
+        }
+    }
+
+    /// Look up a priority by name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "low" => Some(Priority::Low),
+            "medium" => Some(Priority::Medium),
+            "high" => Some(Priority::High),
+            _ => None,
+        }
+    }
+
+    /// Short label shown in the task list's "Pri" column
+    pub fn label(&self) -> &'static str {
+        match self {
+            Priority::Low => "low",
+            Priority::Medium => "med",
+            Priority::High => "HIGH",
+        }
+    }
+
+    /// The priority after this one in the cycle, wrapping from high to low
+    pub fn next(self) -> Self {
+        match self {
+            Priority::Low => Priority::Medium,
+            Priority::Medium => Priority::High,
+            Priority::High => Priority::Low,
+        }
+    }
+}
+
+/// Workflow state of a task, stepped through with the status cycle key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    Todo,
+    InProgress,
+    Done,
+}
+
+impl TaskStatus {
+    /// Status that follows this one in `cycle`, wrapping around
+    ///
+    /// A status missing from the cycle moves to its first entry; an empty
+    /// cycle leaves the status unchanged.
+    pub fn next_in(self, cycle: &[TaskStatus]) -> TaskStatus {
+        match cycle.iter().position(|&status| status == self) {
+            Some(pos) => cycle[(pos + 1) % cycle.len()],
+            None => cycle.first().copied().unwrap_or(self),
+        }
+    }
+}
+
+/// How often a recurring task comes back
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Recurrence {
+    Daily,
+    Weekly,
+    Monthly,
+    EveryNDays(u32),
+}
+
+impl Recurrence {
+    /// Name used in commands (e.g. `:repeat weekly`, `:repeat 3d`)
+    pub fn name(&self) -> String {
+        match self {
+            Recurrence::Daily => "daily".to_string(),
+            Recurrence::Weekly => "weekly".to_string(),
+            Recurrence::Monthly => "monthly".to_string(),
+            Recurrence::EveryNDays(days) => format!("{days}d"),
+        }
+    }
+
+    /// Look up a recurrence by name; `Nd` means every N days (N > 0)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "daily" => Some(Recurrence::Daily),
+            "weekly" => Some(Recurrence::Weekly),
+            "monthly" => Some(Recurrence::Monthly),
+            _ => name
+                .strip_suffix('d')?
+                .parse()
+                .ok()
+                .filter(|&days| days > 0)
+                .map(Recurrence::EveryNDays),
+        }
+    }
+
+    /// The occurrence one interval after `time`, at the same local time of day
+    ///
+    /// Monthly recurrences land on the same day of the next month, or on its
+    /// last day when the month is shorter. `None` if the result can't be
+    /// represented.
+    pub fn next_after(self, time: SystemTime) -> Option<SystemTime> {
+        let local = DateTime::<Local>::from(time).naive_local();
+        let next = match self {
+            Recurrence::Daily => local.checked_add_days(Days::new(1)),
+            Recurrence::Weekly => local.checked_add_days(Days::new(7)),
+            Recurrence::Monthly => local.checked_add_months(Months::new(1)),
+            Recurrence::EveryNDays(days) => local.checked_add_days(Days::new(days.into())),
+        }?;
+        Local
+            .from_local_datetime(&next)
+            .earliest()
+            .map(SystemTime::from)
+    }
+}
+
+/// A checklist item inside a task
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Subtask {
+    pub title: String,
+    #[serde(default)]
+    pub completed: bool,
+}
+
+/// Where a task was created, kept for debugging and analytics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TaskSource {
+    /// Created in the interactive terminal UI (also assumed for older tasks)
+    #[default]
+    Tui,
+    /// Added non-interactively with `wimm add`
+    Cli,
+    /// Brought in from another system or file
+    Import,
 }
 
 /// Represents a single task in the task management system
 ///
 /// Tasks are the core entity of the application, containing all information
 /// needed to track work items including scheduling, completion status, and metadata.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Task {
     /// Unique identifier for the task (typically a UUID)
     pub id: String,
     /// Short, descriptive name for the task
     pub title: String,
-    /// Detailed description or notes about the task
+    /// Short, single-line description of the task
     pub description: String,
+    /// Longer freeform notes, which may span several lines
+    #[serde(default)]
+    pub notes: String,
     /// Whether the task has been completed
     pub completed: bool,
+    /// When the task was last completed; `None` while open and for tasks
+    /// completed before this was recorded
+    #[serde(default)]
+    pub completed_at: Option<SystemTime>,
     /// When the task was created (immutable timestamp)
     pub created_at: SystemTime,
+    /// When the task was last changed; read it through [`Task::last_modified`]
+    ///
+    /// Records saved before this field existed load it as the Unix epoch.
+    #[serde(default = "unix_epoch")]
+    pub modified_at: SystemTime,
     /// Optional deadline - when the task should be completed
     pub due: Option<SystemTime>,
     /// Optional defer date - when to start working on the task (GTD-style)
     pub defer_until: Option<SystemTime>,
+    /// Parked on the someday/maybe list: excluded from urgency and the today view
+    #[serde(default)]
+    pub someday: bool,
+    /// Free-form labels for grouping related tasks
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Energy the task needs, if rated
+    #[serde(default)]
+    pub energy: Option<Energy>,
+    /// How important the task is; records saved before this field existed load as medium
+    #[serde(default)]
+    pub priority: Priority,
+    /// Entry point the task was created from
+    #[serde(default)]
+    pub source: TaskSource,
+    /// Link to where the task came from, such as an imported issue
+    #[serde(default)]
+    pub url: Option<String>,
+    /// The one project the task belongs to, alongside any number of tags
+    #[serde(default)]
+    pub project: Option<String>,
+    /// When the task was marked as waiting on someone else, if it is
+    #[serde(default)]
+    pub waiting_since: Option<SystemTime>,
+    /// Whether work on the (open) task has started
+    #[serde(default)]
+    pub in_progress: bool,
+    /// Checklist of smaller steps, in order
+    #[serde(default)]
+    pub subtasks: Vec<Subtask>,
+    /// ID of a task this one stays deferred behind until it is completed
+    #[serde(default)]
+    pub defer_until_task: Option<String>,
+    /// How often the task comes back, for tasks that repeat
+    #[serde(default)]
+    pub recurrence: Option<Recurrence>,
+    /// ID of the next occurrence added when this task was last completed
+    #[serde(default)]
+    pub next_occurrence_id: Option<String>,
+    /// How long before the due date to remind, overriding `[notifications] lead_min`
+    #[serde(default)]
+    pub reminder_lead: Option<Duration>,
+}
+
+impl Task {
+    /// Create a new open task with a fresh ID and no dates
+    pub fn new(title: &str, source: TaskSource, created_at: SystemTime) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            title: title.to_string(),
+            description: String::new(),
+            completed: false,
+            created_at,
+            due: None,
+            defer_until: None,
+            someday: false,
+            tags: Vec::new(),
+            energy: None,
+            source,
+            url: None,
+            notes: String::new(),
+            project: None,
+            waiting_since: None,
+            in_progress: false,
+            subtasks: Vec::new(),
+            defer_until_task: None,
+            recurrence: None,
+            modified_at: created_at,
+            reminder_lead: None,
+            next_occurrence_id: None,
+            completed_at: None,
+            priority: Priority::Medium,
+        }
+    }
+
+    /// The fresh task that replaces this one when it is completed, if it repeats
+    ///
+    /// The copy keeps the title, description, notes, tags and other settings,
+    /// with every subtask reopened. Its due and defer dates are one interval
+    /// after this task's, so an overdue task's next occurrence follows the
+    /// original schedule rather than the completion time. Dates that are unset
+    /// stay unset.
+    pub fn next_occurrence(&self, now: SystemTime) -> Option<Task> {
+        let recurrence = self.recurrence?;
+        let advance =
+            |date: Option<SystemTime>| date.map(|d| recurrence.next_after(d).unwrap_or(d));
+        Some(Task {
+            description: self.description.clone(),
+            notes: self.notes.clone(),
+            due: advance(self.due),
+            defer_until: advance(self.defer_until),
+            tags: self.tags.clone(),
+            energy: self.energy,
+            url: self.url.clone(),
+            project: self.project.clone(),
+            subtasks: self
+                .subtasks
+                .iter()
+                .map(|subtask| Subtask {
+                    title: subtask.title.clone(),
+                    completed: false,
+                })
+                .collect(),
+            recurrence: Some(recurrence),
+            reminder_lead: self.reminder_lead,
+            priority: self.priority,
+            ..Task::new(&self.title, self.source, now)
+        })
+    }
+
+    /// Workflow state; a completed task is done whether or not it was started
+    pub fn status(&self) -> TaskStatus {
+        if self.completed {
+            TaskStatus::Done
+        } else if self.in_progress {
+            TaskStatus::InProgress
+        } else {
+            TaskStatus::Todo
+        }
+    }
+
+    /// Move the task to a workflow state
+    pub fn set_status(&mut self, status: TaskStatus) {
+        self.completed = status == TaskStatus::Done;
+        self.in_progress = status == TaskStatus::InProgress;
+    }
+
+    /// Whether the task has a due date
+    pub fn is_scheduled(&self) -> bool {
+        self.due.is_some()
+    }
+
+    /// Whether the task's defer date is still in the future, or it waits on another task
+    pub fn is_deferred(&self, now: SystemTime) -> bool {
+        self.defer_until_task.is_some() || self.defer_until.is_some_and(|defer| now < defer)
+    }
+
+    /// Hash of what makes two tasks the same work: title, description and due date
+    ///
+    /// Ids, timestamps and status are left out, so a task imported twice hashes
+    /// the same even though each copy got a fresh id. The hash is only stable
+    /// within one run of the program; don't store it.
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        self.title.hash(&mut hasher);
+        self.description.hash(&mut hasher);
+        self.due.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// When the task was last changed, falling back to its creation for old records
+    pub fn last_modified(&self) -> SystemTime {
+        self.modified_at.max(self.created_at)
+    }
+
+    /// When the task was completed, falling back to its last change for old records
+    ///
+    /// Only meaningful for completed tasks.
+    pub fn finished_at(&self) -> SystemTime {
+        self.completed_at.unwrap_or_else(|| self.last_modified())
+    }
+
+    /// Whether an open task has gone untouched for at least `review_days` days
+    ///
+    /// Age counts from the last change to the task. Someday tasks are reviewed
+    /// on their own schedule and never count as stale; a threshold of 0
+    /// turns the rule off.
+    pub fn is_stale(&self, now: SystemTime, review_days: u64) -> bool {
+        self.is_urgent_candidate()
+            && review_days > 0
+            && now
+                .duration_since(self.last_modified())
+                .is_ok_and(|age| age >= Duration::from_secs(review_days * 24 * 60 * 60))
+    }
+
+    /// Whether an open task has been waiting for at least `nudge_days` days
+    ///
+    /// A threshold of 0 turns the nudge off.
+    pub fn is_waiting_too_long(&self, now: SystemTime, nudge_days: u64) -> bool {
+        !self.completed
+            && nudge_days > 0
+            && self.waiting_since.is_some_and(|since| {
+                now.duration_since(since)
+                    .is_ok_and(|waited| waited >= Duration::from_secs(nudge_days * 24 * 60 * 60))
+            })
+    }
+
+    /// Whether an open task has passed its due date
+    ///
+    /// Completed and someday tasks are never considered overdue.
+    pub fn is_overdue(&self, now: SystemTime) -> bool {
+        self.is_urgent_candidate() && self.due.is_some_and(|due| due < now)
+    }
+
+    /// Whether an open task is due before the end of the local day (including overdue)
+    ///
+    /// Completed and someday tasks are never considered due today.
+    pub fn is_due_today(&self, now: SystemTime) -> bool {
+        self.is_urgent_candidate() && self.due.is_some_and(|due| due <= end_of_day(now))
+    }
+
+    /// Whether an open task is due within the next seven days (including today and overdue)
+    ///
+    /// Completed and someday tasks are never considered due this week.
+    pub fn is_due_this_week(&self, now: SystemTime) -> bool {
+        self.is_urgent_candidate() && self.days_until_due(now).is_some_and(|days| days < 7)
+    }
+
+    /// Calendar days from today until the due date, negative once overdue
+    ///
+    /// Counts local dates, so a task due later today is `0` and one due any
+    /// time tomorrow is `1`. `None` when the task has no due date.
+    pub fn days_until_due(&self, now: SystemTime) -> Option<i64> {
+        let due = DateTime::<Local>::from(self.due?).date_naive();
+        let today = DateTime::<Local>::from(now).date_naive();
+        Some((due - today).num_days())
+    }
+
+    /// When to remind about the task: its due date minus its lead time
+    ///
+    /// The task's own `reminder_lead` wins over `default_lead`. `None` for
+    /// completed tasks and tasks without a due date.
+    pub fn reminder_at(&self, default_lead: Duration) -> Option<SystemTime> {
+        if self.completed {
+            return None;
+        }
+        let due = self.due?;
+        let lead = self.reminder_lead.unwrap_or(default_lead);
+        Some(due.checked_sub(lead).unwrap_or(SystemTime::UNIX_EPOCH))
+    }
+
+    /// Only open tasks that are not parked on the someday list carry date urgency
+    fn is_urgent_candidate(&self) -> bool {
+        !self.completed && !self.someday
+    }
+}
+
+/// Parse a reminder lead time such as `30m`, `1h` or `2d`; a bare `0` means none
+pub fn parse_lead(input: &str) -> Option<Duration> {
+    if input == "0" {
+        return Some(Duration::ZERO);
+    }
+    let split = input.len().checked_sub(1)?;
+    let (amount, unit) = input.split_at(split);
+    let unit_secs = match unit {
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => return None,
+    };
+    let amount: u64 = amount.parse().ok()?;
+    Some(Duration::from_secs(amount.checked_mul(unit_secs)?))
+}
+
+/// Parse a signed date offset such as `+7d`, `-2w`, `3h` or `90m`; no sign means forward
+pub fn parse_offset(input: &str) -> Option<chrono::Duration> {
+    let (negative, magnitude) = match input.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, input.strip_prefix('+').unwrap_or(input)),
+    };
+    let magnitude = match magnitude.strip_suffix('w') {
+        Some(weeks) => parse_lead(&format!("{weeks}d"))?.checked_mul(7)?,
+        None => parse_lead(magnitude)?,
+    };
+    let offset = chrono::Duration::from_std(magnitude).ok()?;
+    Some(if negative { -offset } else { offset })
+}
+
+/// Show an offset the way [`parse_offset`] reads it, e.g. `+7d` or `-90m`
+pub fn format_offset(offset: chrono::Duration) -> String {
+    let sign = if offset < chrono::Duration::zero() {
+        '-'
+    } else {
+        '+'
+    };
+    format!(
+        "{sign}{}",
+        format_lead(offset.abs().to_std().unwrap_or_default())
+    )
+}
+
+/// Show a lead time in the largest unit that divides it evenly, as [`parse_lead`] reads it
+pub fn format_lead(lead: Duration) -> String {
+    let minutes = lead.as_secs() / 60;
+    if minutes == 0 {
+        "0".to_string()
+    } else if minutes % (24 * 60) == 0 {
+        format!("{}d", minutes / (24 * 60))
+    } else if minutes % 60 == 0 {
+        format!("{}h", minutes / 60)
+    } else {
+        format!("{minutes}m")
+    }
+}
+
+fn unix_epoch() -> SystemTime {
+    SystemTime::UNIX_EPOCH
+}
+
+/// The last instant of the local calendar day containing `now`
+///
+/// Falls back to `now` itself if the local midnight cannot be represented
+/// (e.g. around a DST transition).
+fn end_of_day(now: SystemTime) -> SystemTime {
+    let local_now = DateTime::<Local>::from(now);
+    local_now
+        .date_naive()
+        .succ_opt()
+        .and_then(|tomorrow| tomorrow.and_hms_opt(0, 0, 0))
+        .and_then(|midnight| Local.from_local_datetime(&midnight).earliest())
+        .map(|midnight| SystemTime::from(midnight) - std::time::Duration::from_nanos(1))
+        .unwrap_or(now)
+}
+
+/// The parts of a session worth saving and restoring, without tasks or storage
+///
+/// [`AppState`] itself isn't serializable since it owns the storage backend;
+/// this snapshot holds just the mode, cursor and filters.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionSnapshot {
+    pub mode: Mode,
+    /// ID of the task under the cursor
+    pub cursor_task_id: Option<String>,
+    pub view: View,
+    pub query: Query,
+    pub urgency: Option<Urgency>,
 }
 
 /// Global application state containing all runtime data and configuration
@@ -51,7 +744,7 @@ pub struct Task {
 /// This structure holds everything needed to run the application, including
 /// the current UI state, loaded tasks, and the storage backend. It's generic
 /// over the storage type to allow different backends (memory vs persistent).
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug)]
 pub struct AppState<T: Db = MemoryStorage> {
     /// Current input mode (Normal or Insert)
     pub mode: Mode,
@@ -59,18 +752,44 @@ pub struct AppState<T: Db = MemoryStorage> {
     pub should_quit: bool,
     /// Buffer for text input in Insert mode
     pub input_buffer: String,
+    /// Characters of `input_buffer` after the caret (0 keeps it at the end, where typing appends)
+    pub caret_from_end: usize,
     /// Whether to display the help panel overlay
     pub show_help: bool,
+    /// Whether to display the detail pane for the task under the cursor
+    pub show_detail: bool,
+    /// Whether to display the about overlay (version, file locations, counts)
+    pub show_about: bool,
+    /// Whether to display the scratchpad beside the task list
+    pub show_scratch: bool,
+    /// Free-text notes that aren't tasks, kept in storage next to them
+    pub scratch: String,
+    /// Field of the cursor task shown in full in the cell popup (0 title, 1 description)
+    pub cell_popup: Option<usize>,
+    /// Distraction-free mode: only the task list, without title, status or borders
+    pub focus_mode: bool,
+    /// Show the task list under tag headers instead of as a flat table
+    pub group_by_tag: bool,
     /// All loaded tasks from storage
     pub tasks: Vec<Task>,
     /// Storage backend for persistence (generic for testability)
     pub store: T,
     /// Task currently being edited (if any)
     pub editing_task: Option<Task>,
-    /// Index of the field being edited (0=title, 1=description, etc.)
+    /// Position of the field being edited in the `ui.edit_fields` rotation
     pub editing_field: usize,
+    /// Whether the field text is selected, so the next typed character replaces it
+    pub select_pending: bool,
     /// Application configuration
     pub config: Config,
+    /// View currently filtering the task list
+    pub view: View,
+    /// Query narrowing the active view (empty matches everything)
+    pub query: Query,
+    /// Quick filter to tasks at least this urgent, cleared along with the query
+    pub urgency: Option<Urgency>,
+    /// Text searched for with `/` in titles and descriptions (empty shows every task)
+    pub search: String,
 }
 
 impl<T: Db> AppState<T> {
@@ -83,12 +802,25 @@ impl<T: Db> AppState<T> {
             mode: Mode::Normal,
             should_quit: false,
             input_buffer: String::new(),
+            caret_from_end: 0,
             show_help: false,
+            show_detail: false,
+            show_about: false,
+            show_scratch: false,
+            scratch: String::new(),
+            cell_popup: None,
+            focus_mode: false,
+            group_by_tag: false,
             tasks: Vec::new(),
             store,
             editing_task: None,
             editing_field: 0,
+            select_pending: false,
             config: Config::default(),
+            view: View::All,
+            query: Query::default(),
+            urgency: None,
+            search: String::new(),
         }
     }
 }
@@ -103,12 +835,25 @@ impl Default for AppState {
             mode: Mode::Normal,
             should_quit: false,
             input_buffer: String::new(),
+            caret_from_end: 0,
             show_help: false,
+            show_detail: false,
+            show_about: false,
+            show_scratch: false,
+            scratch: String::new(),
+            cell_popup: None,
+            focus_mode: false,
+            group_by_tag: false,
             tasks: Vec::new(),
             store: MemoryStorage::new(HashMap::new()),
             editing_task: None,
             editing_field: 0,
+            select_pending: false,
             config: Config::default(),
+            view: View::All,
+            query: Query::default(),
+            urgency: None,
+            search: String::new(),
         }
     }
 }
@@ -127,6 +872,23 @@ mod tests {
             created_at: SystemTime::now(),
             due: None,
             defer_until: None,
+            someday: false,
+            tags: Vec::new(),
+            energy: None,
+            source: TaskSource::Tui,
+            url: None,
+            notes: String::new(),
+            project: None,
+            waiting_since: None,
+            in_progress: false,
+            subtasks: Vec::new(),
+            defer_until_task: None,
+            recurrence: None,
+            modified_at: SystemTime::UNIX_EPOCH,
+            reminder_lead: None,
+            next_occurrence_id: None,
+            completed_at: None,
+            priority: Priority::Medium,
         }
     }
 
@@ -155,6 +917,23 @@ mod tests {
             created_at: now,
             due: None,
             defer_until: None,
+            someday: false,
+            tags: Vec::new(),
+            energy: None,
+            source: TaskSource::Tui,
+            url: None,
+            notes: String::new(),
+            project: None,
+            waiting_since: None,
+            in_progress: false,
+            subtasks: Vec::new(),
+            defer_until_task: None,
+            recurrence: None,
+            modified_at: SystemTime::UNIX_EPOCH,
+            reminder_lead: None,
+            next_occurrence_id: None,
+            completed_at: None,
+            priority: Priority::Medium,
         };
 
         assert_eq!(task.id, "test123");
@@ -180,6 +959,23 @@ mod tests {
             created_at: now,
             due: Some(due_date),
             defer_until: Some(defer_date),
+            someday: false,
+            tags: Vec::new(),
+            energy: None,
+            source: TaskSource::Tui,
+            url: None,
+            notes: String::new(),
+            project: None,
+            waiting_since: None,
+            in_progress: false,
+            subtasks: Vec::new(),
+            defer_until_task: None,
+            recurrence: None,
+            modified_at: SystemTime::UNIX_EPOCH,
+            reminder_lead: None,
+            next_occurrence_id: None,
+            completed_at: None,
+            priority: Priority::Medium,
         };
 
         assert!(task.completed);
@@ -187,6 +983,57 @@ mod tests {
         assert_eq!(task.defer_until, Some(defer_date));
     }
 
+    #[test]
+    fn test_waiting_too_long_after_threshold() {
+        let now = SystemTime::now();
+        let day = Duration::from_secs(24 * 60 * 60);
+        let mut task = create_test_task("1", "Chase invoice");
+        assert!(!task.is_waiting_too_long(now, 7));
+
+        task.waiting_since = Some(now - day * 6);
+        assert!(!task.is_waiting_too_long(now, 7));
+
+        task.waiting_since = Some(now - day * 8);
+        assert!(task.is_waiting_too_long(now, 7));
+        assert!(!task.is_waiting_too_long(now, 0));
+
+        task.completed = true;
+        assert!(!task.is_waiting_too_long(now, 7));
+    }
+
+    #[test]
+    fn test_status_cycle_order() {
+        let cycle = [TaskStatus::Todo, TaskStatus::InProgress, TaskStatus::Done];
+        assert_eq!(TaskStatus::Todo.next_in(&cycle), TaskStatus::InProgress);
+        assert_eq!(TaskStatus::InProgress.next_in(&cycle), TaskStatus::Done);
+        assert_eq!(TaskStatus::Done.next_in(&cycle), TaskStatus::Todo);
+
+        // Statuses left out of the cycle restart it
+        let short = [TaskStatus::Todo, TaskStatus::Done];
+        assert_eq!(TaskStatus::InProgress.next_in(&short), TaskStatus::Todo);
+        assert_eq!(TaskStatus::Done.next_in(&[]), TaskStatus::Done);
+    }
+
+    #[test]
+    fn test_task_status_round_trips() {
+        let mut task = create_test_task("1", "Write report");
+        assert_eq!(task.status(), TaskStatus::Todo);
+
+        task.set_status(TaskStatus::InProgress);
+        assert!(task.in_progress && !task.completed);
+        assert_eq!(task.status(), TaskStatus::InProgress);
+
+        task.set_status(TaskStatus::Done);
+        assert!(task.completed && !task.in_progress);
+        assert_eq!(task.status(), TaskStatus::Done);
+
+        // Older tasks without the field load as not started
+        let json = serde_json::to_string(&create_test_task("2", "Old")).unwrap();
+        let json = json.replace(",\"in_progress\":false", "");
+        let old: Task = serde_json::from_str(&json).unwrap();
+        assert_eq!(old.status(), TaskStatus::Todo);
+    }
+
     #[test]
     fn test_task_serialization() {
         let task = create_test_task("serialize_test", "Serialize Task");
@@ -319,6 +1166,369 @@ mod tests {
         assert!(app_state.show_help);
     }
 
+    #[test]
+    fn test_someday_task_is_never_overdue() {
+        let now = SystemTime::now();
+        let mut task = create_test_task("someday", "Someday Task");
+        task.due = Some(now - Duration::from_secs(3600));
+        assert!(task.is_overdue(now));
+        assert!(task.is_due_today(now));
+
+        task.someday = true;
+        assert!(!task.is_overdue(now));
+        assert!(!task.is_due_today(now));
+    }
+
+    #[test]
+    fn test_urgency_levels_nest() {
+        let now = SystemTime::from(Local.with_ymd_and_hms(2024, 6, 12, 12, 0, 0).unwrap());
+        let hour = Duration::from_secs(60 * 60);
+        let due_in = |title: &str, offset: Option<i64>| {
+            let mut task = create_test_task(title, title);
+            task.due = offset.map(|hours| {
+                if hours < 0 {
+                    now - hour * hours.unsigned_abs() as u32
+                } else {
+                    now + hour * hours as u32
+                }
+            });
+            task
+        };
+        let mut done = due_in("done", Some(-1));
+        done.completed = true;
+        let tasks = [
+            due_in("overdue", Some(-1)),
+            due_in("today", Some(1)),
+            due_in("in 3 days", Some(72)),
+            due_in("in 8 days", Some(192)),
+            due_in("no date", None),
+            done,
+        ];
+        let matching = |urgency: Urgency| -> Vec<&str> {
+            tasks
+                .iter()
+                .filter(|t| urgency.includes(t, now))
+                .map(|t| t.title.as_str())
+                .collect()
+        };
+
+        assert_eq!(matching(Urgency::Overdue), vec!["overdue"]);
+        assert_eq!(matching(Urgency::Today), vec!["overdue", "today"]);
+        assert_eq!(
+            matching(Urgency::Week),
+            vec!["overdue", "today", "in 3 days"]
+        );
+    }
+
+    #[test]
+    fn test_missing_completed_at_loads_as_none() {
+        let created_at = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
+        let mut task = Task::new("Done long ago", TaskSource::Tui, created_at);
+        task.completed = true;
+        task.modified_at = created_at + Duration::from_secs(60);
+
+        let mut json = serde_json::to_value(&task).unwrap();
+        json.as_object_mut().unwrap().remove("completed_at");
+        let old: Task = serde_json::from_value(json).unwrap();
+        assert_eq!(old.completed_at, None);
+        assert_eq!(old.finished_at(), created_at + Duration::from_secs(60));
+
+        task.completed_at = Some(created_at + Duration::from_secs(30));
+        assert_eq!(task.finished_at(), created_at + Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_missing_modified_at_falls_back_to_created_at() {
+        let created_at = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
+        let task = Task::new("New", TaskSource::Tui, created_at);
+        assert_eq!(task.modified_at, created_at);
+
+        let mut json = serde_json::to_value(&task).unwrap();
+        json.as_object_mut().unwrap().remove("modified_at");
+        let old: Task = serde_json::from_value(json).unwrap();
+        assert_eq!(old.modified_at, SystemTime::UNIX_EPOCH);
+        assert_eq!(old.last_modified(), created_at);
+    }
+
+    #[test]
+    fn test_parse_and_format_lead() {
+        assert_eq!(parse_lead("30m"), Some(Duration::from_secs(30 * 60)));
+        assert_eq!(parse_lead("1h"), Some(Duration::from_secs(60 * 60)));
+        assert_eq!(
+            parse_lead("2d"),
+            Some(Duration::from_secs(2 * 24 * 60 * 60))
+        );
+        assert_eq!(parse_lead("0"), Some(Duration::ZERO));
+        for bad in ["", "h", "1", "1w", "-1h", "1.5h"] {
+            assert_eq!(parse_lead(bad), None, "{bad:?}");
+        }
+
+        for text in ["0", "45m", "90m", "1h", "36h", "2d"] {
+            assert_eq!(format_lead(parse_lead(text).unwrap()), text);
+        }
+    }
+
+    #[test]
+    fn test_parse_offset() {
+        assert_eq!(parse_offset("+7d"), Some(chrono::Duration::days(7)));
+        assert_eq!(parse_offset("7d"), Some(chrono::Duration::days(7)));
+        assert_eq!(parse_offset("-2w"), Some(chrono::Duration::days(-14)));
+        assert_eq!(parse_offset("+3h"), Some(chrono::Duration::hours(3)));
+        assert_eq!(parse_offset("-90m"), Some(chrono::Duration::minutes(-90)));
+        for bad in ["", "+", "7", "+-7d", "7y", "w"] {
+            assert_eq!(parse_offset(bad), None, "{bad:?}");
+        }
+        assert_eq!(format_offset(chrono::Duration::days(-14)), "-14d");
+        assert_eq!(format_offset(chrono::Duration::minutes(90)), "+90m");
+    }
+
+    #[test]
+    fn test_reminder_at_prefers_task_lead() {
+        let due = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let hour = Duration::from_secs(60 * 60);
+        let mut task = Task::new("Call", TaskSource::Tui, SystemTime::UNIX_EPOCH);
+        assert_eq!(task.reminder_at(hour), None);
+
+        task.due = Some(due);
+        assert_eq!(task.reminder_at(Duration::ZERO), Some(due));
+        assert_eq!(task.reminder_at(hour), Some(due - hour));
+        task.reminder_lead = Some(2 * hour);
+        assert_eq!(task.reminder_at(hour), Some(due - 2 * hour));
+
+        task.completed = true;
+        assert_eq!(task.reminder_at(hour), None);
+    }
+
+    #[test]
+    fn test_content_hash_ignores_identity() {
+        let now = SystemTime::now();
+        let mut task = Task::new("Write report", TaskSource::Tui, now);
+        task.description = "Quarterly numbers".to_string();
+        task.due = Some(now + Duration::from_secs(3600));
+
+        let mut copy = Task::new(
+            "Write report",
+            TaskSource::Import,
+            now + Duration::from_secs(60),
+        );
+        copy.description = task.description.clone();
+        copy.due = task.due;
+        copy.completed = true;
+        assert_ne!(task.id, copy.id);
+        assert_eq!(task.content_hash(), copy.content_hash());
+
+        copy.due = None;
+        assert_ne!(task.content_hash(), copy.content_hash());
+        copy.due = task.due;
+        copy.description.clear();
+        assert_ne!(task.content_hash(), copy.content_hash());
+    }
+
+    #[test]
+    fn test_is_stale_threshold() {
+        let day = Duration::from_secs(24 * 60 * 60);
+        let now = SystemTime::UNIX_EPOCH + 100 * day;
+        let mut task = create_test_task("1", "Task");
+        task.created_at = now - 7 * day + Duration::from_secs(1);
+        assert!(!task.is_stale(now, 7));
+
+        task.created_at = now - 7 * day;
+        assert!(task.is_stale(now, 7));
+        assert!(!task.is_stale(now, 0));
+
+        task.someday = true;
+        assert!(!task.is_stale(now, 7));
+    }
+
+    #[test]
+    fn test_days_until_due() {
+        let now = SystemTime::now();
+        let day = std::time::Duration::from_secs(24 * 60 * 60);
+        let mut task = create_test_task("1", "Task");
+        assert_eq!(task.days_until_due(now), None);
+
+        task.due = Some(now);
+        assert_eq!(task.days_until_due(now), Some(0));
+        task.due = Some(now + 3 * day);
+        assert_eq!(task.days_until_due(now), Some(3));
+        task.due = Some(now - 2 * day);
+        assert_eq!(task.days_until_due(now), Some(-2));
+    }
+
+    #[test]
+    fn test_view_includes() {
+        let now = SystemTime::now();
+        let mut due_today = create_test_task("1", "Due today");
+        due_today.due = Some(now);
+        let mut parked = create_test_task("2", "Parked");
+        parked.someday = true;
+        parked.due = Some(now);
+        let unscheduled = create_test_task("3", "Unscheduled");
+
+        assert!(View::All.includes(&parked, now));
+        assert!(View::Today.includes(&due_today, now));
+        assert!(!View::Today.includes(&parked, now));
+        assert!(!View::Today.includes(&unscheduled, now));
+        assert!(View::Someday.includes(&parked, now));
+        assert!(!View::Someday.includes(&due_today, now));
+    }
+
+    #[test]
+    fn test_is_scheduled_and_unscheduled_view() {
+        let now = SystemTime::now();
+        let unscheduled = create_test_task("1", "No date");
+        let mut scheduled = create_test_task("2", "Has date");
+        scheduled.due = Some(now);
+        let mut parked = create_test_task("3", "Parked");
+        parked.someday = true;
+        let mut done = create_test_task("4", "Done");
+        done.completed = true;
+
+        assert!(!unscheduled.is_scheduled());
+        assert!(scheduled.is_scheduled());
+        assert!(View::Unscheduled.includes(&unscheduled, now));
+        assert!(!View::Unscheduled.includes(&scheduled, now));
+        assert!(!View::Unscheduled.includes(&parked, now));
+        assert!(!View::Unscheduled.includes(&done, now));
+    }
+
+    #[test]
+    fn test_priority_cycles_and_defaults_for_old_tasks() {
+        assert_eq!(Priority::default(), Priority::Medium);
+        assert_eq!(Priority::Medium.next(), Priority::High);
+        assert_eq!(Priority::High.next(), Priority::Low);
+        assert!(Priority::High > Priority::Medium && Priority::Medium > Priority::Low);
+        for priority in [Priority::Low, Priority::Medium, Priority::High] {
+            assert_eq!(Priority::from_name(priority.name()), Some(priority));
+        }
+
+        let mut task = Task::new("Old", TaskSource::Tui, SystemTime::UNIX_EPOCH);
+        task.priority = Priority::High;
+        let mut json = serde_json::to_value(&task).unwrap();
+        assert_eq!(json["priority"], "high");
+        json.as_object_mut().unwrap().remove("priority");
+        let old: Task = serde_json::from_value(json).unwrap();
+        assert_eq!(old.priority, Priority::Medium);
+    }
+
+    #[test]
+    fn test_energy_names_round_trip() {
+        for energy in [Energy::Low, Energy::Medium, Energy::High] {
+            assert_eq!(Energy::from_name(energy.name()), Some(energy));
+        }
+        assert_eq!(Energy::from_name("extreme"), None);
+    }
+
+    #[test]
+    fn test_recurrence_names_round_trip() {
+        for recurrence in [
+            Recurrence::Daily,
+            Recurrence::Weekly,
+            Recurrence::Monthly,
+            Recurrence::EveryNDays(3),
+        ] {
+            assert_eq!(Recurrence::from_name(&recurrence.name()), Some(recurrence));
+        }
+        assert_eq!(Recurrence::from_name("0d"), None);
+        assert_eq!(Recurrence::from_name("yearly"), None);
+    }
+
+    #[test]
+    fn test_recurrence_next_after_keeps_time_of_day() {
+        let local = |y, m, d| SystemTime::from(Local.with_ymd_and_hms(y, m, d, 9, 30, 0).unwrap());
+        let start = local(2024, 1, 31);
+        assert_eq!(Recurrence::Daily.next_after(start), Some(local(2024, 2, 1)));
+        assert_eq!(
+            Recurrence::Weekly.next_after(start),
+            Some(local(2024, 2, 7))
+        );
+        // Clamped to the end of a shorter month
+        assert_eq!(
+            Recurrence::Monthly.next_after(start),
+            Some(local(2024, 2, 29))
+        );
+        assert_eq!(
+            Recurrence::EveryNDays(10).next_after(start),
+            Some(local(2024, 2, 10))
+        );
+    }
+
+    #[test]
+    fn test_next_occurrence_copies_task_with_advanced_dates() {
+        let local = |y, m, d| SystemTime::from(Local.with_ymd_and_hms(y, m, d, 9, 30, 0).unwrap());
+        let now = local(2024, 3, 20);
+        let mut task = Task::new("Water plants", TaskSource::Tui, local(2024, 1, 1));
+        task.description = "Balcony too".to_string();
+        task.tags = vec!["home".to_string()];
+        task.priority = Priority::High;
+        task.subtasks = vec![Subtask {
+            title: "Fill can".to_string(),
+            completed: true,
+        }];
+        task.completed = true;
+        assert_eq!(task.next_occurrence(now), None);
+
+        let cases = [
+            (Recurrence::Daily, local(2024, 2, 1)),
+            (Recurrence::Weekly, local(2024, 2, 7)),
+            (Recurrence::Monthly, local(2024, 2, 29)),
+            (Recurrence::EveryNDays(3), local(2024, 2, 3)),
+        ];
+        for (recurrence, next_due) in cases {
+            // Overdue: the next due date follows the old one, not `now`
+            task.due = Some(local(2024, 1, 31));
+            task.recurrence = Some(recurrence);
+            let next = task.next_occurrence(now).unwrap();
+            assert_eq!(next.due, Some(next_due), "{recurrence:?}");
+            assert_ne!(next.id, task.id);
+            assert!(!next.completed && !next.subtasks[0].completed);
+            assert_eq!(next.created_at, now);
+            assert_eq!(
+                (next.title.as_str(), next.description.as_str()),
+                ("Water plants", "Balcony too")
+            );
+            assert_eq!(next.tags, task.tags);
+            assert_eq!(next.priority, Priority::High);
+            assert_eq!(next.recurrence, Some(recurrence));
+            assert_eq!(next.defer_until, None);
+        }
+    }
+
+    #[test]
+    fn test_view_names_round_trip() {
+        for view in [
+            View::All,
+            View::Today,
+            View::Someday,
+            View::Unscheduled,
+            View::Deferred,
+            View::Review,
+            View::Done,
+        ] {
+            assert_eq!(View::from_name(view.name()), Some(view));
+        }
+        assert_eq!(View::from_name("bogus"), None);
+    }
+
+    #[test]
+    fn test_task_someday_defaults_when_missing() {
+        let json = r#"{"id":"1","title":"Old","description":"","completed":false,
+            "created_at":{"secs_since_epoch":0,"nanos_since_epoch":0},"due":null,"defer_until":null}"#;
+        let task: Task = serde_json::from_str(json).unwrap();
+        assert!(!task.someday);
+        assert_eq!(task.source, TaskSource::Tui);
+        assert_eq!(task.notes, "");
+    }
+
+    #[test]
+    fn test_task_new_records_source() {
+        let now = SystemTime::now();
+        let task = Task::new("Imported", TaskSource::Import, now);
+        assert_eq!(task.source, TaskSource::Import);
+        assert_eq!(task.created_at, now);
+        assert!(!task.id.is_empty());
+    }
+
     #[test]
     fn test_appstate_properties() {
         let app_state = AppState::default();
@@ -0,0 +1,154 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use std::path::Path;
+
+use crate::types::Task;
+
+/// Floating overlay with the version, file locations and task counts,
+/// so users can report issues with exact context
+pub struct AboutPanel;
+
+impl AboutPanel {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn render(
+        &self,
+        f: &mut Frame,
+        area: Rect,
+        config_path: Option<&Path>,
+        db_path: Option<&Path>,
+        tasks: &[Task],
+    ) {
+        // Clear the background area to create floating effect
+        f.render_widget(Clear, area);
+
+        let about_text = self.create_about_content(config_path, db_path, tasks);
+
+        let about_paragraph = Paragraph::new(about_text)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(" About ")
+                    .title_style(
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                    .border_style(Style::default().fg(Color::Cyan))
+                    .style(Style::default().bg(Color::DarkGray)),
+            )
+            .style(Style::default().fg(Color::White).bg(Color::DarkGray));
+
+        f.render_widget(about_paragraph, area);
+    }
+
+    fn create_about_content(
+        &self,
+        config_path: Option<&Path>,
+        db_path: Option<&Path>,
+        tasks: &[Task],
+    ) -> Vec<Line<'static>> {
+        let completed = tasks.iter().filter(|t| t.completed).count();
+        let display_path = |path: Option<&Path>, missing: &str| {
+            path.map(|p| p.display().to_string())
+                .unwrap_or_else(|| missing.to_string())
+        };
+
+        vec![
+            Line::from(Span::styled(
+                format!("wimm {}", env!("CARGO_PKG_VERSION")),
+                Style::default().add_modifier(Modifier::BOLD),
+            )),
+            Line::from(""),
+            about_line("Config", display_path(config_path, "(unknown)")),
+            about_line("Database", display_path(db_path, "(in memory)")),
+            about_line(
+                "Tasks",
+                format!(
+                    "{} total, {} open, {completed} completed",
+                    tasks.len(),
+                    tasks.len() - completed
+                ),
+            ),
+        ]
+    }
+}
+
+fn about_line(label: &str, value: String) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(
+            format!("{label:<10}"),
+            Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(value),
+    ])
+}
+
+impl Default for AboutPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TaskSource;
+    use ratatui::{backend::TestBackend, Terminal};
+    use std::time::SystemTime;
+
+    fn content_text(lines: &[Line]) -> Vec<String> {
+        lines.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn test_about_content_includes_version() {
+        let content = AboutPanel::new().create_about_content(None, None, &[]);
+        let text = content_text(&content);
+
+        assert_eq!(text[0], format!("wimm {}", env!("CARGO_PKG_VERSION")));
+        assert!(text.iter().any(|l| l.contains("(in memory)")));
+    }
+
+    #[test]
+    fn test_about_content_paths_and_counts() {
+        let mut done = Task::new("Done", TaskSource::Tui, SystemTime::now());
+        done.completed = true;
+        let tasks = vec![Task::new("Open", TaskSource::Tui, SystemTime::now()), done];
+        let content = AboutPanel::new().create_about_content(
+            Some(Path::new("/home/me/.config/wimm/config.toml")),
+            Some(Path::new("/home/me/.local/share/wimm/tasks.db")),
+            &tasks,
+        );
+        let text = content_text(&content);
+
+        assert!(text
+            .iter()
+            .any(|l| l.starts_with("Config") && l.ends_with("/home/me/.config/wimm/config.toml")));
+        assert!(text
+            .iter()
+            .any(|l| l.starts_with("Database") && l.ends_with("tasks.db")));
+        assert!(text
+            .iter()
+            .any(|l| l.ends_with("2 total, 1 open, 1 completed")));
+    }
+
+    #[test]
+    fn test_about_panel_render() {
+        let backend = TestBackend::new(60, 10);
+        let mut terminal = Terminal::new(backend).unwrap();
+
+        terminal
+            .draw(|f| AboutPanel::new().render(f, Rect::new(0, 0, 60, 10), None, None, &[]))
+            .unwrap();
+    }
+}
@@ -1,429 +1,4687 @@
 use chrono::{DateTime, Datelike, Local, NaiveDate, TimeZone, Weekday};
-use std::collections::HashSet;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::time::{Duration, SystemTime};
 
 use crate::{
+    config::{AfterComplete, EditField, FilterCursor, TagGrouping},
+    filter::Query,
     storage::{Db, DbError},
-    types::{AppState, Task},
+    time_tracking::TimeTracker,
+    types::{
+        parse_lead, parse_offset, AppState, Energy, Mode, Recurrence, SessionSnapshot, SortKey,
+        Subtask, Task, TaskSource, TaskStatus, Urgency, View,
+    },
+    ui::history::{Change, History},
 };
 use ratatui::widgets::TableState;
-use uuid::Uuid;
+use thiserror::Error;
+
+/// How long a just-completed row stays highlighted (two event loop ticks)
+pub const COMPLETION_FEEDBACK_TTL: Duration = Duration::from_millis(500);
+
+/// How long the "press u to undo" hint and undo/redo messages stay up
+pub const UNDO_HINT_TTL: Duration = Duration::from_secs(5);
+
+/// Command names offered by command-line completion
+const COMMAND_NAMES: &[&str] = &[
+    "after",
+    "all",
+    "deferfromdue",
+    "deferred",
+    "done",
+    "duefromdefer",
+    "energy",
+    "filter",
+    "group",
+    "project",
+    "remind",
+    "repeat",
+    "review",
+    "setenergy",
+    "setproject",
+    "shift-defer",
+    "shift-due",
+    "skip",
+    "someday",
+    "tag",
+    "today",
+    "unscheduled",
+    "wait",
+];
+
+/// Why date input could not be turned into a date
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum DateParseError {
+    #[error("Date too far in the future")]
+    TooFarInFuture,
+    #[error("Unrecognized date: {0}")]
+    Unrecognized(String),
+}
+
+/// Action held back until the user answers a y/n prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfirmAction {
+    /// Delete the selected tasks (or the one under the cursor)
+    DeleteTasks,
+}
+
+/// Where a caret movement key takes the caret in the input buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaretMove {
+    Left,
+    Right,
+    Home,
+    End,
+}
+
+/// One of a task's two dates, for operations that work on either
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateField {
+    Due,
+    Defer,
+}
+
+impl DateField {
+    /// Name used in commands and messages
+    pub fn name(&self) -> &'static str {
+        match self {
+            DateField::Due => "due",
+            DateField::Defer => "defer",
+        }
+    }
+
+    fn get(&self, task: &Task) -> Option<SystemTime> {
+        match self {
+            DateField::Due => task.due,
+            DateField::Defer => task.defer_until,
+        }
+    }
+
+    fn set(&self, task: &mut Task, time: SystemTime) {
+        match self {
+            DateField::Due => task.due = Some(time),
+            DateField::Defer => task.defer_until = Some(time),
+        }
+    }
+}
+
+/// A y/n prompt waiting for an answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PendingConfirm {
+    pub action: ConfirmAction,
+    /// When the prompt was shown, for `ui.confirm_timeout_secs`
+    pub asked_at: SystemTime,
+}
+
+/// Source of the current time, injectable so time-dependent behavior is testable
+pub type Clock = Box<dyn Fn() -> SystemTime>;
 
 pub struct App<D: Db> {
     pub state: AppState<D>,
     pub message: Option<String>,
     pub task_list_state: TableState,
-    task_selection: HashSet<usize>,
+    /// IDs of the tasks marked with `x`, so marks follow tasks when the list is reordered
+    task_selection: HashSet<String>,
+    /// First key of a two-key Normal mode sequence (e.g. `]` of `]d`)
+    pub pending_key: Option<char>,
+    /// The leader key was pressed and the next key picks a `<leader>` binding
+    pub leader_pending: bool,
+    /// y/n prompt currently shown in the status bar, if any
+    pub pending_confirm: Option<PendingConfirm>,
+    clock: Clock,
+    /// When the last checkpoint ran (or the app started)
+    last_checkpoint: SystemTime,
+    /// Reminders falling after this time have not been shown yet
+    last_reminder_check: SystemTime,
+    /// Just-completed task IDs and when their highlight expires
+    completion_feedback: HashMap<String, SystemTime>,
+    /// When `message` disappears on its own, for transient messages
+    message_expires_at: Option<SystemTime>,
+    /// Changes to the task list that `u` can undo and Ctrl+R redo
+    history: History,
+    /// A task created with `o`/`O` that has not been saved yet
+    unsaved_task: Option<String>,
+    /// Time the list's membership and order are computed at, while frozen between actions
+    list_time: Option<SystemTime>,
+    /// IDs of tasks flagged for the review view, from [`App::flag_stale_for_review`]
+    review: HashSet<String>,
+    /// Field the task list is ordered by
+    pub sort_key: SortKey,
+    /// Whether the sort runs from highest to lowest
+    pub sort_desc: bool,
+    /// Timer started on a task with `T`, and the sessions timed so far
+    time_tracker: TimeTracker,
 }
 
 impl<D: Db> App<D> {
     pub fn new(state: AppState<D>) -> Self {
-        Self {
+        Self::with_clock(state, Box::new(SystemTime::now))
+    }
+
+    /// Create an app that reads the current time from `clock` instead of the system
+    ///
+    /// The task list opens in the configured `ui.default_view`, and any
+    /// warnings from loading the configuration show as the first message.
+    pub fn with_clock(mut state: AppState<D>, clock: Clock) -> Self {
+        state.view = state.config.ui.default_view;
+        let message = (!state.config.warnings.is_empty())
+            .then(|| format!("Warning: {}", state.config.warnings.join("; ")));
+        let last_checkpoint = clock();
+        let undo_depth = state.config.ui.undo_depth;
+        let mut app = Self {
             state,
-            message: None,
+            message,
             task_list_state: TableState::default(),
             task_selection: HashSet::default(),
+            pending_key: None,
+            leader_pending: false,
+            pending_confirm: None,
+            clock,
+            last_checkpoint,
+            last_reminder_check: last_checkpoint,
+            completion_feedback: HashMap::new(),
+            message_expires_at: None,
+            history: History::new(undo_depth),
+            unsaved_task: None,
+            list_time: None,
+            review: HashSet::new(),
+            sort_key: SortKey::Manual,
+            sort_desc: false,
+            time_tracker: TimeTracker::new(),
+        };
+        if app.state.view == View::Review {
+            app.flag_stale_for_review(app.now());
         }
+        app
+    }
+
+    /// The current time according to the app's clock
+    pub fn now(&self) -> SystemTime {
+        (self.clock)()
     }
 
     pub fn add_task(&mut self, title: &str) -> Result<(), DbError> {
         let new_task = self.create_task(title);
-        self.state.tasks.push(new_task);
-        self.sync_to_storage()
+        self.mutate_atomic(|tasks| tasks.push(new_task))
+    }
+
+    /// Change the task list all-or-nothing
+    ///
+    /// `func` runs on a copy of the tasks, which is persisted and only then
+    /// replaces `state.tasks`, so the list never shows a change that was not
+    /// saved. On a storage error the in-memory tasks are untouched and
+    /// storage is rewritten from them, best effort, before the error is
+    /// returned. Edited tasks get a new `modified_at`; added ones keep
+    /// theirs. Tasks that became completed get `completed_at`, and reopened
+    /// ones lose it. Saved changes are recorded for undo.
+    pub fn mutate_atomic<F, R>(&mut self, func: F) -> Result<R, DbError>
+    where
+        F: FnOnce(&mut Vec<Task>) -> R,
+    {
+        let mut tasks = self.state.tasks.clone();
+        let result = func(&mut tasks);
+
+        // A task still being created counts as added by this change
+        let mut before = self.state.tasks.clone();
+        if let Some(id) = &self.unsaved_task {
+            before.retain(|task| task.id != *id);
+        }
+        let now = self.now();
+        let old_by_id: HashMap<&str, &Task> = before.iter().map(|t| (t.id.as_str(), t)).collect();
+        for task in &mut tasks {
+            if old_by_id
+                .get(task.id.as_str())
+                .is_some_and(|old| *old != task)
+            {
+                task.modified_at = now;
+            }
+            let was_completed = old_by_id
+                .get(task.id.as_str())
+                .is_some_and(|old| old.completed);
+            if !task.completed {
+                task.completed_at = None;
+            } else if !was_completed && task.completed_at.is_none() {
+                task.completed_at = Some(now);
+            }
+        }
+        let change = Change::between(&before, &tasks);
+
+        self.commit_tasks(tasks)?;
+        if let Some(change) = change {
+            self.history.record(change);
+        }
+        Ok(result)
+    }
+
+    /// Persist `tasks` and make them the task list, leaving both untouched on error
+    ///
+    /// Storage is taken to hold the current list, less a task still being
+    /// created, so only tasks added, changed or removed since are written,
+    /// without reading storage back.
+    fn commit_tasks(&mut self, tasks: Vec<Task>) -> Result<(), DbError> {
+        let mut stored = self.state.tasks.clone();
+        if let Some(id) = &self.unsaved_task {
+            stored.retain(|task| task.id != *id);
+        }
+        if let Err(e) = sync_store(&mut self.state.store, &stored, &tasks) {
+            // Storage may hold part of the change; put back what the list still shows
+            let _ = sync_store(&mut self.state.store, &tasks, &stored);
+            return Err(e);
+        }
+        self.state.tasks = tasks;
+        self.unsaved_task = None;
+        Ok(())
     }
 
     pub fn toggle_task_completion(&mut self) -> Result<(), DbError> {
-        self.apply_to_selection(|t| t.completed = !t.completed);
+        let changes = self
+            .selection()
+            .map(|index| {
+                let status = if self.state.tasks[index].completed {
+                    TaskStatus::Todo
+                } else {
+                    TaskStatus::Done
+                };
+                (index, status)
+            })
+            .collect();
+        self.change_statuses(changes);
+        Ok(())
+    }
+
+    /// Move tasks to new statuses, with everything completing or reopening a task brings
+    ///
+    /// See [`apply_statuses`] for the changes to the task list. Tasks this
+    /// completes get the undo hint and completion feedback, and the cursor
+    /// follows `behavior.after_complete` if its task was one of them.
+    fn change_statuses(&mut self, changes: Vec<(usize, TaskStatus)>) {
+        let cursor_id = self
+            .cursor_task_index()
+            .map(|index| self.state.tasks[index].id.clone());
+        let cursor_row = self.task_list_state.selected();
+        let cascade = self.state.config.behavior.complete_cascades_subtasks;
+        let now = self.now();
+        // By ID, since reopening a task can remove its next occurrence from the list
+        let completing: Vec<String> = changes
+            .iter()
+            .filter(|&&(index, status)| {
+                status == TaskStatus::Done && !self.state.tasks[index].completed
+            })
+            .map(|&(index, _)| self.state.tasks[index].id.clone())
+            .collect();
+        let result = self.mutate_atomic(|tasks| apply_statuses(tasks, &changes, cascade, now));
+        let blocked = match result {
+            Ok(blocked) => blocked,
+            Err(e) => {
+                self.set_error_message(format!("Error syncing tasks: {e}"));
+                Vec::new()
+            }
+        };
+        self.clear_task_selection();
+
+        let completed: Vec<&Task> = self
+            .state
+            .tasks
+            .iter()
+            .filter(|task| task.completed && completing.contains(&task.id))
+            .collect();
+        if !completed.is_empty() {
+            let now = self.now();
+            let ids: Vec<String> = completed.iter().map(|task| task.id.clone()).collect();
+            let message = match completed.as_slice() {
+                [task] => format!("Completed '{}' — press u to undo", task.title),
+                tasks => format!("Completed {} tasks — press u to undo", tasks.len()),
+            };
+            if self.state.config.ui.completion_feedback {
+                for id in &ids {
+                    self.completion_feedback
+                        .insert(id.clone(), now + COMPLETION_FEEDBACK_TTL);
+                }
+            }
+            self.set_transient_message(message, now + UNDO_HINT_TTL);
+        }
+        match blocked.as_slice() {
+            [] => {}
+            [title] => self.set_error_message(format!(
+                "'{title}' has open subtasks; finish them before completing it"
+            )),
+            titles => self.set_error_message(format!(
+                "{} tasks have open subtasks; finish them before completing",
+                titles.len()
+            )),
+        }
+
+        let just_completed = cursor_id
+            .filter(|id| completing.contains(id))
+            .and_then(|id| {
+                self.state
+                    .tasks
+                    .iter()
+                    .position(|task| task.id == id && task.completed)
+            });
+        if let (Some(task_index), Some(row)) = (just_completed, cursor_row) {
+            match self.state.config.behavior.after_complete {
+                AfterComplete::Stay => self.cursor_to_task_index(task_index),
+                AfterComplete::Next => self.cursor_next_incomplete_task(row),
+                AfterComplete::None => {}
+            }
+        }
+    }
+
+    /// Turn the task at `child_index` into a subtask of the task at `parent_index`
+    ///
+    /// The child's title (and whether it was done) joins the end of the
+    /// parent's subtask list and the child leaves the task list.
+    pub fn demote_to_subtask(
+        &mut self,
+        child_index: usize,
+        parent_index: usize,
+    ) -> Result<(), DbError> {
+        let count = self.state.tasks.len();
+        if child_index == parent_index || child_index >= count || parent_index >= count {
+            return Ok(());
+        }
+        self.mutate_atomic(|tasks| {
+            let child = tasks.remove(child_index);
+            let parent_index = if parent_index > child_index {
+                parent_index - 1
+            } else {
+                parent_index
+            };
+            tasks[parent_index].subtasks.push(Subtask {
+                title: child.title,
+                completed: child.completed,
+            });
+        })?;
         self.clear_task_selection();
         Ok(())
     }
 
-    pub fn delete_tasks(&mut self) -> Result<(), DbError> {
-        let mut indices: Vec<usize> = self.selection().collect();
-        indices.sort();
+    /// Demote the task under the cursor into the task shown above it
+    pub fn demote_cursor_task(&mut self) -> Result<(), DbError> {
+        let Some(row) = self.task_list_state.selected().filter(|&row| row > 0) else {
+            return Ok(());
+        };
+        let visible = self.row_task_indices();
+        let (Some(&child), Some(&parent)) = (visible.get(row), visible.get(row - 1)) else {
+            return Ok(());
+        };
+        self.demote_to_subtask(child, parent)?;
+        self.task_list_state.select(Some(row - 1));
+        Ok(())
+    }
+
+    /// Advance the selected tasks to the next status in `[ui] status_cycle`
+    ///
+    /// Reaching Done completes a task just as `!` does.
+    pub fn cycle_task_status(&mut self) {
+        let cycle = &self.state.config.ui.status_cycle;
+        let changes = self
+            .selection()
+            .map(|index| (index, self.state.tasks[index].status().next_in(cycle)))
+            .collect();
+        self.change_statuses(changes);
+    }
+
+    /// Defer the `x`-marked tasks until the task under the cursor is completed
+    pub fn defer_selection_until_cursor_task(&mut self) -> Result<(), String> {
+        let blocker = self
+            .cursor_task_index()
+            .ok_or_else(|| "No task under the cursor".to_string())?;
+        let dependents: Vec<usize> = self
+            .marked_indices()
+            .into_iter()
+            .filter(|&i| i != blocker)
+            .collect();
+        if dependents.is_empty() {
+            return Err("Mark tasks with x, then run :after on the task they wait for".to_string());
+        }
+        let blocker_id = self.state.tasks[blocker].id.clone();
+        self.mutate_atomic(|tasks| {
+            for index in dependents {
+                if let Some(task) = tasks.get_mut(index) {
+                    task.defer_until_task = Some(blocker_id.clone());
+                }
+            }
+        })
+        .map_err(|e| format!("Error updating tasks: {e}"))?;
+        self.clear_task_selection();
+        Ok(())
+    }
+
+    /// Set the due date of the selected tasks to their defer date
+    ///
+    /// Useful when a task should be done the day it becomes available.
+    /// Selected tasks without a defer date are left alone.
+    pub fn due_from_defer(&mut self) -> Result<(), String> {
+        self.copy_date("defer", |t| t.defer_until, |t, time| t.due = Some(time))
+    }
+
+    /// Set the defer date of the selected tasks to their due date plus `offset`
+    ///
+    /// A negative offset makes a task available that long before it is due.
+    /// Selected tasks without a due date are left alone.
+    pub fn defer_from_due(&mut self, offset: chrono::Duration) -> Result<(), String> {
+        self.copy_date(
+            "due",
+            |t| t.due.and_then(|due| offset_time(due, offset)),
+            |t, time| t.defer_until = Some(time),
+        )
+    }
+
+    /// Move the selected repeating tasks on to their next occurrence
+    ///
+    /// The due and defer dates each advance by one interval of the task's
+    /// recurrence, as if the current occurrence never happened: the task is
+    /// not completed. Tasks that don't repeat or have no dates are left alone.
+    pub fn skip_occurrence(&mut self) -> Result<(), String> {
+        let skippable = |task: &Task| {
+            !task.completed
+                && task.recurrence.is_some()
+                && (task.due.is_some() || task.defer_until.is_some())
+        };
+        let indices: Vec<usize> = self.selection().collect();
+        if !indices
+            .iter()
+            .filter_map(|&i| self.state.tasks.get(i))
+            .any(skippable)
+        {
+            return Err(match indices.as_slice() {
+                [index] => {
+                    let task = &self.state.tasks[*index];
+                    if task.recurrence.is_none() {
+                        format!("'{}' does not repeat", task.title)
+                    } else {
+                        format!("'{}' has no date to skip", task.title)
+                    }
+                }
+                _ => "No repeating tasks to skip".to_string(),
+            });
+        }
+        self.apply_to_selection(|task| {
+            let Some(recurrence) = task.recurrence.filter(|_| skippable(task)) else {
+                return;
+            };
+            let advance =
+                |date: Option<SystemTime>| date.map(|d| recurrence.next_after(d).unwrap_or(d));
+            task.due = advance(task.due);
+            task.defer_until = advance(task.defer_until);
+        });
+        Ok(())
+    }
 
-        for index in indices.iter().rev() {
-            if *index < self.state.tasks.len() {
-                self.state.tasks.swap_remove(*index);
+    /// Move the `field` date of the open tasks at `indices` by `offset`
+    ///
+    /// Tasks without that date, and completed tasks, are left alone. Returns
+    /// how many dates moved; if any would leave the representable range,
+    /// nothing changes and an error is returned.
+    pub fn shift_dates(
+        &mut self,
+        indices: &[usize],
+        field: DateField,
+        offset: chrono::Duration,
+    ) -> Result<usize, String> {
+        let mut shifted = Vec::new();
+        for &index in indices {
+            let Some(task) = self.state.tasks.get(index).filter(|t| !t.completed) else {
+                continue;
+            };
+            let Some(date) = field.get(task) else {
+                continue;
+            };
+            let moved = offset_time(date, offset).ok_or_else(|| {
+                format!(
+                    "Can't move the {} date of '{}' that far",
+                    field.name(),
+                    task.title
+                )
+            })?;
+            shifted.push((index, moved));
+        }
+        let count = shifted.len();
+        self.mutate_atomic(|tasks| {
+            for (index, moved) in shifted {
+                field.set(&mut tasks[index], moved);
             }
+        })
+        .map_err(|e| format!("Error syncing tasks: {e}"))?;
+        Ok(count)
+    }
+
+    /// Shift dates from the `shift-due`/`shift-defer` commands and report the result
+    ///
+    /// Applies to the `x`-marked tasks, or to every task in the list when none are marked.
+    fn shift_command(&mut self, field: DateField, args: &str) -> Result<(), String> {
+        let command = format!("shift-{}", field.name());
+        if args.is_empty() {
+            return Err(format!("Usage: {command} <+7d|-2w|+3h>"));
         }
-        self.sync_to_storage()?;
+        let offset = parse_offset(args)
+            .ok_or_else(|| format!("Expected an offset like +7d, got '{args}'"))?;
+        let indices: Vec<usize> = if self.task_selection.is_empty() {
+            self.visible_task_indices()
+        } else {
+            self.marked_indices()
+        };
+        let count = self.shift_dates(&indices, field, offset)?;
         self.clear_task_selection();
+        let noun = if count == 1 { "date" } else { "dates" };
+        self.set_error_message(format!("Moved {count} {} {noun} by {args}", field.name()));
         Ok(())
     }
 
-    pub fn quit(&mut self) {
-        self.state.should_quit = true;
+    fn copy_date(
+        &mut self,
+        source_name: &str,
+        source: impl Fn(&Task) -> Option<SystemTime>,
+        set: impl Fn(&mut Task, SystemTime),
+    ) -> Result<(), String> {
+        let indices: Vec<usize> = self.selection().collect();
+        let mut tasks = indices.iter().filter_map(|&i| self.state.tasks.get(i));
+        if !tasks.any(|t| source(t).is_some()) {
+            return Err(match indices.as_slice() {
+                [index] => format!(
+                    "'{}' has no {source_name} date",
+                    self.state.tasks[*index].title
+                ),
+                _ => format!("No {source_name} date to copy"),
+            });
+        }
+        self.apply_to_selection(|task| {
+            if let Some(time) = source(task) {
+                set(task, time);
+            }
+        });
+        Ok(())
     }
 
-    pub fn clear_input_buffer(&mut self) {
-        self.state.input_buffer.clear();
+    /// Revert the most recent change to the task list
+    ///
+    /// Returns whether there was anything to undo.
+    pub fn undo(&mut self) -> Result<bool, DbError> {
+        let Some(change) = self.history.next_undo() else {
+            return Ok(false);
+        };
+        let mut tasks = self.state.tasks.clone();
+        change.revert(&mut tasks);
+        self.commit_tasks(tasks)?;
+        self.history.mark_undone();
+        self.after_history_step("Undid last change");
+        Ok(true)
     }
 
-    pub fn add_to_input_buffer(&mut self, c: char) {
-        self.state.input_buffer.push(c);
+    /// Reapply the most recently undone change
+    ///
+    /// Returns whether there was anything to redo.
+    pub fn redo(&mut self) -> Result<bool, DbError> {
+        let Some(change) = self.history.next_redo() else {
+            return Ok(false);
+        };
+        let mut tasks = self.state.tasks.clone();
+        change.reapply(&mut tasks);
+        self.commit_tasks(tasks)?;
+        self.history.mark_redone();
+        self.after_history_step("Redid last change");
+        Ok(true)
     }
 
-    pub fn backspace_input_buffer(&mut self) {
-        self.state.input_buffer.pop();
+    /// Tidy up after undo or redo replaced tasks under the cursor and highlights
+    fn after_history_step(&mut self, message: &str) {
+        let tasks = &self.state.tasks;
+        self.completion_feedback
+            .retain(|id, _| tasks.iter().any(|t| t.id == *id && t.completed));
+        self.clear_task_selection();
+        let visible = self.row_task_indices().len();
+        if let Some(row) = self.task_list_state.selected() {
+            self.task_list_state
+                .select((visible > 0).then(|| row.min(visible - 1)));
+        }
+        let expires_at = self.now() + UNDO_HINT_TTL;
+        self.set_transient_message(message.to_string(), expires_at);
     }
 
-    pub fn set_error_message(&mut self, message: String) {
-        self.message = Some(message);
+    /// When the completion highlight of the task with `id` expires, if it is showing
+    pub fn completion_feedback(&self, id: &str) -> Option<SystemTime> {
+        self.completion_feedback.get(id).copied()
     }
 
-    pub fn clear_error_message(&mut self) {
-        self.message = None;
+    /// Move the cursor to the first incomplete task below `from_row`, if any
+    fn cursor_next_incomplete_task(&mut self, from_row: usize) {
+        let next_row = self
+            .row_task_indices()
+            .iter()
+            .enumerate()
+            .skip(from_row + 1)
+            .find(|(_, &i)| !self.state.tasks[i].completed)
+            .map(|(row, _)| row);
+        if next_row.is_some() {
+            self.task_list_state.select(next_row);
+        }
     }
 
-    pub fn get_error_message(&self) -> Option<&String> {
-        self.message.as_ref()
+    /// Move the selected tasks on or off the someday/maybe list
+    pub fn toggle_someday(&mut self) -> Result<(), DbError> {
+        self.apply_to_selection(|t| t.someday = !t.someday);
+        Ok(())
     }
 
-    pub fn parse_date_input(&self, input: &str, is_due_date: bool) -> Option<SystemTime> {
-        let input = input.trim().to_lowercase();
-        if input.is_empty() || input == "-" {
-            return None;
+    /// Step the selected tasks to their next priority, wrapping from high to low
+    pub fn cycle_priority(&mut self) -> Result<(), DbError> {
+        self.apply_to_selection(|t| t.priority = t.priority.next());
+        Ok(())
+    }
+
+    /// Start timing the task under the cursor, or stop if it is the one being timed
+    ///
+    /// Starting stops a timer running on another task. Every finished session
+    /// is saved to storage; if that fails, the timers are left as they were.
+    pub fn toggle_timer(&mut self) -> Result<(), DbError> {
+        let Some(task) = self.cursor_task_index().map(|i| &self.state.tasks[i]) else {
+            return Ok(());
+        };
+        let (id, title) = (task.id.clone(), task.title.clone());
+        let now = self.now();
+        let mut tracker = self.time_tracker.clone();
+        let message = if tracker.get_active_timer().as_deref() == Some(id.as_str()) {
+            let elapsed = tracker
+                .stop_timer_at(&id, now)
+                .map_err(DbError::OperationFailed)?;
+            format!(
+                "Stopped timer on '{title}' after {}",
+                format_elapsed(elapsed)
+            )
+        } else {
+            tracker
+                .start_timer_at(&id, now)
+                .map_err(DbError::OperationFailed)?;
+            format!("Started timer on '{title}'")
+        };
+        self.commit_timers(tracker)?;
+        self.set_transient_message(message, now + UNDO_HINT_TTL);
+        Ok(())
+    }
+
+    /// Save the sessions `tracker` finished since `time_tracker`, and only then adopt it
+    fn commit_timers(&mut self, tracker: TimeTracker) -> Result<(), DbError> {
+        let finished = self.time_tracker.entries().len();
+        for entry in &tracker.entries()[finished..] {
+            self.state.store.save_time_entry(entry)?;
         }
+        self.time_tracker = tracker;
+        Ok(())
+    }
 
-        let now = SystemTime::now();
-        let local_now = DateTime::<Local>::from(now);
+    /// Status bar text for the running timer, e.g. `⏱ Working on "Write report" (12m)`
+    pub fn timer_status(&self) -> Option<String> {
+        let entry = self.time_tracker.active_entry()?;
+        let elapsed = self
+            .now()
+            .duration_since(entry.start_time)
+            .unwrap_or_default();
+        let title = self
+            .state
+            .tasks
+            .iter()
+            .find(|task| task.id == entry.task_id)
+            .map_or("a deleted task", |task| task.title.as_str());
+        Some(format!(
+            "⏱ Working on \"{title}\" ({})",
+            format_elapsed(elapsed)
+        ))
+    }
 
-        // Default hour based on date type: due dates at 5pm, defer dates at 8am
-        let default_hour = if is_due_date { 17 } else { 8 };
+    pub fn delete_tasks(&mut self) -> Result<(), DbError> {
+        // Resolve the selection to IDs first so removing one task can't shift another
+        let ids: HashSet<String> = self
+            .selection()
+            .filter_map(|i| self.state.tasks.get(i))
+            .map(|task| task.id.clone())
+            .collect();
 
-        // Handle simple keywords
-        match input.as_str() {
-            "today" => return Some(now),
-            "tomorrow" => {
-                let tomorrow = local_now.date_naive().succ_opt()?;
-                let tomorrow_dt = Local
-                    .from_local_datetime(&tomorrow.and_hms_opt(default_hour, 0, 0)?)
-                    .single()?;
-                return Some(tomorrow_dt.into());
-            }
-            "yesterday" => {
-                let yesterday = local_now.date_naive().pred_opt()?;
-                let yesterday_dt = Local
-                    .from_local_datetime(&yesterday.and_hms_opt(default_hour, 0, 0)?)
-                    .single()?;
-                return Some(yesterday_dt.into());
+        self.mutate_atomic(|tasks| {
+            tasks.retain(|task| !ids.contains(&task.id));
+            release_deferred_dependents(tasks);
+        })?;
+        self.clear_task_selection();
+        Ok(())
+    }
+
+    /// Ask the user to confirm `action` with y/n before running it
+    pub fn request_confirmation(&mut self, action: ConfirmAction) {
+        let prompt = match action {
+            ConfirmAction::DeleteTasks => {
+                let count = self.selection().count();
+                if count == 0 {
+                    return;
+                }
+                let noun = if count == 1 { "task" } else { "tasks" };
+                format!("Delete {count} {noun}? (y/n)")
             }
-            _ => {}
+        };
+        self.pending_confirm = Some(PendingConfirm {
+            action,
+            asked_at: self.now(),
+        });
+        self.set_error_message(prompt);
+    }
+
+    /// Run the action waiting for confirmation, if any
+    pub fn confirm_pending(&mut self) -> Result<(), DbError> {
+        let Some(pending) = self.pending_confirm.take() else {
+            return Ok(());
+        };
+        self.clear_error_message();
+        match pending.action {
+            ConfirmAction::DeleteTasks => self.delete_tasks(),
         }
+    }
 
-        // Handle weekday names
-        if let Some(target_weekday) = self.parse_weekday(&input) {
-            let current_weekday = local_now.weekday();
-            let days_ahead = (target_weekday.num_days_from_monday() as i64
-                - current_weekday.num_days_from_monday() as i64
-                + 7)
-                % 7;
-            let days_ahead = if days_ahead == 0 { 7 } else { days_ahead }; // Next occurrence
+    /// Dismiss the y/n prompt without running its action
+    pub fn cancel_confirmation(&mut self) {
+        if self.pending_confirm.take().is_some() {
+            self.clear_error_message();
+        }
+    }
 
-            let target_date = local_now.date_naive() + chrono::Duration::days(days_ahead);
-            let target_dt = Local
-                .from_local_datetime(&target_date.and_hms_opt(default_hour, 0, 0)?)
-                .single()?;
-            return Some(target_dt.into());
+    /// Whether the pending prompt has gone unanswered for `ui.confirm_timeout_secs`
+    fn confirmation_expired(&self, now: SystemTime) -> bool {
+        let timeout_secs = self.state.config.ui.confirm_timeout_secs;
+        match self.pending_confirm {
+            Some(pending) if timeout_secs > 0 => now
+                .duration_since(pending.asked_at)
+                .is_ok_and(|waited| waited >= Duration::from_secs(timeout_secs)),
+            _ => false,
         }
+    }
 
-        // Handle "next weekday"
-        if let Some(weekday_part) = input.strip_prefix("next ") {
-            if let Some(target_weekday) = self.parse_weekday(weekday_part) {
-                let current_weekday = local_now.weekday();
-                let days_ahead = (target_weekday.num_days_from_monday() as i64
-                    - current_weekday.num_days_from_monday() as i64
-                    + 14)
-                    % 7;
-                let days_ahead = if days_ahead == 0 { 7 } else { days_ahead } + 7; // Next week
+    /// Every tag used by any task, sorted for completion
+    pub fn all_tags(&self) -> BTreeSet<String> {
+        self.state
+            .tasks
+            .iter()
+            .flat_map(|t| t.tags.iter().cloned())
+            .collect()
+    }
 
-                let target_date = local_now.date_naive() + chrono::Duration::days(days_ahead);
-                let target_dt = Local
-                    .from_local_datetime(&target_date.and_hms_opt(default_hour, 0, 0)?)
-                    .single()?;
-                return Some(target_dt.into());
+    /// Every project assigned to any task, sorted for completion
+    pub fn all_projects(&self) -> BTreeSet<String> {
+        self.state
+            .tasks
+            .iter()
+            .filter_map(|t| t.project.clone())
+            .collect()
+    }
+
+    /// Add and remove tags on the tasks at `selection` (indices into `state.tasks`)
+    ///
+    /// Adding a tag a task already has and removing one it lacks are no-ops.
+    pub fn modify_tags(
+        &mut self,
+        selection: &[usize],
+        adds: &[String],
+        removes: &[String],
+    ) -> Result<(), DbError> {
+        self.mutate_atomic(|tasks| {
+            for &index in selection {
+                if let Some(task) = tasks.get_mut(index) {
+                    task.tags.retain(|tag| !removes.contains(tag));
+                    for tag in adds {
+                        if !task.tags.contains(tag) {
+                            task.tags.push(tag.clone());
+                        }
+                    }
+                }
+            }
+        })
+    }
+
+    /// Visible tasks bucketed by tag, as `(tag, indices into state.tasks)`
+    ///
+    /// Groups are sorted by tag with an "Untagged" group last. Depending on
+    /// `ui.tag_grouping`, a task with several tags is listed under each of
+    /// them or only under its first.
+    pub fn group_by_tag(&self) -> Vec<(String, Vec<usize>)> {
+        let mut groups: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        let mut untagged = Vec::new();
+        for index in self.visible_task_indices() {
+            let tags = &self.state.tasks[index].tags;
+            let tags = match self.state.config.ui.tag_grouping {
+                TagGrouping::Each => &tags[..],
+                TagGrouping::First => &tags[..tags.len().min(1)],
+            };
+            if tags.is_empty() {
+                untagged.push(index);
+            }
+            for tag in tags {
+                let group = groups.entry(tag.clone()).or_default();
+                if !group.contains(&index) {
+                    group.push(index);
+                }
             }
         }
 
-        // Handle relative dates like "2d", "1w", "3h"
-        if let Some(last_char) = input.chars().last() {
-            if let Ok(num) = input[..input.len() - 1].parse::<u64>() {
-                let duration = match last_char {
-                    'd' => Duration::from_secs(num * 24 * 60 * 60),
-                    'h' => Duration::from_secs(num * 60 * 60),
-                    'm' => Duration::from_secs(num * 60),
-                    'w' => Duration::from_secs(num * 7 * 24 * 60 * 60),
-                    _ => return None,
-                };
-                return now.checked_add(duration);
+        let mut grouped: Vec<(String, Vec<usize>)> = groups.into_iter().collect();
+        if !untagged.is_empty() {
+            grouped.push(("Untagged".to_string(), untagged));
+        }
+        grouped
+    }
+
+    /// Indices into `state.tasks` in the order the list shows them, one per row
+    ///
+    /// This is [`App::visible_task_indices`], or the groups of
+    /// [`App::group_by_tag`] one after another while grouping is on, so a
+    /// task under several tags has a row in each of them. The cursor moves
+    /// through these rows.
+    pub fn row_task_indices(&self) -> Vec<usize> {
+        if self.state.group_by_tag {
+            self.group_by_tag()
+                .into_iter()
+                .flat_map(|(_, indices)| indices)
+                .collect()
+        } else {
+            self.visible_task_indices()
+        }
+    }
+
+    /// Complete the last word of a command buffer from known names
+    ///
+    /// `+`/`-` arguments of `tag` complete from [`App::all_tags`] and the
+    /// argument of `project`/`setproject` from [`App::all_projects`]; the first
+    /// word completes from the command names. The first match in sorted order
+    /// wins; the buffer is unchanged when nothing matches.
+    pub fn complete_command(&mut self) {
+        let buffer = &self.state.input_buffer;
+        let (head, word) = match buffer.rfind(' ') {
+            Some(pos) => buffer.split_at(pos + 1),
+            None => ("", buffer.as_str()),
+        };
+
+        let completion = if head.is_empty() {
+            COMMAND_NAMES
+                .iter()
+                .filter(|name| name.starts_with(word))
+                .min()
+                .map(|name| name.to_string())
+        } else if head.starts_with("tag ") && !word.is_empty() {
+            let (sign, prefix) = word.split_at(1);
+            if sign == "+" || sign == "-" {
+                self.all_tags()
+                    .into_iter()
+                    .find(|tag| tag.starts_with(prefix))
+                    .map(|tag| format!("{sign}{tag}"))
+            } else {
+                None
             }
+        } else if head == "project " || head == "setproject " {
+            self.all_projects()
+                .into_iter()
+                .find(|project| project.starts_with(word))
+        } else {
+            None
+        };
+
+        if let Some(completion) = completion {
+            self.state.input_buffer = format!("{head}{completion}");
+            self.state.caret_from_end = 0;
         }
+    }
 
-        // Handle YYYY-MM-DD format
-        if let Ok(date) = NaiveDate::parse_from_str(&input, "%Y-%m-%d") {
-            let dt = Local
-                .from_local_datetime(&date.and_hms_opt(default_hour, 0, 0)?)
-                .single()?;
-            return Some(dt.into());
+    /// Show or hide the detail pane for the task under the cursor
+    pub fn toggle_detail(&mut self) {
+        self.state.show_detail = !self.state.show_detail && self.cursor_task_index().is_some();
+    }
+
+    /// Step the cell popup from the title to the description, then close it
+    pub fn cycle_cell_popup(&mut self) {
+        self.state.cell_popup = match self.state.cell_popup {
+            None if self.cursor_task_index().is_some() => Some(0),
+            Some(0) => Some(1),
+            _ => None,
+        };
+    }
+
+    /// Label and full, untruncated value of the cell shown in the popup
+    pub fn cell_popup_content(&self) -> Option<(&'static str, String)> {
+        let task = &self.state.tasks[self.cursor_task_index()?];
+        match self.state.cell_popup? {
+            0 => Some(("Title", task.title.clone())),
+            _ => Some(("Description", task.description.clone())),
         }
+    }
 
-        // Handle MM-DD format (current year)
-        if let Ok(date) =
-            NaiveDate::parse_from_str(&format!("{}-{}", local_now.year(), input), "%Y-%m-%d")
-        {
-            let dt = Local
-                .from_local_datetime(&date.and_hms_opt(default_hour, 0, 0)?)
-                .single()?;
-            return Some(dt.into());
+    /// Switch the task list to a different view, keeping the cursor near its task
+    pub fn set_view(&mut self, view: View) {
+        if view == View::Review {
+            self.flag_stale_for_review(self.now());
         }
+        self.change_filters(|state| state.view = view);
+    }
+
+    /// Flag open tasks untouched for `[maintenance] stale_review_days` for review
+    ///
+    /// Replaces any earlier flags and returns the flagged IDs in list order;
+    /// the `:review` view shows exactly these tasks.
+    pub fn flag_stale_for_review(&mut self, now: SystemTime) -> Vec<String> {
+        let review_days = self.state.config.maintenance.stale_review_days;
+        let flagged: Vec<String> = self
+            .state
+            .tasks
+            .iter()
+            .filter(|t| t.is_stale(now, review_days))
+            .map(|t| t.id.clone())
+            .collect();
+        self.review = flagged.iter().cloned().collect();
+        flagged
+    }
+
+    /// Narrow the task list with a query, keeping the cursor near its task
+    pub fn set_query(&mut self, query: Query) {
+        self.change_filters(|state| state.query = query);
+    }
+
+    /// Show only tasks with the cursor task's first tag, or drop the tag filter
+    ///
+    /// # Errors
+    /// Returns a message if there is no tag filter to drop and the task under
+    /// the cursor has no tags.
+    pub fn toggle_tag_filter(&mut self) -> Result<(), String> {
+        let tag = if self.state.query.tag.is_some() {
+            None
+        } else {
+            let tag = self
+                .cursor_task_index()
+                .and_then(|i| self.state.tasks[i].tags.first().cloned())
+                .ok_or_else(|| "Task has no tags to filter by".to_string())?;
+            Some(tag)
+        };
+        let query = Query {
+            tag,
+            ..self.state.query.clone()
+        };
+        self.set_query(query);
+        Ok(())
+    }
+
+    /// Snapshot of the mode, cursor and filters, for saving the session
+    pub fn session_snapshot(&self) -> SessionSnapshot {
+        SessionSnapshot {
+            mode: self.state.mode.clone(),
+            cursor_task_id: self
+                .cursor_task_index()
+                .map(|i| self.state.tasks[i].id.clone()),
+            view: self.state.view,
+            query: self.state.query.clone(),
+            urgency: self.state.urgency,
+        }
+    }
+
+    /// Bring back a session saved with [`App::session_snapshot`]
+    ///
+    /// The cursor returns to its task if that task is still listed. Insert
+    /// mode can't resume without the task being edited, so it restores as
+    /// Normal mode.
+    pub fn restore_session(&mut self, snapshot: SessionSnapshot) {
+        self.state.mode = match snapshot.mode {
+            Mode::Insert | Mode::Scratch | Mode::Search => Mode::Normal,
+            mode => mode,
+        };
+        self.state.view = snapshot.view;
+        self.state.query = snapshot.query;
+        self.state.urgency = snapshot.urgency;
+        self.task_list_state.select(None);
+        let cursor = snapshot
+            .cursor_task_id
+            .and_then(|id| self.state.tasks.iter().position(|t| t.id == id));
+        match cursor {
+            Some(index) => self.cursor_to_task_index(index),
+            None => self.cursor_first_task(),
+        }
+    }
+
+    /// Quick filter to tasks at least as urgent as `urgency`, within the current view
+    pub fn set_urgency_filter(&mut self, urgency: Urgency) {
+        self.change_filters(|state| state.urgency = Some(urgency));
+    }
+
+    /// Drop the active view, query and urgency filter, returning to the full list
+    pub fn clear_filters(&mut self) {
+        self.change_filters(|state| {
+            state.view = View::All;
+            state.query = Query::default();
+            state.urgency = None;
+            state.search.clear();
+        });
+    }
+
+    /// Enter Search mode with an empty search, showing every task again
+    pub fn start_search(&mut self) {
+        self.clear_input_buffer();
+        self.clear_error_message();
+        self.change_filters(|state| state.search.clear());
+        self.state.mode = Mode::Search;
+    }
+
+    /// Search for the text typed so far, narrowing the list as it is typed
+    pub fn update_search(&mut self) {
+        let search = self.state.input_buffer.clone();
+        self.change_filters(|state| state.search = search);
+    }
+
+    /// Stop searching and show every task again
+    pub fn clear_search(&mut self) {
+        if !self.state.search.is_empty() {
+            self.change_filters(|state| state.search.clear());
+        }
+    }
+
+    /// Move the cursor to the next (or previous) search match, wrapping around
+    ///
+    /// While searching, the list only shows matches, so this steps through
+    /// its rows. Returns false, leaving the cursor alone, when no search is active.
+    pub fn cursor_step_match(&mut self, forward: bool) -> bool {
+        if self.state.search.is_empty() {
+            return false;
+        }
+        let count = self.row_task_indices().len();
+        if count > 0 {
+            let row = self.task_list_state.selected().unwrap_or(0).min(count - 1);
+            let next = if forward {
+                (row + 1) % count
+            } else {
+                (row + count - 1) % count
+            };
+            self.task_list_state.select(Some(next));
+        }
+        true
+    }
+
+    /// Apply a change to the view or query, then reconcile the cursor
+    ///
+    /// Tasks are tracked by ID across the change. With
+    /// [`FilterCursor::Nearest`] the cursor stays on its task if it is still
+    /// shown, otherwise it moves to the closest previously listed task that
+    /// survived, looking below before above. Failing that, and always with
+    /// [`FilterCursor::First`], it goes to the first visible task, or nowhere
+    /// if the list is now empty.
+    fn change_filters<F>(&mut self, change: F)
+    where
+        F: FnOnce(&mut AppState<D>),
+    {
+        let before: Vec<String> = self
+            .row_task_indices()
+            .into_iter()
+            .map(|i| self.state.tasks[i].id.clone())
+            .collect();
+        let cursor_row = self.cursor_task_index().and_then(|index| {
+            before
+                .iter()
+                .position(|id| *id == self.state.tasks[index].id)
+        });
+
+        change(&mut self.state);
+        self.clear_task_selection();
+
+        let visible = self.row_task_indices();
+        let row_of = |id: &String| visible.iter().position(|&i| self.state.tasks[i].id == *id);
+        let follow = self.state.config.behavior.filter_cursor == FilterCursor::Nearest;
+        let nearest = cursor_row.filter(|_| follow).and_then(|row| {
+            (0..before.len()).find_map(|distance| {
+                let below = before.get(row + distance).and_then(row_of);
+                let above = || {
+                    row.checked_sub(distance)
+                        .and_then(|r| before.get(r))
+                        .and_then(row_of)
+                };
+                below.or_else(above)
+            })
+        });
+        let row = nearest.or_else(|| (!visible.is_empty()).then_some(0));
+        self.task_list_state.select(row);
+    }
+
+    /// Indices into `state.tasks` of the tasks shown by the active view and query, in display order
+    ///
+    /// The task currently being edited is always shown so that a new task
+    /// does not vanish while its fields are being filled in.
+    pub fn visible_task_indices(&self) -> Vec<usize> {
+        self.filtered_task_indices(&self.state.search)
+    }
+
+    /// [`App::visible_task_indices`] narrowed to tasks whose title or description contains `query`
+    ///
+    /// Matching ignores case; an empty query matches every task.
+    pub fn filtered_task_indices(&self, query: &str) -> Vec<usize> {
+        let query = query.to_lowercase();
+        let now = self.list_time.unwrap_or_else(|| self.now());
+        let editing_id = self.state.editing_task.as_ref().map(|t| t.id.as_str());
+        let hide_deferred = self.state.config.ui.hide_deferred && self.state.view != View::Deferred;
+        let hide_completed = self.state.config.ui.done_log && self.state.view != View::Done;
+        let mut visible: Vec<usize> = self
+            .state
+            .tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, task)| {
+                Some(task.id.as_str()) == editing_id
+                    || (self.state.view.includes(task, now)
+                        && self.state.query.matches(task)
+                        && crate::filter::text_matches(task, &query)
+                        && self.state.urgency.map_or(true, |u| u.includes(task, now))
+                        && (self.state.view != View::Review || self.review.contains(&task.id))
+                        && !(hide_deferred && task.is_deferred(now))
+                        && !(hide_completed && task.completed))
+            })
+            .map(|(i, _)| i)
+            .collect();
+        if self.state.view == View::Done {
+            // The done log reads newest first, whatever the sort key
+            visible.sort_by_key(|&i| std::cmp::Reverse(self.state.tasks[i].finished_at()));
+            return visible;
+        }
+        self.sorted_indices(visible)
+    }
+
+    /// Order task indices by the current sort key and direction
+    ///
+    /// The sort is stable, so ties keep the order tasks were added in.
+    /// Tasks without a due date stay last when sorting by due date in
+    /// either direction.
+    pub fn sorted_indices(&self, mut indices: Vec<usize>) -> Vec<usize> {
+        let tasks = &self.state.tasks;
+        let directed = |ordering: Ordering| {
+            if self.sort_desc {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        };
+        match self.sort_key {
+            SortKey::Manual => {
+                if self.sort_desc {
+                    indices.reverse();
+                }
+            }
+            SortKey::Due => indices.sort_by(|&a, &b| match (tasks[a].due, tasks[b].due) {
+                (Some(a), Some(b)) => directed(a.cmp(&b)),
+                // Unscheduled tasks sort after scheduled ones
+                (a, b) => a.is_none().cmp(&b.is_none()),
+            }),
+            SortKey::Created => {
+                indices.sort_by(|&a, &b| directed(tasks[a].created_at.cmp(&tasks[b].created_at)))
+            }
+            SortKey::Title => indices.sort_by(|&a, &b| {
+                directed(
+                    tasks[a]
+                        .title
+                        .to_lowercase()
+                        .cmp(&tasks[b].title.to_lowercase()),
+                )
+            }),
+            // Most important first, so ascending puts high priority on top
+            SortKey::Priority => {
+                indices.sort_by(|&a, &b| directed(tasks[b].priority.cmp(&tasks[a].priority)))
+            }
+            SortKey::Completed => indices.sort_by(|&a, &b| {
+                let finished = |i: usize| tasks[i].completed.then(|| tasks[i].finished_at());
+                match (finished(a), finished(b)) {
+                    (Some(a), Some(b)) => directed(b.cmp(&a)),
+                    // Open tasks sort after completed ones
+                    (a, b) => a.is_none().cmp(&b.is_none()),
+                }
+            }),
+            SortKey::Modified => indices.sort_by(|&a, &b| {
+                directed(tasks[b].last_modified().cmp(&tasks[a].last_modified()))
+            }),
+        }
+        indices
+    }
+
+    /// Sort by the next key in the cycle, keeping the cursor on the same task
+    pub fn cycle_sort_key(&mut self) {
+        let cursor_task = self.cursor_task_index();
+        self.sort_key = self.sort_key.next();
+        if let Some(task_index) = cursor_task {
+            self.cursor_to_task_index(task_index);
+        }
+    }
+
+    /// Flip the direction of the current sort, keeping the cursor on the same task
+    pub fn toggle_sort_direction(&mut self) {
+        let cursor_task = self.cursor_task_index();
+        self.sort_desc = !self.sort_desc;
+        if let Some(task_index) = cursor_task {
+            self.cursor_to_task_index(task_index);
+        }
+    }
+
+    /// Message to show in place of an empty task list, if any
+    ///
+    /// An empty store gets a hint on how to start; a today view with nothing
+    /// actionable gets the configured celebration instead.
+    pub fn empty_list_message(&self) -> Option<String> {
+        if self.state.tasks.is_empty() {
+            return Some("No tasks yet - press 'o' to add one".to_string());
+        }
+        if self.state.view == View::Review && self.state.config.maintenance.stale_review_days == 0 {
+            return Some(
+                "Set [maintenance] stale_review_days to flag tasks for review".to_string(),
+            );
+        }
+        let celebration = &self.state.config.ui.today_clear_message;
+        if self.state.view == View::Today
+            && self.state.query.is_empty()
+            && !celebration.is_empty()
+            && self.visible_task_indices().is_empty()
+        {
+            return Some(celebration.clone());
+        }
+        None
+    }
+
+    /// Number of open tasks past their due date (someday tasks are never counted)
+    pub fn overdue_count(&self) -> usize {
+        let now = self.now();
+        self.state
+            .tasks
+            .iter()
+            .filter(|t| t.is_overdue(now))
+            .count()
+    }
+
+    /// Number of open tasks waiting longer than `[ui] waiting_nudge_days`
+    pub fn waiting_too_long_count(&self) -> usize {
+        let now = self.now();
+        let nudge_days = self.state.config.ui.waiting_nudge_days;
+        self.state
+            .tasks
+            .iter()
+            .filter(|t| t.is_waiting_too_long(now, nudge_days))
+            .count()
+    }
+
+    /// Run a `:` command typed in Command mode
+    ///
+    /// Supported commands:
+    /// - `all`, `today`, `someday`, `unscheduled`, `deferred`, `review` - switch the active view
+    /// - `filter <query>` - narrow the view (see [`Query`]); no query clears it
+    /// - `tag +a -b` - add and remove tags on the selected tasks
+    /// - `group` - toggle listing tasks under tag headers
+    /// - `energy <low|medium|high>` - show only tasks at that energy; no level clears it
+    /// - `setenergy <low|medium|high|none>` - rate the selected tasks
+    /// - `project <name>` - show only tasks in that project; no name clears it
+    /// - `setproject <name|none>` - move the selected tasks into a project
+    /// - `wait` - mark the selected tasks as waiting on someone, or clear the mark
+    /// - `after` - defer the `x`-marked tasks until the task under the cursor is done
+    /// - `repeat <daily|weekly|monthly|Nd|none>` - make the selected tasks repeat, or stop
+    /// - `skip` - move the selected repeating tasks on to their next occurrence
+    /// - `duefromdefer` - set the selected tasks' due date to their defer date
+    /// - `deferfromdue [days]` - set the defer date to the due date plus `days` (may be negative)
+    pub fn execute_command(&mut self, command: &str) -> Result<(), String> {
+        let command = command.trim();
+        let (name, args) = command
+            .split_once(char::is_whitespace)
+            .map(|(name, args)| (name, args.trim()))
+            .unwrap_or((command, ""));
+        match name {
+            "" => Ok(()),
+            "filter" => {
+                self.set_query(Query::parse(args)?);
+                Ok(())
+            }
+            "energy" => {
+                let energy = match args {
+                    "" => None,
+                    name => Some(Energy::from_name(name).ok_or_else(|| {
+                        format!("Unknown energy '{name}' (use low, medium or high)")
+                    })?),
+                };
+                let query = Query {
+                    energy,
+                    ..self.state.query.clone()
+                };
+                self.set_query(query);
+                Ok(())
+            }
+            "setenergy" => {
+                let energy = match args {
+                    "none" => None,
+                    name => Some(Energy::from_name(name).ok_or_else(|| {
+                        format!("Unknown energy '{name}' (use low, medium, high or none)")
+                    })?),
+                };
+                self.apply_to_selection(|t| t.energy = energy);
+                Ok(())
+            }
+            "project" => {
+                let query = Query {
+                    project: (!args.is_empty()).then(|| args.to_string()),
+                    ..self.state.query.clone()
+                };
+                self.set_query(query);
+                Ok(())
+            }
+            "setproject" => {
+                let project = match args {
+                    "" => return Err("Usage: setproject <name|none>".to_string()),
+                    "none" => None,
+                    name => Some(name.to_string()),
+                };
+                self.apply_to_selection(|t| t.project = project.clone());
+                Ok(())
+            }
+            "after" => self.defer_selection_until_cursor_task(),
+            "repeat" => {
+                let recurrence = match args {
+                    "" => return Err("Usage: repeat <daily|weekly|monthly|Nd|none>".to_string()),
+                    "none" => None,
+                    name => Some(
+                        Recurrence::from_name(name)
+                            .ok_or_else(|| format!("Unknown recurrence '{name}'"))?,
+                    ),
+                };
+                self.apply_to_selection(|t| t.recurrence = recurrence);
+                Ok(())
+            }
+            "skip" => self.skip_occurrence(),
+            "remind" => {
+                let lead = match args {
+                    "" => return Err("Usage: remind <30m|1h|2d|none>".to_string()),
+                    "none" => None,
+                    lead => Some(
+                        parse_lead(lead).ok_or_else(|| format!("Unknown lead time '{lead}'"))?,
+                    ),
+                };
+                self.apply_to_selection(|t| t.reminder_lead = lead);
+                Ok(())
+            }
+            "duefromdefer" => self.due_from_defer(),
+            "shift-due" => self.shift_command(DateField::Due, args),
+            "shift-defer" => self.shift_command(DateField::Defer, args),
+            "deferfromdue" => {
+                let days = if args.is_empty() {
+                    0
+                } else {
+                    args.parse::<i64>()
+                        .map_err(|_| format!("Expected a number of days, got '{args}'"))?
+                };
+                self.defer_from_due(chrono::Duration::days(days))
+            }
+            "wait" => {
+                let now = self.now();
+                self.apply_to_selection(|t| {
+                    t.waiting_since = match t.waiting_since {
+                        Some(_) => None,
+                        None => Some(now),
+                    };
+                });
+                Ok(())
+            }
+            "group" => {
+                self.change_filters(|state| state.group_by_tag = !state.group_by_tag);
+                Ok(())
+            }
+            "tag" => {
+                let mut adds = Vec::new();
+                let mut removes = Vec::new();
+                for word in args.split_whitespace() {
+                    match (word.strip_prefix('+'), word.strip_prefix('-')) {
+                        (Some(tag), _) if !tag.is_empty() => adds.push(tag.to_string()),
+                        (_, Some(tag)) if !tag.is_empty() => removes.push(tag.to_string()),
+                        _ => return Err(format!("Expected +tag or -tag, got '{word}'")),
+                    }
+                }
+                if adds.is_empty() && removes.is_empty() {
+                    return Err("Usage: tag +add -remove".to_string());
+                }
+                let selection: Vec<usize> = self.selection().collect();
+                self.modify_tags(&selection, &adds, &removes)
+                    .map_err(|e| format!("Error updating tags: {e}"))?;
+                self.clear_task_selection();
+                Ok(())
+            }
+            name => match View::from_name(name) {
+                Some(view) => {
+                    self.set_view(view);
+                    Ok(())
+                }
+                None => Err(format!("Unknown command: {name}")),
+            },
+        }
+    }
+
+    /// Let the task list catch up with the clock after the user acts
+    pub fn refresh_list(&mut self) {
+        self.list_time = None;
+    }
+
+    /// Periodic housekeeping driven by the event loop
+    ///
+    /// Unless `[ui] live_resort` is on, the task list is pinned to the time
+    /// of the last user action, so rows never jump while nobody is typing.
+    pub fn tick(&mut self) {
+        let now = self.now();
+        self.list_time = if self.state.config.ui.live_resort {
+            None
+        } else {
+            self.list_time.or(Some(now))
+        };
+        self.completion_feedback.retain(|_, expiry| *expiry > now);
+        if self.message_expires_at.is_some_and(|expiry| expiry <= now) {
+            self.clear_error_message();
+        }
+        if self.confirmation_expired(now) {
+            self.cancel_confirmation();
+        }
+        if let Some(reminder) = self.due_reminder(now) {
+            self.set_error_message(reminder);
+        }
+        if self.checkpoint_due(now) {
+            if let Err(e) = self.checkpoint() {
+                self.set_error_message(format!("Error writing checkpoint: {e}"));
+            }
+        }
+    }
+
+    /// Reminder for open tasks whose reminder time passed since the last check
+    ///
+    /// A task's reminder time is its due date minus its lead (see
+    /// [`Task::reminder_at`]), so each reminder fires once, on the first
+    /// check at or after it. `None` when `[notifications]` is off or nothing
+    /// came up; a clock that jumped backwards shows nothing.
+    pub fn due_reminder(&mut self, now: SystemTime) -> Option<String> {
+        let since = std::mem::replace(&mut self.last_reminder_check, now);
+        let notifications = &self.state.config.notifications;
+        if !notifications.enabled || now <= since {
+            return None;
+        }
+        let default_lead = Duration::from_secs(notifications.lead_min * 60);
+        let reminded: Vec<&Task> = self
+            .state
+            .tasks
+            .iter()
+            .filter(|t| {
+                t.reminder_at(default_lead)
+                    .is_some_and(|at| since < at && at <= now)
+            })
+            .collect();
+        match reminded.as_slice() {
+            [] => None,
+            [task] => {
+                let due = DateTime::<Local>::from(task.due?).format("%H:%M");
+                Some(format!("Reminder: '{}' is due at {due}", task.title))
+            }
+            tasks => Some(format!("Reminder: {} tasks are coming due", tasks.len())),
+        }
+    }
+
+    /// Whether `[maintenance] checkpoint_min` minutes have passed since the last checkpoint
+    pub fn checkpoint_due(&self, now: SystemTime) -> bool {
+        let interval_min = self.state.config.maintenance.checkpoint_min;
+        if interval_min == 0 {
+            return false;
+        }
+        now.duration_since(self.last_checkpoint)
+            .is_ok_and(|elapsed| elapsed >= Duration::from_secs(interval_min * 60))
+    }
+
+    /// Flush storage and write a backup, restarting the checkpoint timer
+    pub fn checkpoint(&mut self) -> Result<(), DbError> {
+        self.last_checkpoint = self.now();
+        self.state.store.checkpoint()
+    }
+
+    /// Last work before the event loop exits
+    ///
+    /// A timer still running is stopped and its session saved, then a
+    /// checkpoint is taken if `[behavior] quit_flush` is on.
+    pub fn shutdown(&mut self) -> Result<(), DbError> {
+        if let Some(id) = self.time_tracker.get_active_timer() {
+            let mut tracker = self.time_tracker.clone();
+            tracker
+                .stop_timer_at(&id, self.now())
+                .map_err(DbError::OperationFailed)?;
+            self.commit_timers(tracker)?;
+        }
+        if self.state.config.behavior.quit_flush {
+            self.checkpoint()?;
+        }
+        Ok(())
+    }
+
+    pub fn quit(&mut self) {
+        self.state.should_quit = true;
+    }
+
+    pub fn clear_input_buffer(&mut self) {
+        self.state.input_buffer.clear();
+        self.state.caret_from_end = 0;
+        self.state.select_pending = false;
+    }
+
+    /// Type a character at the caret, replacing the whole buffer if its text is selected
+    pub fn add_to_input_buffer(&mut self, c: char) {
+        if self.state.select_pending {
+            self.clear_input_buffer();
+        }
+        let at = self.caret_byte_index();
+        self.state.input_buffer.insert(at, c);
+    }
+
+    /// Delete the character before the caret, or only drop the selection if one is pending
+    pub fn backspace_input_buffer(&mut self) {
+        if self.state.select_pending {
+            self.state.select_pending = false;
+        } else if let Some((at, _)) = self.state.input_buffer[..self.caret_byte_index()]
+            .char_indices()
+            .next_back()
+        {
+            self.state.input_buffer.remove(at);
+        }
+    }
+
+    /// Insert pasted text at the caret, replacing the buffer if its text is selected
+    ///
+    /// Only the scratchpad and notes keep line breaks; elsewhere the lines are
+    /// joined with spaces. Outside the modes that take text, this does nothing.
+    pub fn paste(&mut self, text: &str) {
+        let multiline = match self.state.mode {
+            Mode::Scratch => true,
+            Mode::Insert => {
+                self.state.editing_task.is_some()
+                    && self.edit_field(self.state.editing_field) == EditField::Notes
+            }
+            Mode::Command | Mode::Search => false,
+            Mode::Normal => return,
+        };
+        let text = if multiline {
+            text.replace("\r\n", "\n")
+        } else {
+            text.lines().collect::<Vec<_>>().join(" ")
+        };
+        if self.state.select_pending {
+            self.clear_input_buffer();
+        }
+        let at = self.caret_byte_index();
+        self.state.input_buffer.insert_str(at, &text);
+        if self.state.mode == Mode::Search {
+            self.update_search();
+        }
+    }
+
+    /// Delete the word before the caret, with any whitespace between it and the caret
+    pub fn delete_word_before_caret(&mut self) {
+        self.delete_before_caret(|before| {
+            let word_end = before.trim_end().len();
+            before[..word_end]
+                .char_indices()
+                .rev()
+                .find(|(_, c)| c.is_whitespace())
+                .map_or(0, |(at, c)| at + c.len_utf8())
+        });
+    }
+
+    /// Delete from the start of the caret's line up to the caret
+    pub fn delete_line_before_caret(&mut self) {
+        self.delete_before_caret(|before| before.rfind('\n').map_or(0, |at| at + 1));
+    }
+
+    /// Delete the text between `start(text before the caret)` and the caret
+    ///
+    /// Like Backspace, this only drops the selection if one is pending.
+    fn delete_before_caret(&mut self, start: impl FnOnce(&str) -> usize) {
+        if self.state.select_pending {
+            self.state.select_pending = false;
+            return;
+        }
+        let caret = self.caret_byte_index();
+        let start = start(&self.state.input_buffer[..caret]);
+        self.state.input_buffer.replace_range(start..caret, "");
+    }
+
+    /// Byte offset of the caret in the input buffer
+    fn caret_byte_index(&self) -> usize {
+        let buffer = &self.state.input_buffer;
+        let caret = buffer
+            .chars()
+            .count()
+            .saturating_sub(self.state.caret_from_end);
+        buffer
+            .char_indices()
+            .nth(caret)
+            .map_or(buffer.len(), |(at, _)| at)
+    }
+
+    /// Move the caret one character left or right, or to the start or end of the text
+    ///
+    /// Moving the caret drops a pending selection, keeping the text.
+    pub fn move_caret(&mut self, to: CaretMove) {
+        let len = self.state.input_buffer.chars().count();
+        let from_end = self.state.caret_from_end.min(len);
+        self.state.caret_from_end = match to {
+            CaretMove::Left => (from_end + 1).min(len),
+            CaretMove::Right => from_end.saturating_sub(1),
+            CaretMove::Home => len,
+            CaretMove::End => 0,
+        };
+        self.state.select_pending = false;
+    }
+
+    /// Load the editing field's text into the input buffer
+    ///
+    /// With `behavior.select_on_edit` the loaded text starts out selected.
+    pub fn load_editing_field(&mut self) {
+        self.state.input_buffer = self.get_editing_task_field(self.state.editing_field);
+        self.state.caret_from_end = 0;
+        self.state.select_pending =
+            self.state.config.behavior.select_on_edit && !self.state.input_buffer.is_empty();
+    }
+
+    pub fn set_error_message(&mut self, message: String) {
+        self.message = Some(message);
+        self.message_expires_at = None;
+    }
+
+    /// Show `message` until `expires_at`, when the tick clears it
+    pub fn set_transient_message(&mut self, message: String, expires_at: SystemTime) {
+        self.message = Some(message);
+        self.message_expires_at = Some(expires_at);
+    }
+
+    pub fn clear_error_message(&mut self) {
+        self.message = None;
+        self.message_expires_at = None;
+    }
+
+    pub fn get_error_message(&self) -> Option<&String> {
+        self.message.as_ref()
+    }
+
+    /// Parse date input, reporting why it failed
+    ///
+    /// Empty input (or `-`) clears the date and parses to `Ok(None)`.
+    pub fn try_parse_date_input(
+        &self,
+        input: &str,
+        is_due_date: bool,
+    ) -> Result<Option<SystemTime>, DateParseError> {
+        let trimmed = input.trim().to_lowercase();
+        if let Some(result) = self.parse_relative(&trimmed) {
+            return result.map(Some);
+        }
+        match self.parse_date_input(input, is_due_date) {
+            Some(time) => Ok(Some(time)),
+            None if trimmed.is_empty() || trimmed == "-" => Ok(None),
+            None => Err(DateParseError::Unrecognized(input.trim().to_string())),
+        }
+    }
+
+    pub fn parse_date_input(&self, input: &str, is_due_date: bool) -> Option<SystemTime> {
+        let input = input.trim().to_lowercase();
+        if input.is_empty() || input == "-" {
+            return None;
+        }
+
+        let now = self.now();
+        let local_now = DateTime::<Local>::from(now);
+
+        // Handle time-of-day keywords, alone or after a date ("friday noon")
+        if let Some(dt) = self.parse_time_of_day(&input, is_due_date, local_now) {
+            return Some(dt);
+        }
+
+        // Dates without a time land on the configured hour for their kind
+        let default_hour = if is_due_date {
+            self.state.config.time.due_hour
+        } else {
+            self.state.config.time.defer_hour
+        };
+
+        // Handle simple keywords
+        match input.as_str() {
+            "today" => return Some(now),
+            "tomorrow" => {
+                let tomorrow = local_now.date_naive().succ_opt()?;
+                let tomorrow_dt = Local
+                    .from_local_datetime(&tomorrow.and_hms_opt(default_hour, 0, 0)?)
+                    .single()?;
+                return Some(tomorrow_dt.into());
+            }
+            "yesterday" => {
+                let yesterday = local_now.date_naive().pred_opt()?;
+                let yesterday_dt = Local
+                    .from_local_datetime(&yesterday.and_hms_opt(default_hour, 0, 0)?)
+                    .single()?;
+                return Some(yesterday_dt.into());
+            }
+            _ => {}
+        }
+
+        // Handle weekday names
+        if let Some(target_weekday) = self.parse_weekday(&input) {
+            let current_weekday = local_now.weekday();
+            let days_ahead = (target_weekday.num_days_from_monday() as i64
+                - current_weekday.num_days_from_monday() as i64
+                + 7)
+                % 7;
+            let days_ahead = if days_ahead == 0 { 7 } else { days_ahead }; // Next occurrence
+
+            let target_date = local_now.date_naive() + chrono::Duration::days(days_ahead);
+            let target_dt = Local
+                .from_local_datetime(&target_date.and_hms_opt(default_hour, 0, 0)?)
+                .single()?;
+            return Some(target_dt.into());
+        }
+
+        // Handle "next weekday"
+        if let Some(weekday_part) = input.strip_prefix("next ") {
+            if let Some(target_weekday) = self.parse_weekday(weekday_part) {
+                let current_weekday = local_now.weekday();
+                let days_ahead = (target_weekday.num_days_from_monday() as i64
+                    - current_weekday.num_days_from_monday() as i64
+                    + 14)
+                    % 7;
+                let days_ahead = if days_ahead == 0 { 7 } else { days_ahead } + 7; // Next week
+
+                let target_date = local_now.date_naive() + chrono::Duration::days(days_ahead);
+                let target_dt = Local
+                    .from_local_datetime(&target_date.and_hms_opt(default_hour, 0, 0)?)
+                    .single()?;
+                return Some(target_dt.into());
+            }
+        }
+
+        // Handle relative dates like "2d", "1w", "3h"
+        if let Some(result) = self.parse_relative(&input) {
+            return result.ok();
+        }
+
+        // Handle YYYY-MM-DD format
+        if let Ok(date) = NaiveDate::parse_from_str(&input, "%Y-%m-%d") {
+            let dt = Local
+                .from_local_datetime(&date.and_hms_opt(default_hour, 0, 0)?)
+                .single()?;
+            return Some(dt.into());
+        }
+
+        // Handle MM-DD format (current year)
+        if let Ok(date) =
+            NaiveDate::parse_from_str(&format!("{}-{}", local_now.year(), input), "%Y-%m-%d")
+        {
+            let dt = Local
+                .from_local_datetime(&date.and_hms_opt(default_hour, 0, 0)?)
+                .single()?;
+            return Some(dt.into());
+        }
+
+        None
+    }
+
+    /// Resolve input ending in a time of day to that time
+    ///
+    /// The time is a keyword such as `noon` or a clock time such as `3pm`,
+    /// `9:30am` or `14:00`. It applies to today, or to the date parsed from
+    /// whatever precedes it. Returns `None` when there is no time or the date
+    /// part does not parse.
+    fn parse_time_of_day(
+        &self,
+        input: &str,
+        is_due_date: bool,
+        local_now: DateTime<Local>,
+    ) -> Option<SystemTime> {
+        let time = &self.state.config.time;
+        // Longest first so "end of day" is not shadowed by a shorter keyword
+        let keywords = [
+            ("end of day", time.due_hour, 0),
+            ("afternoon", time.afternoon_hour, 0),
+            ("midnight", 0, 1),
+            ("evening", time.evening_hour, 0),
+            ("morning", time.morning_hour, 0),
+            ("noon", 12, 0),
+            ("eod", time.due_hour, 0),
+        ];
+
+        let (date_part, hour, minute, day_offset) = keywords
+            .iter()
+            .find_map(|&(keyword, hour, offset)| {
+                let date_part = input.strip_suffix(keyword)?;
+                (date_part.is_empty() || date_part.ends_with(' '))
+                    .then(|| (date_part.trim_end(), hour, 0, offset))
+            })
+            .or_else(|| {
+                let (date_part, clock) = input.rsplit_once(' ').unwrap_or(("", input));
+                let (hour, minute) = parse_clock_time(clock)?;
+                Some((date_part.trim_end(), hour, minute, 0))
+            })?;
+
+        let date = if date_part.is_empty() {
+            local_now.date_naive()
+        } else {
+            DateTime::<Local>::from(self.parse_date_input(date_part, is_due_date)?).date_naive()
+        };
+        let date = date + chrono::Duration::days(day_offset);
+        let dt = Local
+            .from_local_datetime(&date.and_hms_opt(hour, minute, 0)?)
+            .single()?;
+        Some(dt.into())
+    }
+
+    /// Parse a relative offset like "2d", "1w", "3h" or "30m" from now
+    ///
+    /// Returns `None` when the input is not of that shape, and an error when
+    /// the offset lands beyond what dates can represent.
+    fn parse_relative(&self, input: &str) -> Option<Result<SystemTime, DateParseError>> {
+        let unit = input.chars().last()?;
+        let unit_secs: u64 = match unit {
+            'd' => 24 * 60 * 60,
+            'h' => 60 * 60,
+            'm' => 60,
+            'w' => 7 * 24 * 60 * 60,
+            _ => return None,
+        };
+        let digits = &input[..input.len() - unit.len_utf8()];
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        let now = self.now();
+        let time = digits
+            .parse::<u64>()
+            .ok()
+            .and_then(|num| num.checked_mul(unit_secs))
+            .and_then(|secs| now.checked_add(Duration::from_secs(secs)))
+            // Dates must also fit chrono's range so they can be displayed
+            .filter(|&time| {
+                time.duration_since(SystemTime::UNIX_EPOCH)
+                    .ok()
+                    .and_then(|d| i64::try_from(d.as_secs()).ok())
+                    .and_then(|secs| DateTime::from_timestamp(secs, 0))
+                    .is_some()
+            });
+        Some(time.ok_or(DateParseError::TooFarInFuture))
+    }
+
+    fn parse_weekday(&self, input: &str) -> Option<Weekday> {
+        match input {
+            "monday" | "mon" => Some(Weekday::Mon),
+            "tuesday" | "tue" => Some(Weekday::Tue),
+            "wednesday" | "wed" => Some(Weekday::Wed),
+            "thursday" | "thu" => Some(Weekday::Thu),
+            "friday" | "fri" => Some(Weekday::Fri),
+            "saturday" | "sat" => Some(Weekday::Sat),
+            "sunday" | "sun" => Some(Weekday::Sun),
+            _ => None,
+        }
+    }
+
+    fn format_date_for_editing(&self, time: Option<SystemTime>) -> String {
+        match time {
+            Some(t) => {
+                let now = self.now();
+                if let Ok(duration) = t.duration_since(now) {
+                    let days = duration.as_secs() / (24 * 60 * 60);
+                    let hours = (duration.as_secs() % (24 * 60 * 60)) / (60 * 60);
+                    if days > 0 {
+                        format!("{days}d")
+                    } else if hours > 0 {
+                        format!("{hours}h")
+                    } else {
+                        "1h".to_string()
+                    }
+                } else if let Ok(duration) = now.duration_since(t) {
+                    // Past date - show negative
+                    let days = duration.as_secs() / (24 * 60 * 60);
+                    let hours = (duration.as_secs() % (24 * 60 * 60)) / (60 * 60);
+                    if days > 0 {
+                        format!("-{days}d")
+                    } else if hours > 0 {
+                        format!("-{hours}h")
+                    } else {
+                        "now".to_string()
+                    }
+                } else {
+                    "0d".to_string()
+                }
+            }
+            None => "".to_string(),
+        }
+    }
+
+    fn create_task(&self, title: &str) -> Task {
+        Task::new(title, TaskSource::Tui, self.now())
+    }
+
+    fn apply_to_selection<F>(&mut self, mut func: F)
+    where
+        F: FnMut(&mut Task),
+    {
+        let indices: Vec<usize> = self.selection().collect();
+        let result = self.mutate_atomic(|tasks| {
+            for index in indices {
+                if let Some(task) = tasks.get_mut(index) {
+                    func(task);
+                }
+            }
+        });
+        if let Err(e) = result {
+            self.set_error_message(format!("Error syncing tasks: {e}"));
+        }
+        self.clear_task_selection();
+    }
+
+    // Task list selection methods
+    pub fn cursor_next_task(&mut self) {
+        self.task_list_state.select_next();
+    }
+
+    pub fn cursor_previous_task(&mut self) {
+        self.task_list_state.select_previous();
+    }
+
+    pub fn cursor_first_task(&mut self) {
+        self.task_list_state.select_first();
+    }
+
+    pub fn cursor_last_task(&mut self) {
+        self.task_list_state.select_last();
+    }
+
+    /// Index into `state.tasks` of the task under the cursor
+    ///
+    /// The cursor tracks a row of the visible list; rows past the end
+    /// are clamped to the last visible task.
+    pub fn cursor_task_index(&self) -> Option<usize> {
+        let row = self.task_list_state.selected()?;
+        let visible = self.row_task_indices();
+        visible
+            .get(row.min(visible.len().saturating_sub(1)))
+            .copied()
+    }
+
+    /// Row of the next overdue or due-today task after `from_row`, wrapping around
+    pub fn next_urgent(&self, from_row: usize) -> Option<usize> {
+        let rows = self.urgent_rows();
+        rows.iter()
+            .find(|&&row| row > from_row)
+            .or_else(|| rows.first())
+            .copied()
+    }
+
+    /// Row of the previous overdue or due-today task before `from_row`, wrapping around
+    pub fn previous_urgent(&self, from_row: usize) -> Option<usize> {
+        let rows = self.urgent_rows();
+        rows.iter()
+            .rev()
+            .find(|&&row| row < from_row)
+            .or_else(|| rows.last())
+            .copied()
+    }
+
+    pub fn cursor_next_urgent_task(&mut self) {
+        let from_row = self.task_list_state.selected().unwrap_or(0);
+        if let Some(row) = self.next_urgent(from_row) {
+            self.task_list_state.select(Some(row));
+        }
+    }
+
+    pub fn cursor_previous_urgent_task(&mut self) {
+        let from_row = self.task_list_state.selected().unwrap_or(0);
+        if let Some(row) = self.previous_urgent(from_row) {
+            self.task_list_state.select(Some(row));
+        }
+    }
+
+    /// Visible rows whose task is overdue or due today
+    fn urgent_rows(&self) -> Vec<usize> {
+        let now = self.now();
+        self.row_task_indices()
+            .iter()
+            .enumerate()
+            .filter(|(_, &i)| self.state.tasks[i].is_due_today(now))
+            .map(|(row, _)| row)
+            .collect()
+    }
+
+    /// Move the cursor onto the row showing the task at `task_index`
+    fn cursor_to_task_index(&mut self, task_index: usize) {
+        let row = self
+            .row_task_indices()
+            .iter()
+            .position(|&i| i == task_index);
+        if row.is_some() {
+            self.task_list_state.select(row);
+        }
+    }
+
+    pub fn clear_task_selection(&mut self) {
+        self.task_selection.clear();
+    }
+
+    /// Indices of the marked tasks, in list order
+    fn marked_indices(&self) -> Vec<usize> {
+        self.state
+            .tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, task)| self.task_selection.contains(&task.id))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    pub fn selection(&self) -> SelectionIterator {
+        if !self.task_selection.is_empty() {
+            SelectionIterator::Multiple(self.marked_indices().into_iter())
+        } else if let Some(selected) = self.cursor_task_index() {
+            SelectionIterator::Single(std::iter::once(selected))
+        } else {
+            SelectionIterator::Empty
+        }
+    }
+
+    pub fn toggle_task_selection(&mut self) {
+        if let Some(selected) = self.cursor_task_index() {
+            let id = &self.state.tasks[selected].id;
+            if !self.task_selection.remove(id) {
+                self.task_selection.insert(id.clone());
+            }
+        }
+    }
+
+    pub fn task_list_state(&mut self) -> &mut TableState {
+        &mut self.task_list_state
+    }
+
+    pub fn get_task_selection(&self) -> &HashSet<String> {
+        &self.task_selection
+    }
+
+    pub fn create_task_below_cursor(&mut self) {
+        let new_task = self.create_task_for_view();
+        let cursor_index = self.cursor_task_index().unwrap_or(0);
+        let insert_index = if self.state.tasks.is_empty() {
+            0
+        } else {
+            (cursor_index + 1).min(self.state.tasks.len())
+        };
+        self.state.tasks.insert(insert_index, new_task.clone());
+        self.unsaved_task = Some(new_task.id.clone());
+        self.state.editing_task = Some(new_task);
+        self.state.editing_field = 0;
+        self.cursor_to_task_index(insert_index);
+    }
+
+    pub fn create_task_above_cursor(&mut self) {
+        let new_task = self.create_task_for_view();
+        let cursor_index = self.cursor_task_index().unwrap_or(0);
+        self.state.tasks.insert(cursor_index, new_task.clone());
+        self.unsaved_task = Some(new_task.id.clone());
+        self.state.editing_task = Some(new_task);
+        self.state.editing_field = 0;
+        self.cursor_to_task_index(cursor_index);
+    }
+
+    /// New blank task, placed on the someday list when created from the someday view
+    fn create_task_for_view(&self) -> Task {
+        let mut task = self.create_task("");
+        task.someday = self.state.view == View::Someday;
+        task
+    }
+
+    /// Show the scratchpad and start typing into it
+    pub fn start_scratch_edit(&mut self) {
+        self.state.show_scratch = true;
+        self.state.input_buffer = self.state.scratch.clone();
+        self.state.caret_from_end = 0;
+        self.state.mode = Mode::Scratch;
+    }
+
+    /// Keep the text typed into the scratchpad and return to Normal mode
+    ///
+    /// The scratchpad stays visible; on a storage error the previous text is
+    /// kept both on screen and on disk.
+    pub fn finish_scratch_edit(&mut self) -> Result<(), DbError> {
+        let text = std::mem::take(&mut self.state.input_buffer);
+        self.state.mode = Mode::Normal;
+        self.state.store.save_scratch(&text)?;
+        self.state.scratch = text;
+        Ok(())
+    }
+
+    /// Whether the task being edited was just created with `o`/`O` and never saved
+    pub fn editing_new_task(&self) -> bool {
+        self.state
+            .editing_task
+            .as_ref()
+            .is_some_and(|task| self.unsaved_task.as_ref() == Some(&task.id))
+    }
+
+    pub fn save_editing_task(&mut self) -> Result<(), DbError> {
+        if let Some(editing_task) = &self.state.editing_task {
+            if let Some(index) = self
+                .state
+                .tasks
+                .iter()
+                .position(|t| t.id == editing_task.id)
+            {
+                let edited = editing_task.clone();
+                self.mutate_atomic(|tasks| tasks[index] = edited)?;
+            }
+        }
+        self.state.editing_task = None;
+        Ok(())
+    }
+
+    /// Fields Tab cycles through in Insert mode, in the configured order
+    pub fn edit_fields(&self) -> &[EditField] {
+        let fields = &self.state.config.ui.edit_fields;
+        if fields.is_empty() {
+            &EditField::ALL
+        } else {
+            fields
+        }
+    }
+
+    /// The field at `index` in the editing rotation
+    ///
+    /// An index past the end, left over from a longer field list, is clamped
+    /// to the last field so edits are never silently dropped.
+    pub fn edit_field(&self, index: usize) -> EditField {
+        let fields = self.edit_fields();
+        fields[index.min(fields.len() - 1)]
+    }
+
+    /// Status bar label of the field at `index` in the editing rotation
+    pub fn field_label(&self, index: usize) -> &'static str {
+        self.edit_field(index).label()
+    }
+
+    /// Move the editing rotation to the next (or previous) field, wrapping around
+    pub fn step_editing_field(&mut self, forward: bool) {
+        let count = self.edit_fields().len();
+        let current = self.state.editing_field.min(count - 1);
+        self.state.editing_field = if forward {
+            (current + 1) % count
+        } else {
+            (current + count - 1) % count
+        };
+    }
+
+    pub fn update_editing_task_field(&mut self, field_index: usize, value: String) {
+        let field = self.edit_field(field_index);
+        // Parse dates outside the mutable borrow to avoid borrowing conflicts
+        let parsed_date = if matches!(field, EditField::Due | EditField::DeferUntil) {
+            // Due dates default to the due hour, defer dates to the defer hour
+            let is_due_date = field == EditField::Due;
+            match self.try_parse_date_input(&value, is_due_date) {
+                Ok(date) => date,
+                Err(e @ DateParseError::TooFarInFuture) => {
+                    // Keep the existing date rather than silently clearing it
+                    self.set_error_message(e.to_string());
+                    return;
+                }
+                Err(DateParseError::Unrecognized(_)) => None,
+            }
+        } else {
+            None
+        };
+
+        if let Some(ref mut editing_task) = self.state.editing_task {
+            match field {
+                EditField::Title => editing_task.title = value,
+                EditField::Description => editing_task.description = value,
+                EditField::Due => editing_task.due = parsed_date,
+                EditField::DeferUntil => editing_task.defer_until = parsed_date,
+                EditField::Tags => editing_task.tags = parse_tags(&value),
+                EditField::Notes => editing_task.notes = value,
+            }
+        }
+    }
+
+    pub fn get_editing_task_field(&self, field_index: usize) -> String {
+        if let Some(ref editing_task) = self.state.editing_task {
+            match self.edit_field(field_index) {
+                EditField::Title => editing_task.title.clone(),
+                EditField::Description => editing_task.description.clone(),
+                EditField::Due => self.format_date_for_editing(editing_task.due),
+                EditField::DeferUntil => self.format_date_for_editing(editing_task.defer_until),
+                EditField::Tags => editing_task.tags.join(", "),
+                EditField::Notes => editing_task.notes.clone(),
+            }
+        } else {
+            String::new()
+        }
+    }
+
+    pub fn start_editing_current_task(&mut self) {
+        if let Some(selected_index) = self.cursor_task_index() {
+            if selected_index < self.state.tasks.len() {
+                self.state.editing_task = Some(self.state.tasks[selected_index].clone());
+                self.state.editing_field = 0;
+                self.load_editing_field();
+            }
+        }
+    }
+}
+
+/// `time` moved by a signed `offset`, or `None` if that leaves `SystemTime`'s range
+fn offset_time(time: SystemTime, offset: chrono::Duration) -> Option<SystemTime> {
+    let magnitude = offset.abs().to_std().ok()?;
+    if offset < chrono::Duration::zero() {
+        time.checked_sub(magnitude)
+    } else {
+        time.checked_add(magnitude)
+    }
+}
+
+/// Tags typed as a comma-separated list, trimmed, without blanks or repeats
+fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in input.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        if !tags.iter().any(|t| t == tag) {
+            tags.push(tag.to_string());
+        }
+    }
+    tags
+}
+
+/// Parse a clock time as (hour, minute): `3pm`, `9:30am`, `09:30` or `14:00`
+///
+/// A bare number such as `9` is not a time, so it can't be mistaken for a
+/// date; 12-hour times need `am`/`pm` and 24-hour times need the minutes.
+fn parse_clock_time(input: &str) -> Option<(u32, u32)> {
+    let (clock, meridiem) = match input.strip_suffix("am") {
+        Some(clock) => (clock, Some(0)),
+        None => match input.strip_suffix("pm") {
+            Some(clock) => (clock, Some(12)),
+            None => (input, None),
+        },
+    };
+    let (hour, minute) = match clock.split_once(':') {
+        Some((hour, minute)) if minute.len() == 2 => (hour, minute),
+        Some(_) => return None,
+        None if meridiem.is_some() => (clock, "00"),
+        None => return None,
+    };
+    let all_digits =
+        |s: &str| !s.is_empty() && s.len() <= 2 && s.bytes().all(|b| b.is_ascii_digit());
+    if !all_digits(hour) || !all_digits(minute) {
+        return None;
+    }
+    let (hour, minute): (u32, u32) = (hour.parse().ok()?, minute.parse().ok()?);
+    let hour = match meridiem {
+        // 12am is midnight and 12pm noon
+        Some(offset) if (1..=12).contains(&hour) => hour % 12 + offset,
+        Some(_) => return None,
+        None if hour < 24 => hour,
+        None => return None,
+    };
+    (minute < 60).then_some((hour, minute))
+}
+
+/// Time spent on a task in whole minutes: `12m`, or `1h 05m` from an hour on
+fn format_elapsed(elapsed: Duration) -> String {
+    let minutes = elapsed.as_secs() / 60;
+    if minutes < 60 {
+        format!("{minutes}m")
+    } else {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    }
+}
+
+/// Move the task at each index in `changes` to its status, returning the
+/// titles of tasks left open because of their open subtasks
+///
+/// Completing a task with open subtasks finishes them too when `cascade` is
+/// set, and is refused otherwise. Completing a repeating task appends its
+/// next occurrence; reopening one removes the occurrence its last completion
+/// added, unless that has been completed since. Tasks are only added and
+/// removed once every status is set, so the indices stay valid.
+fn apply_statuses(
+    tasks: &mut Vec<Task>,
+    changes: &[(usize, TaskStatus)],
+    cascade: bool,
+    now: SystemTime,
+) -> Vec<String> {
+    let mut blocked = Vec::new();
+    let mut next_occurrences = Vec::new();
+    let mut withdrawn = Vec::new();
+    for &(index, status) in changes {
+        let Some(task) = tasks.get_mut(index) else {
+            continue;
+        };
+        let completing = status == TaskStatus::Done && !task.completed;
+        if completing && task.subtasks.iter().any(|s| !s.completed) {
+            if !cascade {
+                blocked.push(task.title.clone());
+                continue;
+            }
+            for subtask in &mut task.subtasks {
+                subtask.completed = true;
+            }
+        }
+        if task.completed && status != TaskStatus::Done {
+            withdrawn.extend(task.next_occurrence_id.take());
+        }
+        task.set_status(status);
+        if completing {
+            if let Some(next) = task.next_occurrence(now) {
+                task.next_occurrence_id = Some(next.id.clone());
+                next_occurrences.push(next);
+            }
+        }
+    }
+    tasks.retain(|task| task.completed || !withdrawn.contains(&task.id));
+    tasks.extend(next_occurrences);
+    release_deferred_dependents(tasks);
+    blocked
+}
+
+/// Clear task-based deferrals whose task has been completed or deleted
+fn release_deferred_dependents(tasks: &mut [Task]) {
+    let open: HashSet<String> = tasks
+        .iter()
+        .filter(|t| !t.completed)
+        .map(|t| t.id.clone())
+        .collect();
+    for task in tasks.iter_mut() {
+        if task
+            .defer_until_task
+            .as_ref()
+            .is_some_and(|id| !open.contains(id))
+        {
+            task.defer_until_task = None;
+        }
+    }
+}
+
+/// Take `store` from holding `stored` to holding `tasks`, writing only what differs
+///
+/// Nothing is ever cleared: added and changed tasks are saved in one batch,
+/// and only then are tasks in `stored` that are no longer listed deleted, so
+/// a failure part way through never loses a task that is still listed. A
+/// task already gone from the store doesn't count as a failure.
+fn sync_store<D: Db>(store: &mut D, stored: &[Task], tasks: &[Task]) -> Result<(), DbError> {
+    let stored_by_id: HashMap<&str, &Task> = stored.iter().map(|t| (t.id.as_str(), t)).collect();
+    let changed: Vec<Task> = tasks
+        .iter()
+        .filter(|task| stored_by_id.get(task.id.as_str()) != Some(task))
+        .cloned()
+        .collect();
+    if !changed.is_empty() {
+        store.save_tasks(&changed)?;
+    }
+
+    let listed: HashSet<&str> = tasks.iter().map(|t| t.id.as_str()).collect();
+    for task in stored.iter().filter(|t| !listed.contains(t.id.as_str())) {
+        match store.delete_task(&task.id) {
+            Ok(()) | Err(DbError::NotFound(_)) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+pub enum SelectionIterator {
+    Multiple(std::vec::IntoIter<usize>),
+    Single(std::iter::Once<usize>),
+    Empty,
+}
+
+impl Iterator for SelectionIterator {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            SelectionIterator::Multiple(iter) => iter.next(),
+            SelectionIterator::Single(iter) => iter.next(),
+            SelectionIterator::Empty => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::time_tracking::TimeEntry;
+    use crate::types::{Priority, TaskStatus};
+    use chrono::Timelike;
+    use std::time::Duration;
+
+    fn create_test_task(id: &str, title: &str) -> Task {
+        Task {
+            id: id.to_string(),
+            title: title.to_string(),
+            description: String::new(),
+            completed: false,
+            created_at: SystemTime::now(),
+            due: None,
+            defer_until: None,
+            someday: false,
+            tags: Vec::new(),
+            energy: None,
+            source: TaskSource::Tui,
+            url: None,
+            notes: String::new(),
+            project: None,
+            waiting_since: None,
+            in_progress: false,
+            subtasks: Vec::new(),
+            defer_until_task: None,
+            recurrence: None,
+            modified_at: SystemTime::UNIX_EPOCH,
+            reminder_lead: None,
+            next_occurrence_id: None,
+            completed_at: None,
+            priority: Priority::Medium,
+        }
+    }
+
+    /// Storage whose writes fail once `fail` is set
+    struct FailingStorage {
+        inner: crate::storage::MemoryStorage,
+        fail: bool,
+    }
+
+    impl FailingStorage {
+        fn new() -> Self {
+            Self {
+                inner: crate::storage::MemoryStorage::new(HashMap::new()),
+                fail: false,
+            }
+        }
+    }
+
+    impl Db for FailingStorage {
+        fn load_tasks(&self) -> Result<Vec<Task>, DbError> {
+            self.inner.load_tasks()
+        }
+
+        fn save_task(&mut self, task: &Task) -> Result<(), DbError> {
+            if self.fail {
+                return Err(DbError::OperationFailed("disk full".to_string()));
+            }
+            self.inner.save_task(task)
+        }
+
+        fn delete_task(&mut self, task_id: &str) -> Result<(), DbError> {
+            if self.fail {
+                return Err(DbError::OperationFailed("disk full".to_string()));
+            }
+            self.inner.delete_task(task_id)
+        }
+
+        fn clear(&mut self) -> Result<(), DbError> {
+            if self.fail {
+                return Err(DbError::OperationFailed("disk full".to_string()));
+            }
+            self.inner.clear()
+        }
+
+        fn save_time_entry(&mut self, entry: &TimeEntry) -> Result<(), DbError> {
+            if self.fail {
+                return Err(DbError::OperationFailed("disk full".to_string()));
+            }
+            self.inner.save_time_entry(entry)
+        }
+
+        fn load_time_entries(&self, task_id: &str) -> Result<Vec<TimeEntry>, DbError> {
+            self.inner.load_time_entries(task_id)
+        }
+    }
+
+    #[test]
+    fn test_mutate_atomic_leaves_state_unchanged_on_error() {
+        let snapshot = |tasks: &[Task]| serde_json::to_string(tasks).unwrap();
+        let mut app = App::new(crate::types::AppState::new(FailingStorage::new()));
+        app.add_task("Keep me").unwrap();
+        let before = snapshot(&app.state.tasks);
+
+        app.state.store.fail = true;
+        let result = app.mutate_atomic(|tasks| {
+            tasks[0].title = "Changed".to_string();
+            tasks.push(Task::new("Extra", TaskSource::Tui, SystemTime::now()));
+        });
+        assert!(result.is_err());
+        assert_eq!(snapshot(&app.state.tasks), before);
+
+        // Selection edits roll back too, and say why
+        app.cursor_first_task();
+        app.execute_command("setenergy high").unwrap();
+        assert_eq!(snapshot(&app.state.tasks), before);
+        assert!(app.message.is_some());
+
+        app.cursor_first_task();
+        assert!(app.delete_tasks().is_err());
+        assert_eq!(snapshot(&app.state.tasks), before);
+
+        app.state.store.fail = false;
+        app.mutate_atomic(|tasks| tasks[0].title = "Changed".to_string())
+            .unwrap();
+        assert_eq!(app.state.tasks[0].title, "Changed");
+        assert_eq!(app.state.store.load_tasks().unwrap()[0].title, "Changed");
+    }
+
+    #[test]
+    fn test_fixed_clock_sets_created_at() {
+        let fixed = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let mut app = App::with_clock(crate::types::AppState::default(), Box::new(move || fixed));
+
+        assert_eq!(app.now(), fixed);
+        app.add_task("Clocked").unwrap();
+        assert_eq!(app.state.tasks[0].created_at, fixed);
+    }
+
+    #[test]
+    fn test_fixed_clock_drives_relative_dates() {
+        let fixed = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let app = App::with_clock(crate::types::AppState::default(), Box::new(move || fixed));
+
+        assert_eq!(
+            app.parse_date_input("2d", true),
+            Some(fixed + Duration::from_secs(2 * 24 * 60 * 60))
+        );
+    }
+
+    #[test]
+    fn test_checkpoint_due_after_interval() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let mut app = App::with_clock(crate::types::AppState::default(), Box::new(move || start));
+        app.state.config.maintenance.checkpoint_min = 5;
+
+        assert!(!app.checkpoint_due(start));
+        assert!(!app.checkpoint_due(start + Duration::from_secs(4 * 60 + 59)));
+        assert!(app.checkpoint_due(start + Duration::from_secs(5 * 60)));
+        // A clock that jumped backwards never triggers a checkpoint
+        assert!(!app.checkpoint_due(start - Duration::from_secs(60 * 60)));
+    }
+
+    #[test]
+    fn test_reminder_fires_lead_before_due() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let hour = Duration::from_secs(60 * 60);
+        let due = start + 3 * hour;
+        let now = std::rc::Rc::new(std::cell::Cell::new(start));
+        let clock_now = now.clone();
+        let mut app = App::with_clock(
+            crate::types::AppState::default(),
+            Box::new(move || clock_now.get()),
+        );
+        app.state.config.notifications.enabled = true;
+        let mut task = create_test_task("1", "Call the bank");
+        task.due = Some(due);
+        app.state.tasks = vec![task];
+        app.cursor_first_task();
+        app.execute_command("remind 1h").unwrap();
+        assert_eq!(app.state.tasks[0].reminder_lead, Some(hour));
+
+        // Step the clock a minute at a time: the reminder appears exactly an hour early
+        let minute = Duration::from_secs(60);
+        let mut fired = Vec::new();
+        let mut time = start;
+        while time < due + hour {
+            time += minute;
+            now.set(time);
+            app.clear_error_message();
+            app.tick();
+            if let Some(message) = app.message.take() {
+                fired.push((time, message));
+            }
+        }
+        let due_at = DateTime::<Local>::from(due).format("%H:%M");
+        assert_eq!(
+            fired,
+            vec![(
+                due - hour,
+                format!("Reminder: 'Call the bank' is due at {due_at}")
+            )]
+        );
+        // A clock that went back does not fire again
+        assert_eq!(app.due_reminder(due - hour), None);
+    }
+
+    #[test]
+    fn test_reminder_uses_default_lead_and_can_be_off() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let minute = Duration::from_secs(60);
+        let mut app = App::with_clock(crate::types::AppState::default(), Box::new(move || start));
+        let mut task = create_test_task("1", "Stand-up");
+        task.due = Some(start + 20 * minute);
+        app.state.tasks = vec![task];
+
+        // Off by default
+        assert_eq!(app.due_reminder(start + 30 * minute), None);
+
+        app.state.config.notifications.enabled = true;
+        app.state.config.notifications.lead_min = 15;
+        assert_eq!(app.due_reminder(start + 31 * minute), None);
+        app.last_reminder_check = start;
+        assert_eq!(app.due_reminder(start + 4 * minute), None);
+        let due = DateTime::<Local>::from(start + 20 * minute).format("%H:%M");
+        assert_eq!(
+            app.due_reminder(start + 5 * minute),
+            Some(format!("Reminder: 'Stand-up' is due at {due}"))
+        );
+        assert_eq!(app.due_reminder(start + 6 * minute), None);
+
+        assert!(app.execute_command("remind soon").is_err());
+    }
+
+    #[test]
+    fn test_completion_sets_transient_feedback() {
+        let now = std::rc::Rc::new(std::cell::Cell::new(SystemTime::UNIX_EPOCH));
+        let clock_now = now.clone();
+        let mut app = App::with_clock(
+            crate::types::AppState::default(),
+            Box::new(move || clock_now.get()),
+        );
+        app.state.tasks = vec![create_test_task("1", "Task 1")];
+        app.cursor_first_task();
+
+        app.toggle_task_completion().unwrap();
+        let expiry = SystemTime::UNIX_EPOCH + COMPLETION_FEEDBACK_TTL;
+        assert_eq!(app.completion_feedback("1"), Some(expiry));
+
+        now.set(expiry - Duration::from_millis(1));
+        app.tick();
+        assert_eq!(app.completion_feedback("1"), Some(expiry));
+
+        now.set(expiry);
+        app.tick();
+        assert_eq!(app.completion_feedback("1"), None);
+    }
+
+    #[test]
+    fn test_completion_undo_hint_expires() {
+        let now = std::rc::Rc::new(std::cell::Cell::new(SystemTime::UNIX_EPOCH));
+        let clock_now = now.clone();
+        let mut app = App::with_clock(
+            crate::types::AppState::default(),
+            Box::new(move || clock_now.get()),
+        );
+        app.state.tasks = vec![create_test_task("1", "Water plants")];
+        app.cursor_first_task();
+
+        app.toggle_task_completion().unwrap();
+        assert_eq!(
+            app.message.as_deref(),
+            Some("Completed 'Water plants' — press u to undo")
+        );
+
+        now.set(SystemTime::UNIX_EPOCH + UNDO_HINT_TTL - Duration::from_millis(1));
+        app.tick();
+        assert!(app.message.is_some());
+
+        now.set(SystemTime::UNIX_EPOCH + UNDO_HINT_TTL);
+        app.tick();
+        assert!(app.message.is_none());
+        // Undo outlives the hint
+        assert!(app.undo().unwrap());
+        assert!(!app.state.tasks[0].completed);
+    }
+
+    #[test]
+    fn test_undo_completion() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.state.tasks = vec![create_test_task("1", "Task 1")];
+        app.cursor_first_task();
+
+        app.toggle_task_completion().unwrap();
+        assert!(app.completion_feedback("1").is_some());
+        assert!(app.undo().unwrap());
+        assert!(!app.state.tasks[0].completed);
+        assert_eq!(app.completion_feedback("1"), None);
+        // Nothing older to undo
+        assert!(!app.undo().unwrap());
+    }
+
+    #[test]
+    fn test_multi_level_undo_and_redo() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.add_task("Write report").unwrap();
+        app.add_task("Call plumber").unwrap();
+        app.cursor_first_task();
+        app.execute_command("setenergy high").unwrap();
+        app.toggle_task_completion().unwrap();
+
+        assert!(app.undo().unwrap());
+        assert!(!app.state.tasks[0].completed);
+        assert_eq!(app.state.tasks[0].energy, Some(Energy::High));
+        assert!(app.undo().unwrap());
+        assert_eq!(app.state.tasks[0].energy, None);
+        assert!(app.undo().unwrap());
+        assert_eq!(app.state.tasks.len(), 1);
+        assert_eq!(app.state.store.load_tasks().unwrap().len(), 1);
+
+        assert!(app.redo().unwrap());
+        assert_eq!(app.state.tasks.len(), 2);
+        assert_eq!(app.state.tasks[1].title, "Call plumber");
+        assert!(app.redo().unwrap());
+        assert_eq!(app.state.tasks[0].energy, Some(Energy::High));
+
+        // A new change drops what could still be redone
+        app.cursor_first_task();
+        app.execute_command("setenergy low").unwrap();
+        assert!(!app.redo().unwrap());
+        assert!(app.undo().unwrap());
+        assert_eq!(app.state.tasks[0].energy, Some(Energy::High));
+    }
+
+    #[test]
+    fn test_undo_delete_restores_tasks_in_order() {
+        let mut app = App::new(crate::types::AppState::default());
+        for title in ["A", "B", "C", "D"] {
+            app.add_task(title).unwrap();
+        }
+        app.task_list_state.select(Some(1));
+        app.toggle_task_selection();
+        app.task_list_state.select(Some(3));
+        app.toggle_task_selection();
+        app.delete_tasks().unwrap();
+        let titles = |app: &App<crate::storage::MemoryStorage>| -> Vec<String> {
+            app.state.tasks.iter().map(|t| t.title.clone()).collect()
+        };
+        assert_eq!(titles(&app), vec!["A", "C"]);
+
+        assert!(app.undo().unwrap());
+        assert_eq!(titles(&app), vec!["A", "B", "C", "D"]);
+        // Storage is back in sync with the restored list
+        assert_eq!(app.state.store.load_tasks().unwrap().len(), 4);
+        assert_eq!(
+            app.state.store.get_task(&app.state.tasks[1].id).unwrap(),
+            Some(app.state.tasks[1].clone())
+        );
+    }
+
+    #[test]
+    fn test_undo_depth_caps_history() {
+        let mut state = crate::types::AppState::default();
+        state.config.ui.undo_depth = 2;
+        let mut app = App::new(state);
+        for title in ["A", "B", "C"] {
+            app.add_task(title).unwrap();
+        }
+
+        assert!(app.undo().unwrap());
+        assert!(app.undo().unwrap());
+        assert!(!app.undo().unwrap());
+        assert_eq!(app.state.tasks.len(), 1);
+        assert_eq!(app.state.tasks[0].title, "A");
+    }
+
+    #[test]
+    fn test_undo_removes_task_created_in_insert_mode() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.add_task("Existing").unwrap();
+        app.cursor_first_task();
+        app.create_task_below_cursor();
+        if let Some(task) = app.state.editing_task.as_mut() {
+            task.title = "New".to_string();
+        }
+        app.save_editing_task().unwrap();
+        assert_eq!(app.state.tasks.len(), 2);
+
+        assert!(app.undo().unwrap());
+        assert_eq!(app.state.tasks.len(), 1);
+        assert_eq!(app.state.tasks[0].title, "Existing");
+    }
+
+    #[test]
+    fn test_error_message_is_not_transient() {
+        let now = std::rc::Rc::new(std::cell::Cell::new(SystemTime::UNIX_EPOCH));
+        let clock_now = now.clone();
+        let mut app = App::with_clock(
+            crate::types::AppState::default(),
+            Box::new(move || clock_now.get()),
+        );
+        app.set_transient_message("Gone soon".to_string(), SystemTime::UNIX_EPOCH);
+        app.set_error_message("Stays".to_string());
+
+        now.set(SystemTime::UNIX_EPOCH + Duration::from_secs(60));
+        app.tick();
+        assert_eq!(app.message.as_deref(), Some("Stays"));
+    }
+
+    #[test]
+    fn test_completion_feedback_only_when_completing_and_enabled() {
+        let mut app = App::new(crate::types::AppState::default());
+        let mut done = create_test_task("1", "Done");
+        done.completed = true;
+        app.state.tasks = vec![done, create_test_task("2", "Open")];
+        app.cursor_first_task();
+
+        // Reopening a task gives no feedback
+        app.toggle_task_completion().unwrap();
+        assert_eq!(app.completion_feedback("1"), None);
+
+        app.state.config.ui.completion_feedback = false;
+        app.cursor_last_task();
+        app.toggle_task_completion().unwrap();
+        assert!(app.state.tasks[1].completed);
+        assert_eq!(app.completion_feedback("2"), None);
+    }
+
+    #[test]
+    fn test_confirmation_times_out_on_tick() {
+        let now = std::rc::Rc::new(std::cell::Cell::new(SystemTime::UNIX_EPOCH));
+        let clock_now = now.clone();
+        let mut app = App::with_clock(
+            crate::types::AppState::default(),
+            Box::new(move || clock_now.get()),
+        );
+        app.state.config.ui.confirm_timeout_secs = 5;
+        app.state.tasks = vec![create_test_task("1", "Task 1")];
+        app.cursor_first_task();
+
+        app.request_confirmation(ConfirmAction::DeleteTasks);
+        assert!(app.pending_confirm.is_some());
+        assert_eq!(app.message.as_deref(), Some("Delete 1 task? (y/n)"));
+
+        now.set(SystemTime::UNIX_EPOCH + Duration::from_secs(4));
+        app.tick();
+        assert!(app.pending_confirm.is_some());
+
+        now.set(SystemTime::UNIX_EPOCH + Duration::from_secs(5));
+        app.tick();
+        assert!(app.pending_confirm.is_none());
+        assert!(app.message.is_none());
+        assert_eq!(app.state.tasks.len(), 1);
+    }
+
+    #[test]
+    fn test_confirmation_without_timeout_waits() {
+        let now = std::rc::Rc::new(std::cell::Cell::new(SystemTime::UNIX_EPOCH));
+        let clock_now = now.clone();
+        let mut app = App::with_clock(
+            crate::types::AppState::default(),
+            Box::new(move || clock_now.get()),
+        );
+        app.state.tasks = vec![create_test_task("1", "Task 1")];
+        app.cursor_first_task();
+
+        app.request_confirmation(ConfirmAction::DeleteTasks);
+        now.set(SystemTime::UNIX_EPOCH + Duration::from_secs(60 * 60));
+        app.tick();
+        assert!(app.pending_confirm.is_some());
+
+        app.confirm_pending().unwrap();
+        assert!(app.state.tasks.is_empty());
+    }
+
+    #[test]
+    fn test_checkpoint_disabled_when_interval_zero() {
+        let start = SystemTime::UNIX_EPOCH;
+        let mut app = App::with_clock(crate::types::AppState::default(), Box::new(move || start));
+        app.state.config.maintenance.checkpoint_min = 0;
+
+        assert!(!app.checkpoint_due(start + Duration::from_secs(24 * 60 * 60)));
+    }
+
+    /// Storage that records which tasks are read and written and refuses to be cleared
+    struct RecordingStorage {
+        inner: crate::storage::MemoryStorage,
+        loads: std::cell::Cell<usize>,
+        saved: Vec<String>,
+        deleted: Vec<String>,
+    }
+
+    impl Db for RecordingStorage {
+        fn load_tasks(&self) -> Result<Vec<Task>, DbError> {
+            self.loads.set(self.loads.get() + 1);
+            self.inner.load_tasks()
+        }
+
+        fn save_task(&mut self, task: &Task) -> Result<(), DbError> {
+            self.saved.push(task.id.clone());
+            self.inner.save_task(task)
+        }
+
+        fn delete_task(&mut self, task_id: &str) -> Result<(), DbError> {
+            self.deleted.push(task_id.to_string());
+            self.inner.delete_task(task_id)
+        }
+
+        fn clear(&mut self) -> Result<(), DbError> {
+            panic!("storage was cleared")
+        }
+    }
+
+    /// Storage whose `fail_on`-th save fails, as if the process died mid-write
+    struct FlakyStorage {
+        inner: crate::storage::MemoryStorage,
+        saves: usize,
+        fail_on: usize,
+    }
+
+    impl Db for FlakyStorage {
+        fn load_tasks(&self) -> Result<Vec<Task>, DbError> {
+            self.inner.load_tasks()
+        }
+
+        fn save_task(&mut self, task: &Task) -> Result<(), DbError> {
+            self.saves += 1;
+            if self.saves == self.fail_on {
+                return Err(DbError::OperationFailed("interrupted".to_string()));
+            }
+            self.inner.save_task(task)
+        }
+
+        fn delete_task(&mut self, task_id: &str) -> Result<(), DbError> {
+            self.inner.delete_task(task_id)
+        }
+
+        fn clear(&mut self) -> Result<(), DbError> {
+            panic!("storage was cleared")
+        }
+    }
+
+    #[test]
+    fn test_partial_write_failure_loses_no_saved_task() {
+        let tasks: Vec<Task> = (1..=3)
+            .map(|i| create_test_task(&i.to_string(), "Task"))
+            .collect();
+        let mut inner = crate::storage::MemoryStorage::new(HashMap::new());
+        inner.save_tasks(&tasks).unwrap();
+        let mut state = AppState::new(FlakyStorage {
+            inner,
+            saves: 0,
+            fail_on: 2,
+        });
+        state.tasks = tasks.clone();
+        let mut app = App::new(state);
+
+        // The second of two saves fails, before task 3 would be deleted
+        let result = app.mutate_atomic(|tasks| {
+            tasks[0].title = "Changed".to_string();
+            tasks[1].title = "Changed".to_string();
+            tasks.remove(2);
+        });
+        assert!(result.is_err());
+
+        let mut stored = app.state.store.load_tasks().unwrap();
+        stored.sort_by(|a, b| a.id.cmp(&b.id));
+        assert_eq!(stored, tasks);
+        assert_eq!(app.state.tasks, tasks);
+    }
+
+    #[test]
+    fn test_mutate_atomic_writes_only_changed_tasks() {
+        let tasks: Vec<Task> = (1..=4)
+            .map(|i| create_test_task(&i.to_string(), "Task"))
+            .collect();
+        let mut inner = crate::storage::MemoryStorage::new(HashMap::new());
+        inner.save_tasks(&tasks).unwrap();
+        let mut state = AppState::new(RecordingStorage {
+            inner,
+            loads: std::cell::Cell::new(0),
+            saved: Vec::new(),
+            deleted: Vec::new(),
+        });
+        state.tasks = tasks;
+        let mut app = App::new(state);
+
+        app.mutate_atomic(|tasks| {
+            tasks[1].title = "Changed".to_string();
+            tasks.remove(3);
+        })
+        .unwrap();
+
+        // The change is worked out from memory, without reading storage back
+        assert_eq!(app.state.store.loads.get(), 0);
+        assert_eq!(app.state.store.saved, vec!["2"]);
+        assert_eq!(app.state.store.deleted, vec!["4"]);
+        let stored = app.state.store.load_tasks().unwrap();
+        assert_eq!(stored.len(), 3);
+        assert!(stored.iter().any(|t| t.id == "2" && t.title == "Changed"));
+    }
+
+    /// Storage that counts checkpoints
+    struct CheckpointCounter {
+        inner: crate::storage::MemoryStorage,
+        checkpoints: usize,
+    }
+
+    impl Db for CheckpointCounter {
+        fn load_tasks(&self) -> Result<Vec<Task>, DbError> {
+            self.inner.load_tasks()
+        }
+
+        fn save_task(&mut self, task: &Task) -> Result<(), DbError> {
+            self.inner.save_task(task)
+        }
+
+        fn delete_task(&mut self, task_id: &str) -> Result<(), DbError> {
+            self.inner.delete_task(task_id)
+        }
+
+        fn clear(&mut self) -> Result<(), DbError> {
+            self.inner.clear()
+        }
+
+        fn checkpoint(&mut self) -> Result<(), DbError> {
+            self.checkpoints += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_shutdown_checkpoints_only_with_quit_flush() {
+        let store = CheckpointCounter {
+            inner: crate::storage::MemoryStorage::new(HashMap::new()),
+            checkpoints: 0,
+        };
+        let mut app = App::new(crate::types::AppState::new(store));
+        assert!(app.state.config.behavior.quit_flush);
+
+        app.shutdown().unwrap();
+        assert_eq!(app.state.store.checkpoints, 1);
+
+        app.state.config.behavior.quit_flush = false;
+        app.shutdown().unwrap();
+        assert_eq!(app.state.store.checkpoints, 1);
+    }
+
+    #[test]
+    fn test_tick_keeps_list_stable_unless_live_resort() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let now = std::rc::Rc::new(std::cell::Cell::new(start));
+        let clock_now = now.clone();
+        let mut app = App::with_clock(
+            crate::types::AppState::default(),
+            Box::new(move || clock_now.get()),
+        );
+        let mut task = create_test_task("1", "Ship it");
+        task.due = Some(start);
+        task.defer_until = Some(start + Duration::from_secs(60));
+        app.state.tasks = vec![task];
+        app.set_view(View::Today);
+        app.state.config.maintenance.checkpoint_min = 0;
+
+        app.tick();
+        now.set(start + Duration::from_secs(120));
+        app.tick();
+        // The deferral has passed, but the list waits for the user
+        assert!(app.visible_task_indices().is_empty());
+        app.refresh_list();
+        assert_eq!(app.visible_task_indices(), vec![0]);
+
+        app.state.tasks[0].defer_until = Some(start + Duration::from_secs(180));
+        app.state.config.ui.live_resort = true;
+        app.tick();
+        assert!(app.visible_task_indices().is_empty());
+        now.set(start + Duration::from_secs(240));
+        app.tick();
+        assert_eq!(app.visible_task_indices(), vec![0]);
+    }
+
+    #[test]
+    fn test_checkpoint_resets_timer() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let now = std::rc::Rc::new(std::cell::Cell::new(start));
+        let clock_now = now.clone();
+        let mut app = App::with_clock(
+            crate::types::AppState::default(),
+            Box::new(move || clock_now.get()),
+        );
+        app.state.config.maintenance.checkpoint_min = 1;
+
+        now.set(start + Duration::from_secs(90));
+        assert!(app.checkpoint_due(app.now()));
+        app.tick();
+        assert!(!app.checkpoint_due(app.now()));
+        assert!(app.get_error_message().is_none());
+    }
+
+    #[test]
+    fn test_next_and_previous_urgent_wrap_around() {
+        let mut app = App::new(crate::types::AppState::default());
+        let now = SystemTime::now();
+        let mut overdue = create_test_task("1", "Overdue");
+        overdue.due = Some(now - Duration::from_secs(60 * 60));
+        let mut later = create_test_task("3", "Next week");
+        later.due = Some(now + Duration::from_secs(7 * 24 * 60 * 60));
+        let mut due_now = create_test_task("4", "Due now");
+        due_now.due = Some(now);
+        app.state.tasks = vec![
+            create_test_task("0", "No date"),
+            overdue,
+            create_test_task("2", "No date"),
+            later,
+            due_now,
+        ];
+
+        assert_eq!(app.next_urgent(0), Some(1));
+        assert_eq!(app.next_urgent(1), Some(4));
+        assert_eq!(app.next_urgent(4), Some(1));
+        assert_eq!(app.previous_urgent(4), Some(1));
+        assert_eq!(app.previous_urgent(1), Some(4));
+        assert_eq!(app.previous_urgent(0), Some(4));
+    }
+
+    #[test]
+    fn test_next_urgent_none_when_nothing_urgent() {
+        let mut app = App::new(crate::types::AppState::default());
+        let mut done = create_test_task("1", "Done");
+        done.completed = true;
+        done.due = Some(SystemTime::now() - Duration::from_secs(60));
+        app.state.tasks = vec![create_test_task("0", "No date"), done];
+        app.task_list_state.select(Some(0));
+
+        assert_eq!(app.next_urgent(0), None);
+        assert_eq!(app.previous_urgent(0), None);
+        app.cursor_next_urgent_task();
+        assert_eq!(app.task_list_state.selected(), Some(0));
+    }
+
+    #[test]
+    fn test_overdue_count_excludes_someday_tasks() {
+        let mut app = App::new(crate::types::AppState::default());
+        let past = SystemTime::now() - Duration::from_secs(60 * 60);
+
+        let mut overdue = create_test_task("1", "Overdue");
+        overdue.due = Some(past);
+        let mut parked = create_test_task("2", "Parked");
+        parked.due = Some(past);
+        parked.someday = true;
+        app.state.tasks = vec![overdue, parked];
+
+        assert_eq!(app.overdue_count(), 1);
+    }
+
+    #[test]
+    fn test_toggle_someday_and_someday_view() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.state.tasks = vec![
+            create_test_task("1", "Active"),
+            create_test_task("2", "Maybe later"),
+        ];
+        app.task_list_state.select(Some(1));
+        app.toggle_someday().unwrap();
+        assert!(app.state.tasks[1].someday);
+
+        app.execute_command("someday").unwrap();
+        assert_eq!(app.state.view, View::Someday);
+        assert_eq!(app.visible_task_indices(), vec![1]);
+        assert_eq!(app.cursor_task_index(), Some(1));
+
+        app.execute_command("all").unwrap();
+        assert_eq!(app.visible_task_indices(), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_filter_command_due_tokens() {
+        let mut app = App::new(crate::types::AppState::default());
+        let mut scheduled = create_test_task("1", "Dentist");
+        scheduled.due = Some(SystemTime::now() + Duration::from_secs(60 * 60));
+        app.state.tasks = vec![scheduled, create_test_task("2", "Read a book")];
+
+        app.execute_command("filter due:none").unwrap();
+        assert_eq!(app.visible_task_indices(), vec![1]);
+
+        app.execute_command("filter due:any").unwrap();
+        assert_eq!(app.visible_task_indices(), vec![0]);
+
+        app.execute_command("filter").unwrap();
+        assert_eq!(app.visible_task_indices(), vec![0, 1]);
+
+        assert!(app.execute_command("filter due:later").is_err());
+    }
+
+    #[test]
+    fn test_launch_applies_default_view() {
+        let mut state = crate::types::AppState::default();
+        state.config.ui.default_view = View::Today;
+        let mut due_today = create_test_task("1", "Due today");
+        due_today.due = Some(SystemTime::now());
+        state.tasks = vec![due_today, create_test_task("2", "Unscheduled")];
+
+        let app = App::new(state);
+        assert_eq!(app.state.view, View::Today);
+        assert_eq!(app.visible_task_indices(), vec![0]);
+    }
+
+    #[test]
+    fn test_launch_shows_config_warnings() {
+        assert!(App::new(crate::types::AppState::default())
+            .message
+            .is_none());
+
+        let mut state = crate::types::AppState::default();
+        state.config.warnings = vec!["Invalid date format: %Q. Using %Y-%m-%d.".to_string()];
+        let app = App::new(state);
+        assert_eq!(
+            app.message.as_deref(),
+            Some("Warning: Invalid date format: %Q. Using %Y-%m-%d.")
+        );
+    }
+
+    #[test]
+    fn test_field_label_follows_custom_field_order() {
+        let mut app = App::new(crate::types::AppState::default());
+        assert_eq!(app.field_label(0), "Title");
+        assert_eq!(app.field_label(2), "Due Date");
+
+        app.state.config.ui.edit_fields = vec![EditField::Due, EditField::Title];
+        assert_eq!(app.field_label(0), "Due Date");
+        assert_eq!(app.field_label(1), "Title");
+
+        app.state.tasks = vec![create_test_task("1", "Reordered")];
+        app.cursor_first_task();
+        app.start_editing_current_task();
+        app.update_editing_task_field(1, "Renamed".to_string());
+        assert_eq!(app.state.editing_task.as_ref().unwrap().title, "Renamed");
+        assert_eq!(app.get_editing_task_field(1), "Renamed");
+    }
+
+    fn due_task(id: &str, due_secs: Option<u64>) -> Task {
+        let mut task = create_test_task(id, id);
+        task.due = due_secs.map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs));
+        task
+    }
+
+    #[test]
+    fn test_reverse_due_ascending_sort() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.state.tasks = vec![
+            due_task("b", Some(200)),
+            due_task("none", None),
+            due_task("a", Some(100)),
+            due_task("c", Some(300)),
+        ];
+
+        app.cycle_sort_key();
+        assert_eq!(app.sort_key, SortKey::Due);
+        assert_eq!(app.visible_task_indices(), vec![2, 0, 3, 1]);
+
+        app.toggle_sort_direction();
+        assert_eq!(app.sort_key, SortKey::Due);
+        assert!(app.sort_desc);
+        // Unscheduled tasks stay last in both directions
+        assert_eq!(app.visible_task_indices(), vec![3, 0, 2, 1]);
+    }
+
+    #[test]
+    fn test_cycle_priority_and_sort_by_priority() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.state.tasks = vec![
+            create_test_task("1", "Medium"),
+            create_test_task("2", "High"),
+            create_test_task("3", "Low"),
+        ];
+        app.task_list_state.select(Some(1));
+        app.cycle_priority().unwrap();
+        assert_eq!(app.state.tasks[1].priority, Priority::High);
+        app.task_list_state.select(Some(2));
+        app.cycle_priority().unwrap();
+        app.cycle_priority().unwrap();
+        assert_eq!(app.state.tasks[2].priority, Priority::Low);
+
+        app.sort_key = SortKey::Priority;
+        assert_eq!(app.visible_task_indices(), vec![1, 0, 2]);
+        app.sort_desc = true;
+        assert_eq!(app.visible_task_indices(), vec![2, 0, 1]);
+    }
+
+    #[test]
+    fn test_filtered_task_indices_matches_title_or_description() {
+        let mut app = App::new(crate::types::AppState::default());
+        let mut invoice = create_test_task("2", "Invoice");
+        invoice.description = "For the ACME account".to_string();
+        let mut done = create_test_task("3", "Call Acme back");
+        done.completed = true;
+        app.state.tasks = vec![create_test_task("1", "Groceries"), invoice, done];
+
+        assert_eq!(app.filtered_task_indices("acme"), vec![1, 2]);
+        assert_eq!(app.filtered_task_indices("GROC"), vec![0]);
+        assert_eq!(app.filtered_task_indices(""), vec![0, 1, 2]);
+        assert!(app.filtered_task_indices("nothing").is_empty());
+
+        // Search narrows the active view rather than replacing it
+        app.set_view(View::Done);
+        assert_eq!(app.filtered_task_indices("acme"), vec![2]);
+    }
+
+    #[test]
+    fn test_toggle_timer_shows_status_and_saves_sessions() {
+        let minute = Duration::from_secs(60);
+        let now = std::rc::Rc::new(std::cell::Cell::new(SystemTime::UNIX_EPOCH));
+        let clock_now = now.clone();
+        let mut app = App::with_clock(
+            crate::types::AppState::default(),
+            Box::new(move || clock_now.get()),
+        );
+        app.state.tasks = vec![
+            create_test_task("1", "Write report"),
+            create_test_task("2", "Email"),
+        ];
+        app.task_list_state.select(Some(0));
+        assert_eq!(app.timer_status(), None);
+
+        app.toggle_timer().unwrap();
+        now.set(SystemTime::UNIX_EPOCH + 12 * minute);
+        assert_eq!(
+            app.timer_status().as_deref(),
+            Some("⏱ Working on \"Write report\" (12m)")
+        );
+
+        // Timing another task stops the first
+        app.task_list_state.select(Some(1));
+        app.toggle_timer().unwrap();
+        now.set(SystemTime::UNIX_EPOCH + 80 * minute);
+        assert_eq!(
+            app.timer_status().as_deref(),
+            Some("⏱ Working on \"Email\" (1h 08m)")
+        );
+        app.toggle_timer().unwrap();
+        assert_eq!(app.timer_status(), None);
+        assert_eq!(
+            app.message.as_deref(),
+            Some("Stopped timer on 'Email' after 1h 08m")
+        );
+
+        let saved = app.state.store.load_time_entries("1").unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].duration, Some(12 * minute));
+        let saved = app.state.store.load_time_entries("2").unwrap();
+        assert_eq!(saved[0].duration, Some(68 * minute));
+    }
+
+    #[test]
+    fn test_toggle_timer_keeps_the_timer_when_saving_fails() {
+        let mut app = App::new(AppState::new(FailingStorage::new()));
+        app.state.tasks = vec![create_test_task("1", "Write report")];
+        app.task_list_state.select(Some(0));
+        app.toggle_timer().unwrap();
+        let running = app.timer_status();
+
+        app.state.store.fail = true;
+        assert!(app.toggle_timer().is_err());
+        assert_eq!(app.timer_status(), running);
+        assert_eq!(
+            app.message.as_deref(),
+            Some("Started timer on 'Write report'")
+        );
+        assert!(app.shutdown().is_err());
+        assert_eq!(app.timer_status(), running);
+
+        // Once storage recovers, stopping saves the session
+        app.state.store.fail = false;
+        app.toggle_timer().unwrap();
+        assert_eq!(app.timer_status(), None);
+        assert_eq!(app.state.store.load_time_entries("1").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_shutdown_saves_the_running_timer() {
+        let now = std::rc::Rc::new(std::cell::Cell::new(SystemTime::UNIX_EPOCH));
+        let clock_now = now.clone();
+        let mut app = App::with_clock(
+            crate::types::AppState::default(),
+            Box::new(move || clock_now.get()),
+        );
+        app.state.tasks = vec![create_test_task("1", "Write report")];
+        app.task_list_state.select(Some(0));
+        app.toggle_timer().unwrap();
+        now.set(SystemTime::UNIX_EPOCH + Duration::from_secs(25 * 60));
+
+        app.shutdown().unwrap();
+        assert_eq!(app.timer_status(), None);
+        let saved = app.state.store.load_time_entries("1").unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].duration, Some(Duration::from_secs(25 * 60)));
+    }
+
+    #[test]
+    fn test_sort_by_title_and_created() {
+        let mut app = App::new(crate::types::AppState::default());
+        let mut older = create_test_task("1", "banana");
+        older.created_at = SystemTime::UNIX_EPOCH;
+        app.state.tasks = vec![
+            create_test_task("2", "Cherry"),
+            older,
+            create_test_task("3", "apple"),
+        ];
+
+        app.sort_key = SortKey::Title;
+        assert_eq!(app.visible_task_indices(), vec![2, 1, 0]);
+        app.sort_key = SortKey::Created;
+        assert_eq!(app.visible_task_indices()[0], 1);
+        app.sort_key = SortKey::Manual;
+        app.sort_desc = true;
+        assert_eq!(app.visible_task_indices(), vec![2, 1, 0]);
+    }
+
+    #[test]
+    fn test_sort_keeps_cursor_on_same_task() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.state.tasks = vec![due_task("late", Some(300)), due_task("early", Some(100))];
+        app.cursor_first_task();
+        assert_eq!(app.cursor_task_index(), Some(0));
+
+        app.cycle_sort_key();
+        assert_eq!(app.cursor_task_index(), Some(0));
+        app.toggle_sort_direction();
+        assert_eq!(app.cursor_task_index(), Some(0));
+    }
+
+    #[test]
+    fn test_out_of_range_editing_field_is_clamped() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.state.tasks = vec![create_test_task("1", "Task")];
+        app.cursor_first_task();
+        app.start_editing_current_task();
+        app.state.config.ui.edit_fields = vec![EditField::Title, EditField::Description];
+        app.state.editing_field = 4;
+
+        assert_eq!(app.field_label(4), "Description");
+        app.update_editing_task_field(4, "Edited".to_string());
+        assert_eq!(
+            app.state.editing_task.as_ref().unwrap().description,
+            "Edited"
+        );
+        assert_eq!(app.get_editing_task_field(4), "Edited");
+
+        // Stepping starts from the clamped field
+        app.step_editing_field(true);
+        assert_eq!(app.state.editing_field, 0);
+        app.state.editing_field = 9;
+        app.step_editing_field(false);
+        assert_eq!(app.state.editing_field, 0);
+        app.step_editing_field(false);
+        assert_eq!(app.state.editing_field, 1);
+    }
+
+    #[test]
+    fn test_unscheduled_view() {
+        let mut app = App::new(crate::types::AppState::default());
+        let mut scheduled = create_test_task("1", "Dentist");
+        scheduled.due = Some(SystemTime::now());
+        app.state.tasks = vec![scheduled, create_test_task("2", "Read a book")];
+
+        app.execute_command("unscheduled").unwrap();
+        assert_eq!(app.visible_task_indices(), vec![1]);
+    }
+
+    #[test]
+    fn test_session_snapshot_round_trips_without_storage() {
+        /// Storage that fails the test if it is touched at all
+        struct UntouchedStorage;
+
+        impl Db for UntouchedStorage {
+            fn load_tasks(&self) -> Result<Vec<Task>, DbError> {
+                panic!("storage was loaded")
+            }
+            fn save_task(&mut self, _: &Task) -> Result<(), DbError> {
+                panic!("storage was written")
+            }
+            fn delete_task(&mut self, _: &str) -> Result<(), DbError> {
+                panic!("storage was written")
+            }
+            fn clear(&mut self) -> Result<(), DbError> {
+                panic!("storage was cleared")
+            }
+        }
+
+        let tasks = vec![
+            create_test_task("1", "Write report"),
+            create_test_task("2", "Call plumber"),
+            create_test_task("3", "Write tests"),
+        ];
+        let mut app = App::new(AppState::new(UntouchedStorage));
+        app.state.tasks = tasks.clone();
+        app.set_query(Query::parse("write").unwrap());
+        app.cursor_last_task();
+        app.state.mode = Mode::Command;
+
+        let json = serde_json::to_string(&app.session_snapshot()).unwrap();
+        let snapshot: SessionSnapshot = serde_json::from_str(&json).unwrap();
+        assert_eq!(snapshot.cursor_task_id.as_deref(), Some("3"));
+
+        let mut restored = App::new(AppState::new(UntouchedStorage));
+        restored.state.tasks = tasks;
+        restored.restore_session(snapshot);
+        assert_eq!(restored.state.mode, Mode::Command);
+        assert_eq!(restored.state.query, app.state.query);
+        assert_eq!(restored.cursor_task_index(), Some(2));
+    }
+
+    #[test]
+    fn test_flag_stale_for_review() {
+        let day = Duration::from_secs(24 * 60 * 60);
+        let now = SystemTime::UNIX_EPOCH + 100 * day;
+        let mut app = App::with_clock(crate::types::AppState::default(), Box::new(move || now));
+        let aged = |id: &str, age: Duration| {
+            let mut task = create_test_task(id, id);
+            task.created_at = now - age;
+            task
+        };
+        let mut done = aged("done", 30 * day);
+        done.completed = true;
+        app.state.tasks = vec![
+            aged("old", 30 * day),
+            aged("fresh", day),
+            aged("exactly", 14 * day),
+            done,
+        ];
+
+        // Off by default
+        assert!(app.flag_stale_for_review(now).is_empty());
+
+        app.state.config.maintenance.stale_review_days = 14;
+        assert_eq!(app.flag_stale_for_review(now), vec!["old", "exactly"]);
+
+        app.execute_command("review").unwrap();
+        assert_eq!(app.state.view, View::Review);
+        assert_eq!(app.visible_task_indices(), vec![0, 2]);
+
+        // A recent change makes an old task fresh again
+        app.state.tasks[0].modified_at = now - day;
+        assert_eq!(app.flag_stale_for_review(now), vec!["exactly"]);
+    }
+
+    #[test]
+    fn test_modified_at_tracks_edits() {
+        let now = std::rc::Rc::new(std::cell::Cell::new(SystemTime::UNIX_EPOCH));
+        let clock_now = now.clone();
+        let mut app = App::with_clock(
+            crate::types::AppState::default(),
+            Box::new(move || clock_now.get()),
+        );
+        now.set(SystemTime::UNIX_EPOCH + Duration::from_secs(100));
+        app.add_task("Write report").unwrap();
+        let created = app.state.tasks[0].created_at;
+        assert_eq!(app.state.tasks[0].modified_at, created);
+
+        now.set(created + Duration::from_secs(60));
+        app.cursor_first_task();
+        app.execute_command("setenergy high").unwrap();
+        assert_eq!(app.state.tasks[0].modified_at, now.get());
+        assert_eq!(app.state.tasks[0].created_at, created);
+        assert_eq!(
+            app.state.store.load_tasks().unwrap()[0].modified_at,
+            now.get()
+        );
+
+        // Untouched tasks keep their timestamp
+        app.add_task("Call plumber").unwrap();
+        now.set(now.get() + Duration::from_secs(60));
+        app.cursor_first_task();
+        app.execute_command("setenergy low").unwrap();
+        assert_eq!(
+            app.state.tasks[1].modified_at,
+            created + Duration::from_secs(60)
+        );
+    }
+
+    #[test]
+    fn test_hide_deferred_until_defer_time_passes() {
+        let now = std::rc::Rc::new(std::cell::Cell::new(SystemTime::UNIX_EPOCH));
+        let clock_now = now.clone();
+        let mut app = App::with_clock(
+            crate::types::AppState::default(),
+            Box::new(move || clock_now.get()),
+        );
+        let defer_until = SystemTime::UNIX_EPOCH + Duration::from_secs(3600);
+        let mut deferred = create_test_task("1", "Renew passport");
+        deferred.defer_until = Some(defer_until);
+        app.state.tasks = vec![deferred, create_test_task("2", "Pay rent")];
+
+        // Off by default: deferred tasks are only dimmed
+        assert_eq!(app.visible_task_indices(), vec![0, 1]);
+
+        app.state.config.ui.hide_deferred = true;
+        assert_eq!(app.visible_task_indices(), vec![1]);
+        app.execute_command("deferred").unwrap();
+        assert_eq!(app.visible_task_indices(), vec![0]);
+
+        app.execute_command("all").unwrap();
+        now.set(defer_until);
+        app.refresh_list();
+        assert_eq!(app.visible_task_indices(), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_editing_task_advances_modified_at_but_not_created_at() {
+        let now = std::rc::Rc::new(std::cell::Cell::new(SystemTime::UNIX_EPOCH));
+        let clock_now = now.clone();
+        let mut app = App::with_clock(
+            crate::types::AppState::default(),
+            Box::new(move || clock_now.get()),
+        );
+        app.add_task("Write report").unwrap();
+        app.add_task("Call plumber").unwrap();
+        let created = app.state.tasks[0].created_at;
+
+        let edited_at = created + Duration::from_secs(60);
+        now.set(edited_at);
+        app.cursor_first_task();
+        app.start_editing_current_task();
+        app.update_editing_task_field(0, "Write quarterly report".to_string());
+        app.save_editing_task().unwrap();
+
+        let task = &app.state.tasks[0];
+        assert_eq!(task.title, "Write quarterly report");
+        assert_eq!(task.created_at, created);
+        assert_eq!(task.modified_at, edited_at);
+        assert_eq!(app.state.tasks[1].modified_at, created);
+
+        // Sorting by modification lists the edited task first
+        app.sort_key = SortKey::Modified;
+        app.state.tasks.swap(0, 1);
+        assert_eq!(app.visible_task_indices(), vec![1, 0]);
+    }
+
+    #[test]
+    fn test_completed_at_is_set_on_completion_and_cleared_on_reopen() {
+        let now = std::rc::Rc::new(std::cell::Cell::new(SystemTime::UNIX_EPOCH));
+        let clock_now = now.clone();
+        let mut app = App::with_clock(
+            crate::types::AppState::default(),
+            Box::new(move || clock_now.get()),
+        );
+        app.state.tasks = vec![
+            create_test_task("1", "File taxes"),
+            create_test_task("2", "Pay rent"),
+            create_test_task("3", "Call mum"),
+        ];
+
+        let done_at = SystemTime::UNIX_EPOCH + Duration::from_secs(60);
+        now.set(done_at);
+        app.cursor_first_task();
+        app.toggle_task_completion().unwrap();
+        assert_eq!(app.state.tasks[0].completed_at, Some(done_at));
+        assert_eq!(
+            app.state.store.load_tasks().unwrap()[0].completed_at,
+            Some(done_at)
+        );
+
+        // Later edits to a completed task keep its completion time
+        now.set(done_at + Duration::from_secs(60));
+        app.cursor_first_task();
+        app.execute_command("setenergy high").unwrap();
+        assert_eq!(app.state.tasks[0].completed_at, Some(done_at));
+
+        // Completing through the status cycle counts too
+        app.state.config.ui.status_cycle = vec![TaskStatus::Todo, TaskStatus::Done];
+        app.task_list_state.select(Some(2));
+        app.cycle_task_status();
+        assert_eq!(app.state.tasks[2].completed_at, Some(now.get()));
+
+        app.cursor_first_task();
+        app.toggle_task_completion().unwrap();
+        assert_eq!(app.state.tasks[0].completed_at, None);
+
+        // Sorting by completion puts the latest first and open tasks last
+        app.sort_key = SortKey::Completed;
+        assert_eq!(app.visible_task_indices(), vec![2, 0, 1]);
+    }
+
+    #[test]
+    fn test_done_log_moves_completed_tasks_out_of_views() {
+        let now = std::rc::Rc::new(std::cell::Cell::new(SystemTime::UNIX_EPOCH));
+        let clock_now = now.clone();
+        let mut app = App::with_clock(
+            crate::types::AppState::default(),
+            Box::new(move || clock_now.get()),
+        );
+        app.state.config.ui.done_log = true;
+        app.state.tasks = vec![
+            create_test_task("1", "File taxes"),
+            create_test_task("2", "Pay rent"),
+            create_test_task("3", "Call mum"),
+        ];
+
+        app.cursor_first_task();
+        now.set(SystemTime::UNIX_EPOCH + Duration::from_secs(60));
+        app.toggle_task_completion().unwrap();
+        assert_eq!(app.visible_task_indices(), vec![1, 2]);
+
+        app.cursor_last_task();
+        now.set(SystemTime::UNIX_EPOCH + Duration::from_secs(120));
+        app.toggle_task_completion().unwrap();
+        assert_eq!(app.visible_task_indices(), vec![1]);
+
+        // The log lists the most recently completed first
+        app.execute_command("done").unwrap();
+        assert_eq!(app.visible_task_indices(), vec![2, 0]);
+
+        // Without the log, completed tasks stay in the list
+        app.state.config.ui.done_log = false;
+        app.execute_command("all").unwrap();
+        assert_eq!(app.visible_task_indices(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_due_from_defer_copies_exact_time() {
+        let mut app = App::new(crate::types::AppState::default());
+        let defer = SystemTime::UNIX_EPOCH + Duration::from_millis(1_700_000_000_123);
+        let mut deferred = create_test_task("1", "Renew passport");
+        deferred.defer_until = Some(defer);
+        app.state.tasks = vec![deferred, create_test_task("2", "Pay rent")];
+        app.cursor_first_task();
+
+        app.due_from_defer().unwrap();
+        assert_eq!(app.state.tasks[0].due, Some(defer));
+        assert_eq!(app.state.store.load_tasks().unwrap()[0].due, Some(defer));
+
+        app.cursor_next_task();
+        assert_eq!(
+            app.due_from_defer(),
+            Err("'Pay rent' has no defer date".to_string())
+        );
+        assert_eq!(app.state.tasks[1].due, None);
+    }
+
+    #[test]
+    fn test_defer_from_due_with_offset() {
+        let mut app = App::new(crate::types::AppState::default());
+        let due = SystemTime::UNIX_EPOCH + Duration::from_secs(10 * 86_400);
+        let mut task = create_test_task("1", "File taxes");
+        task.due = Some(due);
+        app.state.tasks = vec![task, create_test_task("2", "No dates")];
+        app.cursor_first_task();
+        app.toggle_task_selection();
+        app.cursor_next_task();
+        app.toggle_task_selection();
+
+        app.execute_command("deferfromdue -2").unwrap();
+        assert_eq!(
+            app.state.tasks[0].defer_until,
+            Some(due - Duration::from_secs(2 * 86_400))
+        );
+        // Tasks without a due date are skipped
+        assert_eq!(app.state.tasks[1].defer_until, None);
+
+        app.cursor_first_task();
+        app.execute_command("deferfromdue").unwrap();
+        assert_eq!(app.state.tasks[0].defer_until, Some(due));
+        assert!(app.execute_command("deferfromdue soon").is_err());
+    }
+
+    #[test]
+    fn test_shift_due_moves_every_listed_due_date() {
+        let mut app = App::new(crate::types::AppState::default());
+        let day = Duration::from_secs(86_400);
+        let due = SystemTime::UNIX_EPOCH + 100 * day;
+        let defer = SystemTime::UNIX_EPOCH + 90 * day;
+        let mut report = create_test_task("1", "Report");
+        report.due = Some(due);
+        report.defer_until = Some(defer);
+        let mut invoice = create_test_task("2", "Invoice");
+        invoice.due = Some(due + day);
+        let mut done = create_test_task("3", "Done");
+        done.due = Some(due);
+        done.completed = true;
+        app.state.tasks = vec![
+            report,
+            invoice,
+            create_test_task("4", "Someday maybe"),
+            done,
+        ];
+        app.state.store.save_tasks(&app.state.tasks).unwrap();
+
+        app.execute_command("shift-due +7d").unwrap();
+        assert_eq!(app.message.as_deref(), Some("Moved 2 due dates by +7d"));
+        assert_eq!(app.state.tasks[0].due, Some(due + 7 * day));
+        assert_eq!(app.state.tasks[1].due, Some(due + 8 * day));
+        // Defer dates, dateless tasks and completed tasks are untouched
+        assert_eq!(app.state.tasks[0].defer_until, Some(defer));
+        assert_eq!(app.state.tasks[2].due, None);
+        let stored = app.state.store.load_tasks().unwrap();
+        assert!(stored.iter().any(|t| t.id == "4" && t.due.is_none()));
+        assert_eq!(app.state.tasks[3].due, Some(due));
+
+        // Marked tasks narrow the shift
+        app.cursor_first_task();
+        app.toggle_task_selection();
+        app.execute_command("shift-defer -1w").unwrap();
+        assert_eq!(app.state.tasks[0].defer_until, Some(defer - 7 * day));
+        assert_eq!(app.state.tasks[1].defer_until, None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_shift_dates_out_of_range_changes_nothing() {
+        let mut app = App::new(crate::types::AppState::default());
+        let mut ordinary = create_test_task("1", "Ordinary");
+        ordinary.due = Some(SystemTime::UNIX_EPOCH + Duration::from_secs(86_400));
+        let mut far = create_test_task("2", "Far future");
+        // Close to the largest time Unix platforms can represent
+        far.due = Some(SystemTime::UNIX_EPOCH + Duration::from_secs(i64::MAX as u64 - 60));
+        app.state.tasks = vec![ordinary.clone(), far.clone()];
+
+        let result = app.shift_dates(&[0, 1], DateField::Due, chrono::Duration::days(1));
+        assert_eq!(
+            result,
+            Err("Can't move the due date of 'Far future' that far".to_string())
+        );
+        assert_eq!(app.state.tasks, vec![ordinary, far]);
+        assert!(app.execute_command("shift-due").is_err());
+        assert!(app.execute_command("shift-due soon").is_err());
+    }
+
+    #[test]
+    fn test_completing_repeating_task_adds_next_occurrence() {
+        let mut app = App::new(crate::types::AppState::default());
+        let due = SystemTime::UNIX_EPOCH + Duration::from_secs(100 * 86_400);
+        let mut gym = create_test_task("1", "Gym");
+        gym.due = Some(due);
+        gym.defer_until = Some(due - Duration::from_secs(3600));
+        gym.recurrence = Some(Recurrence::Weekly);
+        app.state.tasks = vec![gym, create_test_task("2", "One-off")];
+        app.state.store.save_tasks(&app.state.tasks).unwrap();
+        app.cursor_first_task();
+
+        app.toggle_task_completion().unwrap();
+        assert!(app.state.tasks[0].completed);
+        assert_eq!(app.state.tasks.len(), 3);
+        let next = &app.state.tasks[2];
+        assert_eq!(next.title, "Gym");
+        assert!(!next.completed);
+        assert_eq!(next.due, Recurrence::Weekly.next_after(due));
+        assert_eq!(
+            next.defer_until,
+            Recurrence::Weekly.next_after(due - Duration::from_secs(3600))
+        );
+        assert_eq!(app.state.store.load_tasks().unwrap().len(), 3);
+
+        // Reopening takes the copy back; undoing brings it back, then removes it
+        app.cursor_first_task();
+        app.toggle_task_completion().unwrap();
+        assert_eq!(app.state.tasks.len(), 2);
+        assert!(app.undo().unwrap());
+        assert_eq!(app.state.tasks.len(), 3);
+        assert!(app.undo().unwrap());
+        assert_eq!(app.state.tasks.len(), 2);
+
+        // Tasks that don't repeat are just completed
+        app.task_list_state.select(Some(1));
+        app.toggle_task_completion().unwrap();
+        assert_eq!(app.state.tasks.len(), 2);
+    }
+
+    #[test]
+    fn test_completing_again_after_reopening_adds_one_occurrence() {
+        let mut app = App::new(crate::types::AppState::default());
+        let mut gym = create_test_task("1", "Gym");
+        gym.due = Some(SystemTime::UNIX_EPOCH + Duration::from_secs(100 * 86_400));
+        gym.recurrence = Some(Recurrence::Weekly);
+        app.state.tasks = vec![gym];
+        app.cursor_first_task();
+
+        for _ in 0..3 {
+            app.cursor_first_task();
+            app.toggle_task_completion().unwrap();
+        }
+        assert!(app.state.tasks[0].completed);
+        let copies: Vec<&Task> = app.state.tasks[1..].iter().collect();
+        assert_eq!(copies.len(), 1);
+        assert_eq!(
+            app.state.tasks[0].next_occurrence_id,
+            Some(copies[0].id.clone())
+        );
+        assert_eq!(app.state.store.load_tasks().unwrap().len(), 2);
+
+        // A copy completed in the meantime is history and stays
+        app.state.tasks[1].completed = true;
+        app.cursor_first_task();
+        app.toggle_task_completion().unwrap();
+        assert_eq!(app.state.tasks.len(), 2);
+        assert_eq!(app.state.tasks[0].next_occurrence_id, None);
+    }
+
+    #[test]
+    fn test_cycling_to_done_completes_like_toggling() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.state.config.ui.status_cycle = vec![TaskStatus::Todo, TaskStatus::Done];
+        app.state.config.behavior.after_complete = AfterComplete::Next;
+        let mut gym = create_test_task("1", "Gym");
+        gym.due = Some(SystemTime::UNIX_EPOCH + Duration::from_secs(100 * 86_400));
+        gym.recurrence = Some(Recurrence::Weekly);
+        let mut report = create_test_task("2", "Report");
+        report.subtasks = vec![Subtask {
+            title: "Draft".to_string(),
+            completed: false,
+        }];
+        app.state.tasks = vec![gym, report, create_test_task("3", "Other")];
+        app.cursor_first_task();
+
+        app.cycle_task_status();
+        assert!(app.state.tasks[0].completed);
+        assert_eq!(app.state.tasks.len(), 4);
+        assert_eq!(
+            app.state.tasks[3].due,
+            Recurrence::Weekly.next_after(app.state.tasks[0].due.unwrap())
+        );
+        assert!(app.message.as_ref().unwrap().contains("press u to undo"));
+        assert_eq!(app.cursor_task_index(), Some(1));
+
+        // Open subtasks block completion here too
+        app.cycle_task_status();
+        assert!(!app.state.tasks[1].completed);
+        assert!(app.message.as_ref().unwrap().contains("open subtasks"));
+
+        // Cycling back round reopens and takes the copy back
+        app.task_list_state.select(None);
+        app.cursor_to_task_index(0);
+        app.cycle_task_status();
+        assert!(!app.state.tasks[0].completed);
+        assert_eq!(app.state.tasks.len(), 3);
+    }
+
+    #[test]
+    fn test_skip_occurrence_advances_without_completing() {
+        let mut app = App::new(crate::types::AppState::default());
+        let due = SystemTime::UNIX_EPOCH + Duration::from_secs(100 * 86_400);
+        let mut gym = create_test_task("1", "Gym");
+        gym.due = Some(due);
+        app.state.tasks = vec![gym];
+        app.cursor_first_task();
+
+        assert_eq!(
+            app.skip_occurrence(),
+            Err("'Gym' does not repeat".to_string())
+        );
+        app.execute_command("repeat 2d").unwrap();
+        assert_eq!(
+            app.state.tasks[0].recurrence,
+            Some(Recurrence::EveryNDays(2))
+        );
+
+        app.execute_command("skip").unwrap();
+        let expected = Recurrence::EveryNDays(2).next_after(due).unwrap();
+        assert_eq!(app.state.tasks[0].due, Some(expected));
+        assert_eq!(app.state.store.load_tasks().unwrap()[0].due, Some(expected));
+        // Skipping is not a completion
+        assert_eq!(app.state.tasks.len(), 1);
+        assert!(!app.state.tasks[0].completed);
+        assert_eq!(app.completion_feedback("1"), None);
+
+        assert!(app.undo().unwrap());
+        assert_eq!(app.state.tasks[0].due, Some(due));
+        assert!(app.execute_command("repeat sometimes").is_err());
+    }
+
+    #[test]
+    fn test_cell_popup_shows_full_field_value() {
+        let mut app = App::new(crate::types::AppState::default());
+        let mut task = create_test_task("1", "A title far too long to fit in the title column");
+        task.description = "Every word of the description".to_string();
+        app.state.tasks = vec![task];
+        app.cursor_first_task();
+
+        assert_eq!(app.cell_popup_content(), None);
+        app.cycle_cell_popup();
+        assert_eq!(
+            app.cell_popup_content(),
+            Some((
+                "Title",
+                "A title far too long to fit in the title column".to_string()
+            ))
+        );
+        app.cycle_cell_popup();
+        assert_eq!(
+            app.cell_popup_content(),
+            Some(("Description", "Every word of the description".to_string()))
+        );
+        app.cycle_cell_popup();
+        assert_eq!(app.state.cell_popup, None);
+    }
+
+    #[test]
+    fn test_cell_popup_needs_a_task() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.cycle_cell_popup();
+        assert_eq!(app.state.cell_popup, None);
+        assert_eq!(app.cell_popup_content(), None);
+    }
+
+    #[test]
+    fn test_empty_list_message() {
+        let mut app = App::new(crate::types::AppState::default());
+        assert_eq!(
+            app.empty_list_message().as_deref(),
+            Some("No tasks yet - press 'o' to add one")
+        );
+
+        let mut later = create_test_task("1", "Later");
+        later.due = Some(SystemTime::now() + Duration::from_secs(3 * 24 * 60 * 60));
+        app.state.tasks = vec![later];
+        assert_eq!(app.empty_list_message(), None);
+
+        app.set_view(View::Today);
+        assert_eq!(
+            app.empty_list_message().as_deref(),
+            Some("All clear for today 🎉")
+        );
+
+        app.state.config.ui.today_clear_message = String::new();
+        assert_eq!(app.empty_list_message(), None);
+
+        app.state.config.ui.today_clear_message = "Done!".to_string();
+        app.state.tasks[0].due = Some(SystemTime::now());
+        assert_eq!(app.empty_list_message(), None);
+    }
+
+    #[test]
+    fn test_toggle_tag_filter_uses_cursor_task_first_tag() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.state.tasks = vec![
+            tagged_task("1", &[]),
+            tagged_task("2", &["work", "home"]),
+            tagged_task("3", &["work"]),
+            tagged_task("4", &["home"]),
+        ];
+        app.task_list_state.select(Some(0));
+        assert!(app.toggle_tag_filter().is_err());
+        assert!(app.state.query.is_empty());
+
+        app.task_list_state.select(Some(1));
+        app.toggle_tag_filter().unwrap();
+        assert_eq!(app.state.query.tag.as_deref(), Some("work"));
+        assert_eq!(app.visible_task_indices(), vec![1, 2]);
+
+        app.toggle_tag_filter().unwrap();
+        assert!(app.state.query.tag.is_none());
+        assert_eq!(app.visible_task_indices(), vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_tags_field_parses_comma_separated_list() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.state.editing_task = Some(create_test_task("1", "Plan trip"));
+        let tags_field = app
+            .edit_fields()
+            .iter()
+            .position(|&f| f == EditField::Tags)
+            .unwrap();
+
+        app.update_editing_task_field(tags_field, " travel, home,,travel ,".to_string());
+        assert_eq!(
+            app.state.editing_task.as_ref().unwrap().tags,
+            vec!["travel", "home"]
+        );
+        assert_eq!(app.get_editing_task_field(tags_field), "travel, home");
+    }
+
+    #[test]
+    fn test_tag_command_adds_and_removes_across_selection() {
+        let mut app = App::new(crate::types::AppState::default());
+        let mut first = create_test_task("1", "First");
+        first.tags = vec!["home".to_string()];
+        let mut second = create_test_task("2", "Second");
+        second.tags = vec!["home".to_string(), "errand".to_string()];
+        app.state.tasks = vec![first, second, create_test_task("3", "Untouched")];
+        app.state.store.save_tasks(&app.state.tasks).unwrap();
+        app.cursor_first_task();
+        app.toggle_task_selection();
+        app.cursor_next_task();
+        app.toggle_task_selection();
+
+        app.execute_command("tag +work -home").unwrap();
+
+        assert_eq!(app.state.tasks[0].tags, vec!["work"]);
+        assert_eq!(app.state.tasks[1].tags, vec!["errand", "work"]);
+        assert!(app.state.tasks[2].tags.is_empty());
+        assert!(app.get_task_selection().is_empty());
+        assert_eq!(app.state.store.load_tasks().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_tag_command_rejects_bare_words() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.state.tasks = vec![create_test_task("1", "First")];
+        app.cursor_first_task();
+
+        assert!(app.execute_command("tag work").is_err());
+        assert!(app.execute_command("tag").is_err());
+        assert!(app.state.tasks[0].tags.is_empty());
+    }
+
+    #[test]
+    fn test_all_tags_and_completion() {
+        let mut app = App::new(crate::types::AppState::default());
+        let mut first = create_test_task("1", "First");
+        first.tags = vec!["work".to_string(), "home".to_string()];
+        let mut second = create_test_task("2", "Second");
+        second.tags = vec!["wishlist".to_string(), "work".to_string()];
+        app.state.tasks = vec![first, second];
+
+        let tags: Vec<String> = app.all_tags().into_iter().collect();
+        assert_eq!(tags, vec!["home", "wishlist", "work"]);
+
+        app.state.input_buffer = "tag +wo".to_string();
+        app.complete_command();
+        assert_eq!(app.state.input_buffer, "tag +work");
+
+        app.state.input_buffer = "tag +work -h".to_string();
+        app.complete_command();
+        assert_eq!(app.state.input_buffer, "tag +work -home");
+
+        app.state.input_buffer = "ta".to_string();
+        app.complete_command();
+        assert_eq!(app.state.input_buffer, "tag");
+
+        app.state.input_buffer = "tag +zzz".to_string();
+        app.complete_command();
+        assert_eq!(app.state.input_buffer, "tag +zzz");
+    }
+
+    #[test]
+    fn test_energy_commands_set_and_filter() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.state.tasks = vec![
+            create_test_task("1", "Sort mail"),
+            create_test_task("2", "Write proposal"),
+        ];
+        app.cursor_first_task();
+        app.execute_command("setenergy low").unwrap();
+        app.cursor_next_task();
+        app.execute_command("setenergy high").unwrap();
+        assert_eq!(app.state.tasks[0].energy, Some(Energy::Low));
+        assert_eq!(app.state.tasks[1].energy, Some(Energy::High));
+
+        app.execute_command("filter mail").unwrap();
+        app.execute_command("energy low").unwrap();
+        assert_eq!(app.visible_task_indices(), vec![0]);
+        assert_eq!(app.state.query.terms, vec!["mail"]);
+
+        app.execute_command("energy high").unwrap();
+        assert!(app.visible_task_indices().is_empty());
+
+        app.execute_command("energy").unwrap();
+        assert_eq!(app.visible_task_indices(), vec![0]);
+
+        assert!(app.execute_command("energy tired").is_err());
+        assert!(app.execute_command("setenergy").is_err());
+    }
+
+    #[test]
+    fn test_project_commands_set_and_filter() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.state.tasks = vec![
+            create_test_task("1", "Paint fence"),
+            create_test_task("2", "Quarterly report"),
+            create_test_task("3", "Loose end"),
+        ];
+        app.cursor_first_task();
+        app.execute_command("setproject home").unwrap();
+        app.cursor_next_task();
+        app.execute_command("setproject work").unwrap();
+        assert_eq!(app.state.tasks[0].project.as_deref(), Some("home"));
+        assert_eq!(app.state.tasks[1].project.as_deref(), Some("work"));
 
-        None
+        app.execute_command("project home").unwrap();
+        assert_eq!(app.visible_task_indices(), vec![0]);
+
+        app.execute_command("project").unwrap();
+        assert_eq!(app.visible_task_indices(), vec![0, 1, 2]);
+
+        app.cursor_first_task();
+        app.execute_command("setproject none").unwrap();
+        assert_eq!(app.state.tasks[0].project, None);
+        assert!(app.execute_command("setproject").is_err());
     }
 
-    fn parse_weekday(&self, input: &str) -> Option<Weekday> {
-        match input {
-            "monday" | "mon" => Some(Weekday::Mon),
-            "tuesday" | "tue" => Some(Weekday::Tue),
-            "wednesday" | "wed" => Some(Weekday::Wed),
-            "thursday" | "thu" => Some(Weekday::Thu),
-            "friday" | "fri" => Some(Weekday::Fri),
-            "saturday" | "sat" => Some(Weekday::Sat),
-            "sunday" | "sun" => Some(Weekday::Sun),
-            _ => None,
-        }
+    #[test]
+    fn test_all_projects_lists_each_project_once() {
+        let mut app = App::new(crate::types::AppState::default());
+        let mut tasks = vec![
+            create_test_task("1", "A"),
+            create_test_task("2", "B"),
+            create_test_task("3", "C"),
+            create_test_task("4", "D"),
+        ];
+        tasks[0].project = Some("work".to_string());
+        tasks[1].project = Some("home".to_string());
+        tasks[2].project = Some("work".to_string());
+        app.state.tasks = tasks;
+
+        let projects: Vec<String> = app.all_projects().into_iter().collect();
+        assert_eq!(projects, vec!["home", "work"]);
+
+        app.state.input_buffer = "setproject w".to_string();
+        app.complete_command();
+        assert_eq!(app.state.input_buffer, "setproject work");
     }
 
-    fn format_date_for_editing(&self, time: Option<SystemTime>) -> String {
-        match time {
-            Some(t) => {
-                let now = SystemTime::now();
-                if let Ok(duration) = t.duration_since(now) {
-                    let days = duration.as_secs() / (24 * 60 * 60);
-                    let hours = (duration.as_secs() % (24 * 60 * 60)) / (60 * 60);
-                    if days > 0 {
-                        format!("{days}d")
-                    } else if hours > 0 {
-                        format!("{hours}h")
-                    } else {
-                        "1h".to_string()
-                    }
-                } else if let Ok(duration) = now.duration_since(t) {
-                    // Past date - show negative
-                    let days = duration.as_secs() / (24 * 60 * 60);
-                    let hours = (duration.as_secs() % (24 * 60 * 60)) / (60 * 60);
-                    if days > 0 {
-                        format!("-{days}d")
-                    } else if hours > 0 {
-                        format!("-{hours}h")
-                    } else {
-                        "now".to_string()
-                    }
-                } else {
-                    "0d".to_string()
-                }
-            }
-            None => "".to_string(),
-        }
+    fn cursor_title<D: Db>(app: &App<D>) -> Option<&str> {
+        app.cursor_task_index()
+            .map(|i| app.state.tasks[i].title.as_str())
     }
 
-    fn create_task(&self, title: &str) -> Task {
-        Task {
-            id: Uuid::new_v4().to_string(),
-            title: title.to_string(),
-            description: String::new(),
-            completed: false,
-            created_at: std::time::SystemTime::now(),
-            due: None,
-            defer_until: None,
-        }
+    #[test]
+    fn test_filter_keeps_cursor_on_visible_task() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.state.tasks = vec![
+            create_test_task("1", "Buy milk"),
+            create_test_task("2", "Call plumber"),
+            create_test_task("3", "Buy stamps"),
+        ];
+        app.task_list_state.select(Some(2));
+
+        app.execute_command("filter buy").unwrap();
+        assert_eq!(cursor_title(&app), Some("Buy stamps"));
+        assert_eq!(app.task_list_state.selected(), Some(1));
     }
 
-    fn apply_to_selection<F>(&mut self, mut func: F)
-    where
-        F: FnMut(&mut Task),
-    {
-        let indices: Vec<usize> = self.selection().collect();
-        for index in indices {
-            if let Some(task) = self.state.tasks.get_mut(index) {
-                func(task);
-            }
-        }
-        self.sync_to_storage().unwrap_or_else(|e| {
-            self.set_error_message(format!("Error syncing tasks: {e}"));
-        });
-        self.clear_task_selection();
+    #[test]
+    fn test_filter_moves_cursor_to_nearest_remaining_task() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.state.tasks = vec![
+            create_test_task("1", "Buy milk"),
+            create_test_task("2", "Call plumber"),
+            create_test_task("3", "Buy stamps"),
+            create_test_task("4", "Email landlord"),
+        ];
+
+        // The task below wins over the task above
+        app.task_list_state.select(Some(1));
+        app.execute_command("filter buy").unwrap();
+        assert_eq!(cursor_title(&app), Some("Buy stamps"));
+
+        // With nothing below, the cursor moves up
+        app.clear_filters();
+        app.task_list_state.select(Some(3));
+        app.execute_command("filter buy").unwrap();
+        assert_eq!(cursor_title(&app), Some("Buy stamps"));
+
+        // Nothing left to show clears the cursor
+        app.execute_command("filter nothing-matches").unwrap();
+        assert_eq!(app.task_list_state.selected(), None);
+        assert_eq!(app.cursor_task_index(), None);
+
+        // Widening again from an empty list starts at the top
+        app.clear_filters();
+        assert_eq!(cursor_title(&app), Some("Buy milk"));
     }
 
-    fn sync_to_storage(&mut self) -> Result<(), DbError> {
-        self.state.store.clear()?;
-        for task in &self.state.tasks {
-            self.state.store.save_task(task)?;
-        }
-        Ok(())
+    #[test]
+    fn test_filter_cursor_first_resets_to_top() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.state.config.behavior.filter_cursor = FilterCursor::First;
+        app.state.tasks = vec![
+            create_test_task("1", "Buy milk"),
+            create_test_task("2", "Call plumber"),
+            create_test_task("3", "Buy stamps"),
+        ];
+        app.task_list_state.select(Some(2));
+
+        app.execute_command("filter buy").unwrap();
+        assert_eq!(cursor_title(&app), Some("Buy milk"));
     }
 
-    // Task list selection methods
-    pub fn cursor_next_task(&mut self) {
-        self.task_list_state.select_next();
+    #[test]
+    fn test_wait_command_flags_stalled_tasks() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.state.tasks = vec![
+            create_test_task("1", "Chase invoice"),
+            create_test_task("2", "Await review"),
+        ];
+        app.cursor_first_task();
+        app.execute_command("wait").unwrap();
+        assert!(app.state.tasks[0].waiting_since.is_some());
+        assert_eq!(app.waiting_too_long_count(), 0);
+
+        let nine_days = Duration::from_secs(9 * 24 * 60 * 60);
+        app.state.tasks[0].waiting_since = Some(SystemTime::now() - nine_days);
+        assert_eq!(app.waiting_too_long_count(), 1);
+
+        app.state.config.ui.waiting_nudge_days = 10;
+        assert_eq!(app.waiting_too_long_count(), 0);
+
+        app.cursor_first_task();
+        app.execute_command("wait").unwrap();
+        assert_eq!(app.state.tasks[0].waiting_since, None);
     }
 
-    pub fn cursor_previous_task(&mut self) {
-        self.task_list_state.select_previous();
+    #[test]
+    fn test_demote_to_subtask_moves_title_into_parent() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.state.tasks = vec![
+            create_test_task("1", "Plan trip"),
+            create_test_task("2", "Book flights"),
+            create_test_task("3", "Pack"),
+        ];
+
+        app.demote_to_subtask(1, 0).unwrap();
+        assert_eq!(app.state.tasks.len(), 2);
+        assert!(app.state.tasks.iter().all(|t| t.id != "2"));
+        assert_eq!(
+            app.state.tasks[0].subtasks,
+            vec![Subtask {
+                title: "Book flights".to_string(),
+                completed: false
+            }]
+        );
+
+        // A parent below the child works too
+        app.demote_to_subtask(0, 1).unwrap();
+        assert_eq!(app.state.tasks.len(), 1);
+        assert_eq!(app.state.tasks[0].title, "Pack");
+        assert_eq!(app.state.tasks[0].subtasks[0].title, "Plan trip");
+        assert_eq!(app.state.store.load_tasks().unwrap().len(), 1);
     }
 
-    pub fn cursor_first_task(&mut self) {
-        self.task_list_state.select_first();
+    #[test]
+    fn test_demote_cursor_task_uses_task_above() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.state.tasks = vec![
+            create_test_task("1", "Plan trip"),
+            create_test_task("2", "Book flights"),
+        ];
+        app.cursor_first_task();
+        app.demote_cursor_task().unwrap();
+        assert_eq!(app.state.tasks.len(), 2);
+
+        app.cursor_next_task();
+        app.demote_cursor_task().unwrap();
+        assert_eq!(app.state.tasks.len(), 1);
+        assert_eq!(app.state.tasks[0].subtasks[0].title, "Book flights");
+        assert_eq!(app.cursor_task_index(), Some(0));
     }
 
-    pub fn cursor_last_task(&mut self) {
-        self.task_list_state.select_last();
+    fn task_with_subtasks(id: &str, title: &str, subtasks: &[&str]) -> Task {
+        let mut task = create_test_task(id, title);
+        task.subtasks = subtasks
+            .iter()
+            .map(|title| Subtask {
+                title: title.to_string(),
+                completed: false,
+            })
+            .collect();
+        task
     }
 
-    pub fn cursor_task_index(&self) -> Option<usize> {
-        self.task_list_state.selected()
+    #[test]
+    fn test_completing_parent_with_open_subtasks_is_blocked() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.state.tasks = vec![
+            task_with_subtasks("1", "Plan trip", &["Book flights"]),
+            create_test_task("2", "Pack"),
+        ];
+        app.cursor_first_task();
+        app.toggle_task_selection();
+        app.cursor_next_task();
+        app.toggle_task_selection();
+
+        app.toggle_task_completion().unwrap();
+        assert!(!app.state.tasks[0].completed);
+        assert!(!app.state.tasks[0].subtasks[0].completed);
+        // Tasks without open subtasks still complete
+        assert!(app.state.tasks[1].completed);
+        assert_eq!(
+            app.message.as_deref(),
+            Some("'Plan trip' has open subtasks; finish them before completing it")
+        );
+
+        // Once the subtasks are done the parent completes
+        app.state.tasks[0].subtasks[0].completed = true;
+        app.cursor_first_task();
+        app.toggle_task_completion().unwrap();
+        assert!(app.state.tasks[0].completed);
     }
 
-    pub fn clear_task_selection(&mut self) {
-        self.task_selection.clear();
+    #[test]
+    fn test_completing_parent_cascades_to_subtasks() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.state.config.behavior.complete_cascades_subtasks = true;
+        app.state.tasks = vec![task_with_subtasks(
+            "1",
+            "Plan trip",
+            &["Book flights", "Pack"],
+        )];
+        app.cursor_first_task();
+
+        app.toggle_task_completion().unwrap();
+        assert!(app.state.tasks[0].completed);
+        assert!(app.state.tasks[0].subtasks.iter().all(|s| s.completed));
+        assert!(app.state.store.load_tasks().unwrap()[0]
+            .subtasks
+            .iter()
+            .all(|s| s.completed));
+
+        // Reopening the parent leaves the subtasks alone
+        app.toggle_task_completion().unwrap();
+        assert!(!app.state.tasks[0].completed);
+        assert!(app.state.tasks[0].subtasks.iter().all(|s| s.completed));
     }
 
-    pub fn selection(&self) -> SelectionIterator<'_> {
-        if !self.task_selection.is_empty() {
-            SelectionIterator::Multiple(self.task_selection.iter())
-        } else if let Some(selected) = self.task_list_state.selected() {
-            SelectionIterator::Single(std::iter::once(selected))
-        } else {
-            SelectionIterator::Empty
-        }
+    #[test]
+    fn test_completing_blocker_releases_deferred_task() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.state.tasks = vec![
+            create_test_task("1", "Get quote"),
+            create_test_task("2", "Order parts"),
+        ];
+        app.cursor_first_task();
+        assert!(app.defer_selection_until_cursor_task().is_err());
+
+        app.cursor_next_task();
+        app.toggle_task_selection();
+        app.cursor_first_task();
+        app.defer_selection_until_cursor_task().unwrap();
+        let now = app.now();
+        assert_eq!(app.state.tasks[1].defer_until_task.as_deref(), Some("1"));
+        assert!(app.state.tasks[1].is_deferred(now));
+        assert!(app.state.tasks[0].defer_until_task.is_none());
+
+        app.cursor_first_task();
+        app.toggle_task_completion().unwrap();
+        assert!(app.state.tasks[1].defer_until_task.is_none());
+        assert!(!app.state.tasks[1].is_deferred(now));
     }
 
-    pub fn toggle_task_selection(&mut self) {
-        if let Some(selected) = self.task_list_state.selected() {
-            if self.task_selection.contains(&selected) {
-                self.task_selection.remove(&selected);
-            } else {
-                self.task_selection.insert(selected);
-            }
-        }
+    #[test]
+    fn test_status_cycle_and_delete_release_deferred_tasks() {
+        let mut app = App::new(crate::types::AppState::default());
+        let mut waiting = create_test_task("2", "Order parts");
+        waiting.defer_until_task = Some("1".to_string());
+        let mut also_waiting = create_test_task("3", "Schedule fitting");
+        also_waiting.defer_until_task = Some("1".to_string());
+        app.state.tasks = vec![create_test_task("1", "Get quote"), waiting];
+        app.state.config.ui.status_cycle = vec![
+            crate::types::TaskStatus::Todo,
+            crate::types::TaskStatus::Done,
+        ];
+
+        app.cursor_first_task();
+        app.cycle_task_status();
+        assert!(app.state.tasks[1].defer_until_task.is_none());
+
+        app.state.tasks[0].completed = false;
+        app.state.tasks[1] = also_waiting;
+        app.cursor_first_task();
+        app.delete_tasks().unwrap();
+        assert!(app.state.tasks[0].defer_until_task.is_none());
     }
 
-    pub fn task_list_state(&mut self) -> &mut TableState {
-        &mut self.task_list_state
+    #[test]
+    fn test_selection_follows_task_when_list_is_reordered() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.state.tasks = vec![
+            create_test_task("1", "Water plants"),
+            create_test_task("2", "Call bank"),
+            create_test_task("3", "Book flights"),
+        ];
+        app.cursor_first_task();
+        app.toggle_task_selection();
+        assert!(app.get_task_selection().contains("1"));
+
+        // Sorting by title moves "Water plants" from the first row to the last
+        app.sort_key = SortKey::Title;
+        app.cursor_first_task();
+        assert_eq!(app.cursor_task_index(), Some(2));
+        assert!(app.get_task_selection().contains("1"));
+
+        app.toggle_task_completion().unwrap();
+        let completed: Vec<&str> = app
+            .state
+            .tasks
+            .iter()
+            .filter(|t| t.completed)
+            .map(|t| t.id.as_str())
+            .collect();
+        assert_eq!(completed, vec!["1"]);
     }
 
-    pub fn get_task_selection(&self) -> &HashSet<usize> {
-        &self.task_selection
+    #[test]
+    fn test_delete_tasks_removes_exactly_the_selected_ids() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.state.tasks = (1..=5)
+            .map(|i| create_test_task(&i.to_string(), "Task"))
+            .collect();
+        app.state.store.save_tasks(&app.state.tasks).unwrap();
+        app.cursor_first_task();
+        app.toggle_task_selection();
+        app.cursor_next_task();
+        app.cursor_next_task();
+        app.toggle_task_selection();
+
+        app.delete_tasks().unwrap();
+
+        let ids: Vec<&str> = app.state.tasks.iter().map(|t| t.id.as_str()).collect();
+        assert_eq!(ids, vec!["2", "4", "5"]);
+        let mut stored: Vec<String> = app
+            .state
+            .store
+            .load_tasks()
+            .unwrap()
+            .into_iter()
+            .map(|t| t.id)
+            .collect();
+        stored.sort();
+        assert_eq!(stored, vec!["2", "4", "5"]);
+        assert!(app.get_task_selection().is_empty());
     }
 
-    pub fn create_task_below_cursor(&mut self) {
-        let new_task = self.create_task("");
-        let cursor_index = self.task_list_state.selected().unwrap_or(0);
-        let insert_index = if self.state.tasks.is_empty() {
-            0
-        } else {
-            (cursor_index + 1).min(self.state.tasks.len())
-        };
-        self.state.tasks.insert(insert_index, new_task.clone());
-        self.state.editing_task = Some(new_task);
-        self.state.editing_field = 0;
-        self.task_list_state.select(Some(insert_index));
+    #[test]
+    fn test_clear_filters_restores_full_list() {
+        let mut app = App::new(crate::types::AppState::default());
+        let mut low = create_test_task("1", "Sort mail");
+        low.energy = Some(Energy::Low);
+        low.due = Some(SystemTime::now());
+        let mut parked = create_test_task("2", "Learn piano");
+        parked.someday = true;
+        app.state.tasks = vec![low, parked, create_test_task("3", "Read")];
+
+        app.execute_command("today").unwrap();
+        app.execute_command("filter mail due:any").unwrap();
+        app.execute_command("energy low").unwrap();
+        assert_eq!(app.visible_task_indices(), vec![0]);
+
+        app.clear_filters();
+        assert_eq!(app.state.view, View::All);
+        assert!(app.state.query.is_empty());
+        assert_eq!(app.visible_task_indices(), vec![0, 1, 2]);
     }
 
-    pub fn create_task_above_cursor(&mut self) {
-        let new_task = self.create_task("");
-        let cursor_index = self.task_list_state.selected().unwrap_or(0);
-        self.state.tasks.insert(cursor_index, new_task.clone());
-        self.state.editing_task = Some(new_task);
-        self.state.editing_field = 0;
-        self.task_list_state.select(Some(cursor_index));
+    #[test]
+    fn test_tui_created_tasks_carry_tui_source() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.add_task("Typed in").unwrap();
+        app.create_task_below_cursor();
+        assert_eq!(app.state.tasks[0].source, TaskSource::Tui);
+        assert_eq!(
+            app.state.editing_task.as_ref().unwrap().source,
+            TaskSource::Tui
+        );
     }
 
-    pub fn save_editing_task(&mut self) -> Result<(), DbError> {
-        if let Some(editing_task) = &self.state.editing_task {
-            if let Some(selected_index) = self.task_list_state.selected() {
-                if selected_index < self.state.tasks.len() {
-                    self.state.tasks[selected_index] = editing_task.clone();
-                    self.sync_to_storage()?;
-                }
-            }
-        }
-        self.state.editing_task = None;
-        Ok(())
+    fn tagged_task(id: &str, tags: &[&str]) -> Task {
+        let mut task = create_test_task(id, id);
+        task.tags = tags.iter().map(|t| t.to_string()).collect();
+        task
     }
 
-    pub fn update_editing_task_field(&mut self, field_index: usize, value: String) {
-        // Parse dates outside the mutable borrow to avoid borrowing conflicts
-        let parsed_date = if field_index == 2 || field_index == 3 {
-            // field_index 2 is due date (5pm), field_index 3 is defer date (8am)
-            let is_due_date = field_index == 2;
-            self.parse_date_input(&value, is_due_date)
-        } else {
-            None
-        };
+    #[test]
+    fn test_group_by_tag_each() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.state.tasks = vec![
+            tagged_task("a", &["work", "home"]),
+            tagged_task("b", &[]),
+            tagged_task("c", &["home"]),
+        ];
 
-        if let Some(ref mut editing_task) = self.state.editing_task {
-            match field_index {
-                0 => editing_task.title = value,
-                1 => editing_task.description = value,
-                2 => editing_task.due = parsed_date,
-                3 => editing_task.defer_until = parsed_date,
-                _ => {}
-            }
-        }
+        assert_eq!(
+            app.group_by_tag(),
+            vec![
+                ("home".to_string(), vec![0, 2]),
+                ("work".to_string(), vec![0]),
+                ("Untagged".to_string(), vec![1]),
+            ]
+        );
     }
 
-    pub fn get_editing_task_field(&self, field_index: usize) -> String {
-        if let Some(ref editing_task) = self.state.editing_task {
-            match field_index {
-                0 => editing_task.title.clone(),
-                1 => editing_task.description.clone(),
-                2 => self.format_date_for_editing(editing_task.due),
-                3 => self.format_date_for_editing(editing_task.defer_until),
-                _ => String::new(),
-            }
-        } else {
-            String::new()
-        }
+    #[test]
+    fn test_group_by_tag_first_and_without_untagged() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.state.config.ui.tag_grouping = TagGrouping::First;
+        app.state.tasks = vec![
+            tagged_task("a", &["work", "home"]),
+            tagged_task("b", &["home"]),
+        ];
+
+        assert_eq!(
+            app.group_by_tag(),
+            vec![("home".to_string(), vec![1]), ("work".to_string(), vec![0]),]
+        );
     }
 
-    pub fn start_editing_current_task(&mut self) {
-        if let Some(selected_index) = self.task_list_state.selected() {
-            if selected_index < self.state.tasks.len() {
-                self.state.editing_task = Some(self.state.tasks[selected_index].clone());
-                self.state.editing_field = 0;
+    #[test]
+    fn test_group_by_tag_respects_view() {
+        let mut app = App::new(crate::types::AppState::default());
+        let mut parked = tagged_task("a", &["work"]);
+        parked.someday = true;
+        app.state.tasks = vec![parked, tagged_task("b", &[])];
+        app.execute_command("someday").unwrap();
 
-                // Load the current field content into input buffer
-                let field_content = self.get_editing_task_field(self.state.editing_field);
-                self.state.input_buffer = field_content;
-            }
-        }
+        assert_eq!(app.group_by_tag(), vec![("work".to_string(), vec![0])]);
     }
-}
 
-pub enum SelectionIterator<'a> {
-    Multiple(std::collections::hash_set::Iter<'a, usize>),
-    Single(std::iter::Once<usize>),
-    Empty,
-}
+    #[test]
+    fn test_cursor_follows_grouped_rows() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.state.tasks = vec![
+            tagged_task("a", &["work", "home"]),
+            tagged_task("b", &[]),
+            tagged_task("c", &["home"]),
+        ];
+        app.task_list_state.select(Some(1));
+        assert_eq!(app.cursor_task_index(), Some(1));
 
-impl<'a> Iterator for SelectionIterator<'a> {
-    type Item = usize;
+        // Grouping keeps the cursor on its task, now in the Untagged group
+        app.execute_command("group").unwrap();
+        assert_eq!(app.row_task_indices(), vec![0, 2, 0, 1]);
+        assert_eq!(app.cursor_task_index(), Some(1));
 
-    fn next(&mut self) -> Option<Self::Item> {
-        match self {
-            SelectionIterator::Multiple(iter) => iter.next().copied(),
-            SelectionIterator::Single(iter) => iter.next(),
-            SelectionIterator::Empty => None,
+        app.cursor_first_task();
+        let mut visited = Vec::new();
+        for _ in 0..4 {
+            visited.push(app.cursor_task_index().unwrap());
+            app.cursor_next_task();
         }
+        assert_eq!(visited, vec![0, 2, 0, 1]);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use chrono::Timelike;
-    use std::time::Duration;
+    #[test]
+    fn test_execute_unknown_command() {
+        let mut app = App::new(crate::types::AppState::default());
+        assert!(app.execute_command("bogus").is_err());
+        assert_eq!(app.state.view, View::All);
+    }
+
+    #[test]
+    fn test_new_task_in_someday_view_is_someday() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.set_view(View::Someday);
+        app.create_task_below_cursor();
+        app.update_editing_task_field(0, "Learn the cello".to_string());
+        app.save_editing_task().unwrap();
+
+        assert!(app.state.tasks[0].someday);
+        assert_eq!(app.visible_task_indices(), vec![0]);
+    }
 
     #[test]
     fn test_parse_date_input_relative_days() {
@@ -457,6 +4715,110 @@ mod tests {
         }
     }
 
+    fn local(date: NaiveDate, hour: u32) -> SystemTime {
+        Local
+            .from_local_datetime(&date.and_hms_opt(hour, 0, 0).unwrap())
+            .single()
+            .unwrap()
+            .into()
+    }
+
+    #[test]
+    fn test_parse_time_of_day_keywords() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 12).unwrap(); // Wednesday
+        let fixed = local(today, 10);
+        let app = App::with_clock(crate::types::AppState::default(), Box::new(move || fixed));
+        let tomorrow = today.succ_opt().unwrap();
+
+        assert_eq!(app.parse_date_input("noon", true), Some(local(today, 12)));
+        assert_eq!(
+            app.parse_date_input("midnight", true),
+            Some(local(tomorrow, 0))
+        );
+        assert_eq!(app.parse_date_input("eod", true), Some(local(today, 17)));
+        assert_eq!(
+            app.parse_date_input("End of Day", true),
+            Some(local(today, 17))
+        );
+        assert_eq!(app.parse_date_input("morning", true), Some(local(today, 9)));
+        assert_eq!(
+            app.parse_date_input("afternoon", true),
+            Some(local(today, 14))
+        );
+        assert_eq!(
+            app.parse_date_input("evening", false),
+            Some(local(today, 19))
+        );
+    }
+
+    #[test]
+    fn test_parse_time_of_day_with_date_prefix() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 12).unwrap(); // Wednesday
+        let fixed = local(today, 10);
+        let mut app = App::with_clock(crate::types::AppState::default(), Box::new(move || fixed));
+        app.state.config.time.evening_hour = 20;
+
+        let friday = NaiveDate::from_ymd_opt(2025, 3, 14).unwrap();
+        assert_eq!(
+            app.parse_date_input("friday noon", true),
+            Some(local(friday, 12))
+        );
+        assert_eq!(
+            app.parse_date_input("tomorrow evening", true),
+            Some(local(today.succ_opt().unwrap(), 20))
+        );
+        assert_eq!(
+            app.parse_date_input("2025-04-01 midnight", true),
+            Some(local(NaiveDate::from_ymd_opt(2025, 4, 2).unwrap(), 0))
+        );
+        assert_eq!(app.parse_date_input("gibberish noon", true), None);
+        assert_eq!(app.parse_date_input("afternoonx", true), None);
+    }
+
+    #[test]
+    fn test_parse_clock_time_after_a_date() {
+        let today = NaiveDate::from_ymd_opt(2025, 3, 12).unwrap(); // Wednesday
+        let fixed = local(today, 10);
+        let app = App::with_clock(crate::types::AppState::default(), Box::new(move || fixed));
+        let parsed = |input: &str| {
+            app.parse_date_input(input, false)
+                .map(|time| DateTime::<Local>::from(time).naive_local())
+        };
+        let at = |date: NaiveDate, hour, minute| date.and_hms_opt(hour, minute, 0);
+
+        let tomorrow = today.succ_opt().unwrap();
+        let friday = NaiveDate::from_ymd_opt(2025, 3, 14).unwrap();
+        let christmas = NaiveDate::from_ymd_opt(2024, 12, 25).unwrap();
+        assert_eq!(parsed("tomorrow 3pm"), at(tomorrow, 15, 0));
+        assert_eq!(parsed("Friday 09:30"), at(friday, 9, 30));
+        assert_eq!(parsed("2024-12-25 14:00"), at(christmas, 14, 0));
+        assert_eq!(
+            parsed("next friday 9:15am"),
+            at(friday + chrono::Duration::days(7), 9, 15)
+        );
+        assert_eq!(parsed("tomorrow 12am"), at(tomorrow, 0, 0));
+        assert_eq!(parsed("tomorrow 12pm"), at(tomorrow, 12, 0));
+        // A time alone is today
+        assert_eq!(parsed("16:45"), at(today, 16, 45));
+
+        // Without a time the configured hour still applies
+        assert_eq!(parsed("tomorrow"), at(tomorrow, 9, 0));
+        assert_eq!(
+            DateTime::<Local>::from(app.parse_date_input("tomorrow", true).unwrap()).hour(),
+            17
+        );
+
+        for input in [
+            "tomorrow 13pm",
+            "tomorrow 24:00",
+            "tomorrow 9:5",
+            "tomorrow 9",
+            "someday 3pm",
+        ] {
+            assert_eq!(parsed(input), None, "{input:?}");
+        }
+    }
+
     #[test]
     fn test_parse_date_input_empty() {
         let app = App::new(crate::types::AppState::default());
@@ -465,6 +4827,43 @@ mod tests {
         assert_eq!(app.parse_date_input("   ", true), None);
     }
 
+    #[test]
+    fn test_parse_date_input_overflow_is_reported() {
+        let app = App::new(crate::types::AppState::default());
+        assert_eq!(
+            app.try_parse_date_input("99999999999d", true),
+            Err(DateParseError::TooFarInFuture)
+        );
+        assert_eq!(
+            app.try_parse_date_input("99999999999999999999999w", true),
+            Err(DateParseError::TooFarInFuture)
+        );
+        assert_eq!(app.parse_date_input("99999999999d", true), None);
+        assert!(app.try_parse_date_input("2d", true).unwrap().is_some());
+        assert_eq!(app.try_parse_date_input(" ", true), Ok(None));
+        assert_eq!(
+            app.try_parse_date_input("2x", true),
+            Err(DateParseError::Unrecognized("2x".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_overflowing_due_date_keeps_existing_and_sets_error() {
+        let mut app = App::new(crate::types::AppState::default());
+        let due = SystemTime::now() + Duration::from_secs(60 * 60);
+        let mut task = create_test_task("1", "Task");
+        task.due = Some(due);
+        app.state.editing_task = Some(task);
+
+        app.update_editing_task_field(2, "99999999999d".to_string());
+
+        assert_eq!(app.state.editing_task.as_ref().unwrap().due, Some(due));
+        assert_eq!(
+            app.get_error_message().map(String::as_str),
+            Some("Date too far in the future")
+        );
+    }
+
     #[test]
     fn test_parse_date_input_invalid() {
         let app = App::new(crate::types::AppState::default());
@@ -484,6 +4883,23 @@ mod tests {
             created_at: SystemTime::now(),
             due: None,
             defer_until: None,
+            someday: false,
+            tags: Vec::new(),
+            energy: None,
+            source: TaskSource::Tui,
+            url: None,
+            notes: String::new(),
+            project: None,
+            waiting_since: None,
+            in_progress: false,
+            subtasks: Vec::new(),
+            defer_until_task: None,
+            recurrence: None,
+            modified_at: SystemTime::UNIX_EPOCH,
+            reminder_lead: None,
+            next_occurrence_id: None,
+            completed_at: None,
+            priority: Priority::Medium,
         };
 
         app.state.editing_task = Some(task.clone());
@@ -548,7 +4964,7 @@ mod tests {
     fn test_parse_date_input_due_vs_defer_times() {
         let app = App::new(crate::types::AppState::default());
 
-        // Test due date (should default to 5pm/17:00)
+        // Test due date (should default to time.due_hour, 5pm)
         if let Some(due_date) = app.parse_date_input("tomorrow", true) {
             let dt = DateTime::<Local>::from(due_date);
             assert_eq!(dt.hour(), 17);
@@ -556,12 +4972,24 @@ mod tests {
             panic!("Failed to parse due date");
         }
 
-        // Test defer date (should default to 8am/08:00)
+        // Test defer date (should default to time.defer_hour, 9am)
         if let Some(defer_date) = app.parse_date_input("tomorrow", false) {
             let dt = DateTime::<Local>::from(defer_date);
-            assert_eq!(dt.hour(), 8);
+            assert_eq!(dt.hour(), 9);
         } else {
             panic!("Failed to parse defer date");
         }
     }
+
+    #[test]
+    fn test_parse_date_input_uses_configured_hours() {
+        let mut app = App::new(crate::types::AppState::default());
+        app.state.config.time.due_hour = 20;
+        app.state.config.time.defer_hour = 6;
+
+        let due = DateTime::<Local>::from(app.parse_date_input("tomorrow", true).unwrap());
+        assert_eq!((due.hour(), due.minute()), (20, 0));
+        let defer = DateTime::<Local>::from(app.parse_date_input("friday", false).unwrap());
+        assert_eq!(defer.hour(), 6);
+    }
 }
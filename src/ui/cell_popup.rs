@@ -0,0 +1,46 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+use super::render_text;
+
+/// Small floating popup with the full content of a clipped table cell
+pub struct CellPopup;
+
+impl CellPopup {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn render(&self, f: &mut Frame, area: Rect, label: &str, value: &str, indicator: &str) {
+        // Clear the background area to create floating effect
+        f.render_widget(Clear, area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(format!(" {label} "))
+            .title_style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .border_style(Style::default().fg(Color::Cyan))
+            .style(Style::default().bg(Color::DarkGray));
+        let content = render_text(block.inner(area), value, true, indicator);
+
+        let popup_paragraph = Paragraph::new(content)
+            .block(block)
+            .style(Style::default().fg(Color::White).bg(Color::DarkGray));
+
+        f.render_widget(popup_paragraph, area);
+    }
+}
+
+impl Default for CellPopup {
+    fn default() -> Self {
+        Self::new()
+    }
+}
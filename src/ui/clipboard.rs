@@ -0,0 +1,17 @@
+//! Reading the system clipboard for `Ctrl+V` in insert mode
+//!
+//! Only built in with the `clipboard` feature. Without it, or without a
+//! clipboard to talk to (e.g. over SSH), pasting with `Ctrl+V` does nothing;
+//! the terminal's own paste still works through bracketed paste.
+
+/// The text on the clipboard, or `None` when there is no clipboard or no text on it
+#[cfg(feature = "clipboard")]
+pub fn read_text() -> Option<String> {
+    arboard::Clipboard::new().ok()?.get_text().ok()
+}
+
+/// The text on the clipboard; always `None` without the `clipboard` feature
+#[cfg(not(feature = "clipboard"))]
+pub fn read_text() -> Option<String> {
+    None
+}
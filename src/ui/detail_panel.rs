@@ -0,0 +1,304 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+use std::time::SystemTime;
+
+use super::{format_created_at, format_date, markdown, render_text};
+use crate::config::{CreatedFormat, UiConfig};
+use crate::types::{format_lead, Task};
+
+/// Floating pane showing every field of the task under the cursor
+pub struct DetailPanel;
+
+impl DetailPanel {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Draw the pane, following the `[ui]` settings for clipping, dates and markdown
+    pub fn render(&self, f: &mut Frame, area: Rect, task: &Task, now: SystemTime, ui: &UiConfig) {
+        // Clear the background area to create floating effect
+        f.render_widget(Clear, area);
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(" Details ")
+            .title_style(
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .border_style(Style::default().fg(Color::Cyan))
+            .style(Style::default().bg(Color::DarkGray));
+        let content = self.create_detail_content(task, now, block.inner(area), ui);
+
+        let detail_paragraph = Paragraph::new(content)
+            .block(block)
+            .style(Style::default().fg(Color::White).bg(Color::DarkGray));
+
+        f.render_widget(detail_paragraph, area);
+    }
+
+    /// Lines of the pane, with title and description wrapped to fit `inner`
+    fn create_detail_content(
+        &self,
+        task: &Task,
+        now: SystemTime,
+        inner: Rect,
+        ui: &UiConfig,
+    ) -> Vec<Line<'static>> {
+        let status = if task.completed {
+            "Completed"
+        } else if task.someday {
+            "Someday"
+        } else {
+            "Open"
+        };
+
+        let title_area = Rect {
+            height: inner.height.min(3),
+            ..inner
+        };
+        let mut lines: Vec<Line<'static>> =
+            render_text(title_area, &task.title, true, &ui.truncation_indicator)
+                .lines
+                .into_iter()
+                .map(|line| line.style(Style::default().add_modifier(Modifier::BOLD)))
+                .collect();
+        lines.extend([
+            Line::from(""),
+            field_line("Status", status.to_string()),
+            field_line(
+                "Created",
+                format_created_at(task.created_at, now, CreatedFormat::Hybrid, &ui.date_format),
+            ),
+            field_line("Due", format_date(task.due, now, Some(&ui.date_format))),
+            field_line(
+                "Completed",
+                format_date(task.completed_at, now, Some(&ui.date_format)),
+            ),
+            field_line(
+                "Defer Until",
+                format_date(task.defer_until, now, Some(&ui.date_format)),
+            ),
+            field_line(
+                "Repeats",
+                task.recurrence.map(|r| r.name()).unwrap_or_default(),
+            ),
+            field_line(
+                "Remind",
+                task.reminder_lead
+                    .map(|lead| format!("{} before due", format_lead(lead)))
+                    .unwrap_or_default(),
+            ),
+            field_line("Tags", task.tags.join(", ")),
+            field_line(
+                "Energy",
+                task.energy.map(|e| e.name()).unwrap_or("").to_string(),
+            ),
+            field_line("Priority", task.priority.name().to_string()),
+            field_line("URL", task.url.clone().unwrap_or_default()),
+            Line::from(""),
+        ]);
+        let long_text = |text: &str| -> Vec<Line<'static>> {
+            if ui.render_markdown {
+                markdown::to_lines(text, inner.width as usize)
+            } else {
+                let area = Rect {
+                    height: u16::MAX,
+                    ..inner
+                };
+                render_text(area, text, true, &ui.truncation_indicator).lines
+            }
+        };
+        lines.extend(long_text(&task.description));
+        if !task.subtasks.is_empty() {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                "Subtasks",
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD),
+            )));
+            lines.extend(task.subtasks.iter().map(|subtask| {
+                let check = if subtask.completed { "[x]" } else { "[ ]" };
+                Line::from(format!("{check} {}", subtask.title))
+            }));
+        }
+        if !task.notes.is_empty() {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                "Notes",
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD),
+            )));
+            lines.extend(long_text(&task.notes));
+        }
+        lines
+    }
+}
+
+fn field_line(label: &str, value: String) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(
+            format!("{label:<12}"),
+            Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::raw(value),
+    ])
+}
+
+impl Default for DetailPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Priority, TaskSource};
+    use ratatui::{backend::TestBackend, Terminal};
+
+    fn create_test_task() -> Task {
+        Task {
+            id: "1".to_string(),
+            title: "Write report".to_string(),
+            description: "First line\nSecond line".to_string(),
+            completed: false,
+            created_at: SystemTime::now(),
+            due: None,
+            defer_until: None,
+            someday: false,
+            tags: Vec::new(),
+            energy: None,
+            source: TaskSource::Tui,
+            url: None,
+            notes: String::new(),
+            project: None,
+            waiting_since: None,
+            in_progress: false,
+            subtasks: Vec::new(),
+            defer_until_task: None,
+            recurrence: None,
+            modified_at: SystemTime::UNIX_EPOCH,
+            reminder_lead: None,
+            next_occurrence_id: None,
+            completed_at: None,
+            priority: Priority::Medium,
+        }
+    }
+
+    #[test]
+    fn test_detail_content_includes_fields() {
+        let task = create_test_task();
+        let content = DetailPanel::new().create_detail_content(
+            &task,
+            SystemTime::now(),
+            Rect::new(0, 0, 40, 10),
+            &UiConfig::default(),
+        );
+        let text: Vec<String> = content.iter().map(|l| l.to_string()).collect();
+
+        assert_eq!(text[0], "Write report");
+        assert!(text
+            .iter()
+            .any(|l| l.starts_with("Status") && l.ends_with("Open")));
+        assert!(text.iter().any(|l| l == "First line"));
+        assert!(text.iter().any(|l| l == "Second line"));
+    }
+
+    #[test]
+    fn test_detail_content_shows_completion_time() {
+        let now = SystemTime::now();
+        let mut task = create_test_task();
+        task.completed = true;
+        task.completed_at = Some(now - std::time::Duration::from_secs(2 * 86_400));
+        let content = DetailPanel::new().create_detail_content(
+            &task,
+            now,
+            Rect::new(0, 0, 60, 20),
+            &UiConfig::default(),
+        );
+        let text: Vec<String> = content.iter().map(|l| l.to_string()).collect();
+        assert!(text
+            .iter()
+            .any(|l| l.starts_with("Completed") && l.contains("2d ago")));
+    }
+
+    #[test]
+    fn test_detail_content_wraps_long_title() {
+        let mut task = create_test_task();
+        task.title = "A title long enough to need wrapping".to_string();
+        let content = DetailPanel::new().create_detail_content(
+            &task,
+            SystemTime::now(),
+            Rect::new(0, 0, 20, 10),
+            &UiConfig::default(),
+        );
+
+        assert_eq!(content[0].to_string(), "A title long enough");
+        assert_eq!(content[1].to_string(), "to need wrapping");
+    }
+
+    #[test]
+    fn test_detail_content_renders_markdown_when_enabled() {
+        let mut task = create_test_task();
+        task.description = "**Important**".to_string();
+        task.notes = "- first\n- second".to_string();
+        let content = |render_markdown| {
+            DetailPanel::new().create_detail_content(
+                &task,
+                SystemTime::now(),
+                Rect::new(0, 0, 40, 10),
+                &UiConfig {
+                    render_markdown,
+                    ..UiConfig::default()
+                },
+            )
+        };
+
+        let plain: Vec<String> = content(false).iter().map(|l| l.to_string()).collect();
+        assert!(plain.iter().any(|l| l == "**Important**"));
+        assert!(plain.iter().any(|l| l == "- first"));
+
+        let rendered = content(true);
+        let important = rendered
+            .iter()
+            .find(|l| l.to_string() == "Important")
+            .unwrap();
+        assert!(important.spans[0]
+            .style
+            .add_modifier
+            .contains(Modifier::BOLD));
+        let text: Vec<String> = rendered.iter().map(|l| l.to_string()).collect();
+        assert!(text.iter().any(|l| l == "• first"));
+        assert!(text.iter().any(|l| l == "• second"));
+    }
+
+    #[test]
+    fn test_detail_panel_render() {
+        let backend = TestBackend::new(50, 12);
+        let mut terminal = Terminal::new(backend).unwrap();
+        let task = create_test_task();
+
+        terminal
+            .draw(|f| {
+                DetailPanel::new().render(
+                    f,
+                    Rect::new(0, 0, 50, 12),
+                    &task,
+                    SystemTime::now(),
+                    &UiConfig::default(),
+                )
+            })
+            .unwrap();
+    }
+}
@@ -1,8 +1,10 @@
-use ratatui::crossterm::event::{Event, KeyCode, KeyEventKind};
+use ratatui::crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 
+use crate::config::{Action, ConfirmOtherKeys, EnterBehavior};
 use crate::storage::Db;
-use crate::types::Mode;
-use crate::ui::app::App;
+use crate::types::{Mode, Urgency};
+use crate::ui::app::{App, CaretMove, ConfirmAction};
+use crate::ui::clipboard;
 
 pub struct EventHandler;
 
@@ -12,57 +14,277 @@ impl EventHandler {
     }
 
     pub fn handle_event<D: Db>(&self, event: Event, app: &mut App<D>) {
+        if let Event::Paste(text) = &event {
+            app.paste(text);
+        }
         if let Event::Key(key) = event {
             if key.kind == KeyEventKind::Press {
+                app.refresh_list();
+                if self.handle_chord(key, app) {
+                    return;
+                }
                 match app.state.mode {
+                    // A y/n prompt takes every key, modified or not, until answered
+                    Mode::Normal if app.pending_confirm.is_some() => {
+                        self.handle_confirm_key(key.code, app)
+                    }
+                    Mode::Normal
+                        if key.modifiers.contains(KeyModifiers::CONTROL)
+                            && key.code == KeyCode::Char('r') =>
+                    {
+                        self.redo(app)
+                    }
+                    // Alt keeps the urgency quick filters apart from plain digits
+                    Mode::Normal if key.modifiers.contains(KeyModifiers::ALT) => match key.code {
+                        KeyCode::Char('1') => app.set_urgency_filter(Urgency::Overdue),
+                        KeyCode::Char('2') => app.set_urgency_filter(Urgency::Today),
+                        KeyCode::Char('3') => app.set_urgency_filter(Urgency::Week),
+                        code => self.handle_normal_key(code, app),
+                    },
                     Mode::Normal => self.handle_normal_key(key.code, app),
+                    Mode::Insert if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        self.handle_insert_ctrl_key(key.code, app)
+                    }
                     Mode::Insert => self.handle_insert_key(key.code, app),
+                    Mode::Command => self.handle_command_key(key.code, app),
+                    Mode::Scratch => self.handle_scratch_key(key.code, app),
+                    Mode::Search => self.handle_search_key(key.code, app),
                 }
             }
         }
     }
 
-    fn handle_normal_key<D: Db>(&self, key: KeyCode, app: &mut App<D>) {
+    /// Run the action a Ctrl or Alt chord such as `Ctrl+[` is bound to
+    ///
+    /// Returns whether the chord was bound; unbound chords keep their
+    /// built-in meaning. Chords never complete a pending prompt, leader or
+    /// sequence.
+    fn handle_chord<D: Db>(&self, key: KeyEvent, app: &mut App<D>) -> bool {
+        if !key
+            .modifiers
+            .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT)
+        {
+            return false;
+        }
+        let Some(name) = key_name(key.code, key.modifiers) else {
+            return false;
+        };
+        let keymap = &app.state.config.keymap;
+        match app.state.mode {
+            Mode::Normal
+                if app.pending_confirm.is_none()
+                    && !app.leader_pending
+                    && app.pending_key.is_none() =>
+            {
+                match keymap.normal_action(&name) {
+                    Some(action) => self.run_action(action, app),
+                    None => return false,
+                }
+            }
+            Mode::Insert => match keymap.insert_action(&name) {
+                Some(action) => self.run_insert_action(action, app),
+                None => return false,
+            },
+            _ => return false,
+        }
+        true
+    }
+
+    fn redo<D: Db>(&self, app: &mut App<D>) {
+        match app.redo() {
+            Ok(true) => {}
+            Ok(false) => app.set_error_message("Nothing to redo".to_string()),
+            Err(e) => app.set_error_message(format!("Error redoing change: {e}")),
+        }
+    }
+
+    /// Answer a y/n prompt: `y` confirms, `n` and Esc cancel
+    ///
+    /// Other keys are swallowed; `behavior.confirm_other_keys` decides whether
+    /// they also cancel the prompt or are ignored.
+    fn handle_confirm_key<D: Db>(&self, key: KeyCode, app: &mut App<D>) {
         match key {
-            KeyCode::Char('q') => app.quit(),
+            KeyCode::Char('y') => {
+                if let Err(e) = app.confirm_pending() {
+                    app.set_error_message(format!("Error deleting tasks: {e}"));
+                }
+            }
+            KeyCode::Char('n') | KeyCode::Esc => app.cancel_confirmation(),
+            _ => match app.state.config.behavior.confirm_other_keys {
+                ConfirmOtherKeys::Cancel => app.cancel_confirmation(),
+                ConfirmOtherKeys::Ignore => {}
+            },
+        }
+    }
 
-            KeyCode::Char('o') => {
-                app.create_task_below_cursor();
-                app.state.mode = Mode::Insert;
-                app.clear_error_message();
-                // Load the current field content into input buffer
-                let field_content = app.get_editing_task_field(app.state.editing_field);
-                app.state.input_buffer = field_content;
+    fn handle_normal_key<D: Db>(&self, key: KeyCode, app: &mut App<D>) {
+        // Finish a leader sequence; Esc or an unbound key just closes the popup
+        if app.leader_pending {
+            app.leader_pending = false;
+            if let KeyCode::Char(c) = key {
+                if let Some(action) = app.state.config.keymap.leader_action(c) {
+                    self.run_action(action, app);
+                }
+            }
+            return;
+        }
+        let keymap = &app.state.config.keymap;
+        if matches!(key, KeyCode::Char(c) if Some(c) == keymap.leader_key())
+            && !keymap.leader_bindings().is_empty()
+        {
+            app.leader_pending = true;
+            return;
+        }
+
+        // Complete a two-key sequence; an unknown second key cancels it
+        let action = match (app.pending_key, key) {
+            (Some(prefix), KeyCode::Char(c)) => keymap.normal_action(&format!("{prefix}{c}")),
+            (Some(_), _) => None,
+            (None, KeyCode::Char(c)) if keymap.starts_sequence(c) => {
+                app.pending_key = Some(c);
+                return;
+            }
+            (None, key) => {
+                key_name(key, KeyModifiers::NONE).and_then(|name| keymap.normal_action(&name))
             }
-            KeyCode::Char('O') => {
-                app.create_task_above_cursor();
+        };
+        app.pending_key = None;
+        if let Some(action) = action {
+            self.run_action(action, app);
+        }
+    }
+
+    /// Run the action a key is bound to
+    ///
+    /// Actions that only make sense while typing (like `confirm`) do nothing
+    /// in Normal mode.
+    fn run_action<D: Db>(&self, action: Action, app: &mut App<D>) {
+        match action {
+            Action::Quit => app.quit(),
+            Action::NewTaskBelow | Action::NewTaskAbove => {
+                if action == Action::NewTaskBelow {
+                    app.create_task_below_cursor();
+                } else {
+                    app.create_task_above_cursor();
+                }
                 app.state.mode = Mode::Insert;
                 app.clear_error_message();
                 // Load the current field content into input buffer
-                let field_content = app.get_editing_task_field(app.state.editing_field);
-                app.state.input_buffer = field_content;
+                app.load_editing_field();
             }
-            KeyCode::Char('i') => {
+            Action::EditTask => {
                 app.start_editing_current_task();
                 app.state.mode = Mode::Insert;
                 app.clear_error_message();
             }
-            KeyCode::Char('h') => {
-                app.state.show_help = !app.state.show_help;
+            Action::OpenDetail => app.toggle_detail(),
+            Action::Escape => {
+                app.state.show_detail = false;
+                app.state.cell_popup = None;
+                app.state.show_about = false;
+                app.clear_search();
             }
-            KeyCode::Char('j') => app.cursor_next_task(),
-            KeyCode::Char('k') => app.cursor_previous_task(),
-            KeyCode::Char('g') => app.cursor_first_task(),
-            KeyCode::Char('G') => app.cursor_last_task(),
-            KeyCode::Char('!') => {
+            Action::ToggleAbout => app.state.show_about = !app.state.show_about,
+            Action::ToggleHelp => app.state.show_help = !app.state.show_help,
+            Action::ToggleScratch => app.state.show_scratch = !app.state.show_scratch,
+            Action::EditScratch => app.start_scratch_edit(),
+            Action::ToggleFocus => app.state.focus_mode = !app.state.focus_mode,
+            Action::CycleSort => app.cycle_sort_key(),
+            Action::ReverseSort => app.toggle_sort_direction(),
+            Action::CellPopup => app.cycle_cell_popup(),
+            Action::ClearFilters => app.clear_filters(),
+            Action::MoveDown => app.cursor_next_task(),
+            Action::MoveUp => app.cursor_previous_task(),
+            Action::MoveTop => app.cursor_first_task(),
+            Action::MoveBottom => app.cursor_last_task(),
+            Action::NextUrgent => app.cursor_next_urgent_task(),
+            Action::PreviousUrgent => app.cursor_previous_urgent_task(),
+            Action::ToggleComplete => {
                 if let Err(e) = app.toggle_task_completion() {
                     app.set_error_message(format!("Error updating task: {e}"));
                 }
             }
-            KeyCode::Char('x') => app.toggle_task_selection(),
-            KeyCode::Char('D') => {
-                if let Err(e) = app.delete_tasks() {
-                    app.set_error_message(format!("Error deleting tasks: {e}"));
+            Action::CycleStatus => app.cycle_task_status(),
+            Action::CyclePriority => {
+                if let Err(e) = app.cycle_priority() {
+                    app.set_error_message(format!("Error updating task: {e}"));
+                }
+            }
+            Action::ToggleTagFilter => {
+                if let Err(e) = app.toggle_tag_filter() {
+                    app.set_error_message(e);
+                }
+            }
+            Action::ToggleTimer => {
+                if let Err(e) = app.toggle_timer() {
+                    app.set_error_message(format!("Error saving time entry: {e}"));
+                }
+            }
+            Action::ToggleSomeday => {
+                if let Err(e) = app.toggle_someday() {
+                    app.set_error_message(format!("Error updating task: {e}"));
+                }
+            }
+            Action::Demote => {
+                if let Err(e) = app.demote_cursor_task() {
+                    app.set_error_message(format!("Error updating task: {e}"));
+                }
+            }
+            Action::ToggleMark => app.toggle_task_selection(),
+            Action::DeleteTasks => app.request_confirmation(ConfirmAction::DeleteTasks),
+            Action::DueFromDefer => {
+                if let Err(e) = app.due_from_defer() {
+                    app.set_error_message(e);
+                }
+            }
+            Action::DeferFromDue => {
+                if let Err(e) = app.defer_from_due(chrono::Duration::zero()) {
+                    app.set_error_message(e);
+                }
+            }
+            Action::Undo => match app.undo() {
+                Ok(true) => {}
+                Ok(false) => app.set_error_message("Nothing to undo".to_string()),
+                Err(e) => app.set_error_message(format!("Error undoing change: {e}")),
+            },
+            Action::Redo => self.redo(app),
+            Action::CommandMode => {
+                app.clear_input_buffer();
+                app.clear_error_message();
+                app.state.mode = Mode::Command;
+            }
+            Action::Search => app.start_search(),
+            Action::NextMatch | Action::PreviousMatch => {
+                if !app.cursor_step_match(action == Action::NextMatch) {
+                    app.set_error_message("No search; press / to search".to_string());
+                }
+            }
+            Action::Confirm | Action::NextField | Action::PrevField => {}
+        }
+    }
+
+    /// Store the field being typed, save the edited task and leave Insert mode
+    fn save_edit<D: Db>(&self, app: &mut App<D>) {
+        let input_text = app.state.input_buffer.trim().to_string();
+        app.update_editing_task_field(app.state.editing_field, input_text);
+        if let Err(e) = app.save_editing_task() {
+            app.set_error_message(format!("Error saving task: {e}"));
+        }
+        app.clear_input_buffer();
+        app.state.mode = Mode::Normal;
+    }
+
+    /// Ctrl+S saves, Ctrl+W deletes the word before the caret and Ctrl+U the line up to it
+    ///
+    /// Ctrl+V pastes from the system clipboard, when wimm is built with one.
+    fn handle_insert_ctrl_key<D: Db>(&self, key: KeyCode, app: &mut App<D>) {
+        match key {
+            KeyCode::Char('s') if app.state.editing_task.is_some() => self.save_edit(app),
+            KeyCode::Char('w') => app.delete_word_before_caret(),
+            KeyCode::Char('u') => app.delete_line_before_caret(),
+            KeyCode::Char('v') => {
+                if let Some(text) = clipboard::read_text() {
+                    app.paste(&text);
                 }
             }
             _ => {}
@@ -70,26 +292,60 @@ impl EventHandler {
     }
 
     fn handle_insert_key<D: Db>(&self, key: KeyCode, app: &mut App<D>) {
+        let keymap = &app.state.config.keymap;
+        if let Some(action) =
+            key_name(key, KeyModifiers::NONE).and_then(|name| keymap.insert_action(&name))
+        {
+            self.run_insert_action(action, app);
+            return;
+        }
         match key {
-            KeyCode::Esc => {
+            KeyCode::Backspace => {
+                app.backspace_input_buffer();
+            }
+            KeyCode::Left => app.move_caret(CaretMove::Left),
+            KeyCode::Right => app.move_caret(CaretMove::Right),
+            KeyCode::Home => app.move_caret(CaretMove::Home),
+            KeyCode::End => app.move_caret(CaretMove::End),
+            KeyCode::Up | KeyCode::Down => {
+                app.state.select_pending = false;
+            }
+            KeyCode::Char(c) => {
+                app.add_to_input_buffer(c);
+            }
+            _ => {}
+        }
+    }
+
+    /// Run the action an Insert mode key is bound to
+    fn run_insert_action<D: Db>(&self, action: Action, app: &mut App<D>) {
+        match action {
+            Action::Escape => {
                 app.clear_input_buffer();
                 app.state.mode = Mode::Normal;
                 app.state.editing_task = None;
             }
-            KeyCode::Backspace => {
-                app.backspace_input_buffer();
-            }
-            KeyCode::Enter => {
+            Action::Confirm => {
                 if app.state.editing_task.is_some() {
-                    // Save current field
+                    // With next_field, Enter only saves from the last field; a new
+                    // task with next_field_when_new also saves on an empty field
+                    let advance = match app.state.config.editing.enter_behavior {
+                        EnterBehavior::Save => false,
+                        EnterBehavior::NextField => true,
+                        EnterBehavior::NextFieldWhenNew => {
+                            app.editing_new_task() && !app.state.input_buffer.trim().is_empty()
+                        }
+                    } && app.state.editing_field + 1 < app.edit_fields().len();
+                    if !advance {
+                        self.save_edit(app);
+                        return;
+                    }
+
+                    // Save current field and move on
                     let input_text = app.state.input_buffer.trim().to_string();
                     app.update_editing_task_field(app.state.editing_field, input_text);
-
-                    if let Err(e) = app.save_editing_task() {
-                        app.set_error_message(format!("Error saving task: {e}"));
-                    }
-                    app.clear_input_buffer();
-                    app.state.mode = Mode::Normal;
+                    app.step_editing_field(true);
+                    app.load_editing_field();
                 } else {
                     // Legacy behavior for backward compatibility
                     let input_text = app.state.input_buffer.trim().to_string();
@@ -104,41 +360,83 @@ impl EventHandler {
                     app.state.mode = Mode::Normal;
                 }
             }
-            KeyCode::Tab => {
-                if app.state.editing_task.is_some() {
-                    // Save current field before switching
-                    let input_text = app.state.input_buffer.trim().to_string();
-                    app.update_editing_task_field(app.state.editing_field, input_text);
+            Action::NextField | Action::PrevField if app.state.editing_task.is_some() => {
+                // Save current field before switching
+                let input_text = app.state.input_buffer.trim().to_string();
+                app.update_editing_task_field(app.state.editing_field, input_text);
 
-                    // Move to next field (0: title, 1: description, 2: due, 3: defer_until)
-                    app.state.editing_field = (app.state.editing_field + 1) % 4;
+                // Move to the neighbouring field in the configured order
+                app.step_editing_field(action == Action::NextField);
 
-                    // Load the new field's content into input buffer
-                    let field_content = app.get_editing_task_field(app.state.editing_field);
-                    app.state.input_buffer = field_content;
-                }
+                // Load the new field's content into input buffer
+                app.load_editing_field();
             }
-            KeyCode::BackTab => {
-                if app.state.editing_task.is_some() {
-                    // Save current field before switching
-                    let input_text = app.state.input_buffer.trim().to_string();
-                    app.update_editing_task_field(app.state.editing_field, input_text);
+            _ => {}
+        }
+    }
 
-                    // Move to previous field
-                    app.state.editing_field = if app.state.editing_field == 0 {
-                        3
-                    } else {
-                        app.state.editing_field - 1
-                    };
+    fn handle_command_key<D: Db>(&self, key: KeyCode, app: &mut App<D>) {
+        match key {
+            KeyCode::Esc => {
+                app.clear_input_buffer();
+                app.state.mode = Mode::Normal;
+            }
+            KeyCode::Backspace => {
+                app.backspace_input_buffer();
+            }
+            KeyCode::Tab => app.complete_command(),
+            KeyCode::Enter => {
+                let command = app.state.input_buffer.clone();
+                app.clear_input_buffer();
+                app.state.mode = Mode::Normal;
+                if let Err(e) = app.execute_command(&command) {
+                    app.set_error_message(e);
+                }
+            }
+            KeyCode::Char(c) => {
+                app.add_to_input_buffer(c);
+            }
+            _ => {}
+        }
+    }
 
-                    // Load the new field's content into input buffer
-                    let field_content = app.get_editing_task_field(app.state.editing_field);
-                    app.state.input_buffer = field_content;
+    /// Type a search: the list narrows as the text changes, Enter keeps it, Esc drops it
+    fn handle_search_key<D: Db>(&self, key: KeyCode, app: &mut App<D>) {
+        match key {
+            KeyCode::Esc => {
+                app.clear_input_buffer();
+                app.clear_search();
+                app.state.mode = Mode::Normal;
+            }
+            KeyCode::Enter => {
+                app.clear_input_buffer();
+                app.state.mode = Mode::Normal;
+                if !app.state.search.is_empty() && app.visible_task_indices().is_empty() {
+                    app.set_error_message(format!("No tasks match '{}'", app.state.search));
                 }
             }
+            KeyCode::Backspace => {
+                app.backspace_input_buffer();
+                app.update_search();
+            }
             KeyCode::Char(c) => {
                 app.add_to_input_buffer(c);
+                app.update_search();
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_scratch_key<D: Db>(&self, key: KeyCode, app: &mut App<D>) {
+        match key {
+            KeyCode::Esc => {
+                if let Err(e) = app.finish_scratch_edit() {
+                    app.set_error_message(format!("Error saving scratchpad: {e}"));
+                }
             }
+            KeyCode::Backspace => app.backspace_input_buffer(),
+            KeyCode::Enter => app.add_to_input_buffer('\n'),
+            KeyCode::Char(c) => app.add_to_input_buffer(c),
             _ => {}
         }
     }
@@ -150,14 +448,48 @@ impl Default for EventHandler {
     }
 }
 
+/// The name a key goes by in keymaps, e.g. `"j"`, `"Enter"`, `"Shift+Tab"`
+/// or `"Ctrl+["`
+///
+/// Ctrl and Alt prefix the name; Shift is already part of the character.
+fn key_name(key: KeyCode, modifiers: KeyModifiers) -> Option<String> {
+    let mut prefix = String::new();
+    if modifiers.contains(KeyModifiers::CONTROL) {
+        prefix.push_str("Ctrl+");
+    }
+    if modifiers.contains(KeyModifiers::ALT) {
+        prefix.push_str("Alt+");
+    }
+    let name = match key {
+        KeyCode::Char(c) => return Some(format!("{prefix}{c}")),
+        KeyCode::Enter => "Enter",
+        KeyCode::Esc => "Esc",
+        KeyCode::Tab => "Tab",
+        KeyCode::BackTab => "Shift+Tab",
+        KeyCode::Backspace => "Backspace",
+        KeyCode::Delete => "Delete",
+        KeyCode::Up => "Up",
+        KeyCode::Down => "Down",
+        KeyCode::Left => "Left",
+        KeyCode::Right => "Right",
+        KeyCode::Home => "Home",
+        KeyCode::End => "End",
+        KeyCode::PageUp => "PageUp",
+        KeyCode::PageDown => "PageDown",
+        _ => return None,
+    };
+    Some(format!("{prefix}{name}"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::config::{Config, EditField};
     use crate::storage::MemoryStorage;
-    use crate::types::{AppState, Task};
+    use crate::types::{AppState, Priority, Task, TaskSource, TaskStatus};
     use ratatui::crossterm::event::{KeyEvent, KeyModifiers};
     use std::collections::HashMap;
-    use std::time::SystemTime;
+    use std::time::{Duration, SystemTime};
 
     fn create_test_app() -> App<MemoryStorage> {
         let store = MemoryStorage::new(HashMap::new());
@@ -174,6 +506,23 @@ mod tests {
             created_at: SystemTime::now(),
             due: None,
             defer_until: None,
+            someday: false,
+            tags: Vec::new(),
+            energy: None,
+            source: TaskSource::Tui,
+            url: None,
+            notes: String::new(),
+            project: None,
+            waiting_since: None,
+            in_progress: false,
+            subtasks: Vec::new(),
+            defer_until_task: None,
+            recurrence: None,
+            modified_at: SystemTime::UNIX_EPOCH,
+            reminder_lead: None,
+            next_occurrence_id: None,
+            completed_at: None,
+            priority: Priority::Medium,
         }
     }
 
@@ -231,6 +580,99 @@ mod tests {
         assert!(!app.state.show_help);
     }
 
+    #[test]
+    fn test_handle_clear_filters_key() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        app.state.tasks = vec![create_test_task("1", "Task 1")];
+        app.execute_command("filter nothing-matches").unwrap();
+        app.set_view(crate::types::View::Someday);
+
+        handler.handle_event(create_key_event(KeyCode::Char('\\')), &mut app);
+        assert_eq!(app.visible_task_indices(), vec![0]);
+    }
+
+    #[test]
+    fn test_handle_search_filters_and_steps_through_matches() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        let mut report = create_test_task("3", "Report");
+        report.description = "Send the quarterly NUMBERS".to_string();
+        app.state.tasks = vec![
+            create_test_task("1", "Buy numbers game"),
+            create_test_task("2", "Walk the dog"),
+            report,
+        ];
+        app.cursor_first_task();
+
+        handler.handle_event(create_key_event(KeyCode::Char('/')), &mut app);
+        assert_eq!(app.state.mode, Mode::Search);
+        for c in "numbz".chars() {
+            handler.handle_event(create_key_event(KeyCode::Char(c)), &mut app);
+        }
+        assert!(app.visible_task_indices().is_empty());
+        handler.handle_event(create_key_event(KeyCode::Backspace), &mut app);
+        // Narrowed as it is typed, matching titles and descriptions in any case
+        assert_eq!(app.visible_task_indices(), vec![0, 2]);
+        handler.handle_event(create_key_event(KeyCode::Enter), &mut app);
+        assert_eq!(app.state.mode, Mode::Normal);
+        assert_eq!(app.state.search, "numb");
+
+        // n and N wrap around the matches
+        assert_eq!(app.cursor_task_index(), Some(0));
+        handler.handle_event(create_key_event(KeyCode::Char('n')), &mut app);
+        assert_eq!(app.cursor_task_index(), Some(2));
+        handler.handle_event(create_key_event(KeyCode::Char('n')), &mut app);
+        assert_eq!(app.cursor_task_index(), Some(0));
+        handler.handle_event(create_key_event(KeyCode::Char('N')), &mut app);
+        assert_eq!(app.cursor_task_index(), Some(2));
+
+        // Esc shows every task again, keeping the cursor on its task
+        handler.handle_event(create_key_event(KeyCode::Esc), &mut app);
+        assert!(app.state.search.is_empty());
+        assert_eq!(app.visible_task_indices(), vec![0, 1, 2]);
+        assert_eq!(app.cursor_task_index(), Some(2));
+
+        handler.handle_event(create_key_event(KeyCode::Char('n')), &mut app);
+        assert_eq!(
+            app.get_error_message(),
+            Some(&"No search; press / to search".to_string())
+        );
+    }
+
+    #[test]
+    fn test_handle_search_esc_cancels_and_enter_reports_no_matches() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        app.state.tasks = vec![create_test_task("1", "Task 1")];
+
+        handler.handle_event(create_key_event(KeyCode::Char('/')), &mut app);
+        handler.handle_event(create_key_event(KeyCode::Char('x')), &mut app);
+        handler.handle_event(create_key_event(KeyCode::Esc), &mut app);
+        assert_eq!(app.state.mode, Mode::Normal);
+        assert_eq!(app.visible_task_indices(), vec![0]);
+
+        handler.handle_event(create_key_event(KeyCode::Char('/')), &mut app);
+        handler.handle_event(create_key_event(KeyCode::Char('x')), &mut app);
+        handler.handle_event(create_key_event(KeyCode::Enter), &mut app);
+        assert!(app.get_error_message().is_some());
+        // Clearing filters drops the search too
+        handler.handle_event(create_key_event(KeyCode::Char('\\')), &mut app);
+        assert_eq!(app.visible_task_indices(), vec![0]);
+    }
+
+    #[test]
+    fn test_handle_focus_mode_toggle() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+
+        handler.handle_event(create_key_event(KeyCode::Char('F')), &mut app);
+        assert!(app.state.focus_mode);
+
+        handler.handle_event(create_key_event(KeyCode::Char('F')), &mut app);
+        assert!(!app.state.focus_mode);
+    }
+
     #[test]
     fn test_handle_create_task_below() {
         let handler = EventHandler::new();
@@ -340,6 +782,159 @@ mod tests {
         assert!(app.state.input_buffer.is_empty());
     }
 
+    #[test]
+    fn test_handle_insert_mode_enter_next_field_advances_then_saves() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        app.state.config.editing.enter_behavior = EnterBehavior::NextField;
+        app.state.config.ui.edit_fields = vec![EditField::Title, EditField::Description];
+        let task = create_test_task("test", "Original Title");
+        app.state.tasks = vec![task.clone()];
+
+        app.state.mode = Mode::Insert;
+        app.state.input_buffer = "Updated Title".to_string();
+        app.state.editing_task = Some(task);
+        app.state.editing_field = 0;
+
+        handler.handle_event(create_key_event(KeyCode::Enter), &mut app);
+        assert_eq!(app.state.mode, Mode::Insert);
+        assert_eq!(app.state.editing_field, 1);
+        assert_eq!(app.state.tasks[0].title, "Original Title");
+
+        app.state.input_buffer = "New description".to_string();
+        handler.handle_event(create_key_event(KeyCode::Enter), &mut app);
+        assert_eq!(app.state.mode, Mode::Normal);
+        assert!(app.state.editing_task.is_none());
+        assert_eq!(app.state.tasks[0].title, "Updated Title");
+        assert_eq!(app.state.tasks[0].description, "New description");
+    }
+
+    #[test]
+    fn test_handle_insert_mode_enter_new_task_advances_until_empty_field() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        app.state.config.editing.enter_behavior = EnterBehavior::NextFieldWhenNew;
+        app.state.config.ui.edit_fields =
+            vec![EditField::Title, EditField::Due, EditField::Description];
+
+        handler.handle_event(create_key_event(KeyCode::Char('o')), &mut app);
+        for c in "Pay rent".chars() {
+            handler.handle_event(create_key_event(KeyCode::Char(c)), &mut app);
+        }
+        handler.handle_event(create_key_event(KeyCode::Enter), &mut app);
+        assert_eq!(app.state.mode, Mode::Insert);
+        assert_eq!(app.state.editing_field, 1);
+
+        for c in "2d".chars() {
+            handler.handle_event(create_key_event(KeyCode::Char(c)), &mut app);
+        }
+        handler.handle_event(create_key_event(KeyCode::Enter), &mut app);
+        assert_eq!(app.state.editing_field, 2);
+
+        // Enter on the empty description saves
+        handler.handle_event(create_key_event(KeyCode::Enter), &mut app);
+        assert_eq!(app.state.mode, Mode::Normal);
+        assert_eq!(app.state.tasks.len(), 1);
+        assert_eq!(app.state.tasks[0].title, "Pay rent");
+        assert!(app.state.tasks[0].due.is_some());
+        assert!(app.state.tasks[0].description.is_empty());
+    }
+
+    #[test]
+    fn test_handle_insert_mode_enter_existing_task_saves_when_new_only() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        app.state.config.editing.enter_behavior = EnterBehavior::NextFieldWhenNew;
+        let task = create_test_task("test", "Original Title");
+        app.state.tasks = vec![task.clone()];
+
+        app.state.mode = Mode::Insert;
+        app.state.input_buffer = "Updated Title".to_string();
+        app.state.editing_task = Some(task);
+        app.state.editing_field = 0;
+
+        handler.handle_event(create_key_event(KeyCode::Enter), &mut app);
+        assert_eq!(app.state.mode, Mode::Normal);
+        assert_eq!(app.state.tasks[0].title, "Updated Title");
+    }
+
+    #[test]
+    fn test_handle_ctrl_s_saves_new_task_from_any_field() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        app.state.config.editing.enter_behavior = EnterBehavior::NextFieldWhenNew;
+
+        handler.handle_event(create_key_event(KeyCode::Char('o')), &mut app);
+        for c in "Call Sam".chars() {
+            handler.handle_event(create_key_event(KeyCode::Char(c)), &mut app);
+        }
+        let ctrl_s = Event::Key(KeyEvent {
+            code: KeyCode::Char('s'),
+            modifiers: KeyModifiers::CONTROL,
+            kind: KeyEventKind::Press,
+            state: ratatui::crossterm::event::KeyEventState::NONE,
+        });
+        handler.handle_event(ctrl_s, &mut app);
+
+        assert_eq!(app.state.mode, Mode::Normal);
+        assert!(app.state.editing_task.is_none());
+        assert_eq!(app.state.tasks[0].title, "Call Sam");
+    }
+
+    #[test]
+    fn test_handle_scratchpad_edit_persists() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+
+        handler.handle_event(create_key_event(KeyCode::Char('I')), &mut app);
+        assert_eq!(app.state.mode, Mode::Scratch);
+        assert!(app.state.show_scratch);
+        for key in [
+            KeyCode::Char('a'),
+            KeyCode::Char('b'),
+            KeyCode::Backspace,
+            KeyCode::Enter,
+            KeyCode::Char('c'),
+        ] {
+            handler.handle_event(create_key_event(key), &mut app);
+        }
+        handler.handle_event(create_key_event(KeyCode::Esc), &mut app);
+
+        assert_eq!(app.state.mode, Mode::Normal);
+        assert!(app.state.show_scratch);
+        assert_eq!(app.state.scratch, "a\nc");
+        assert_eq!(app.state.store.load_scratch().unwrap(), "a\nc");
+
+        // A new session over the same storage starts from the saved text
+        let mut state = AppState::new(app.state.store.clone());
+        state.scratch = state.store.load_scratch().unwrap();
+        let mut reloaded = App::new(state);
+        handler.handle_event(create_key_event(KeyCode::Char('I')), &mut reloaded);
+        assert_eq!(reloaded.state.input_buffer, "a\nc");
+
+        handler.handle_event(create_key_event(KeyCode::Esc), &mut reloaded);
+        handler.handle_event(create_key_event(KeyCode::Char('P')), &mut reloaded);
+        assert!(!reloaded.state.show_scratch);
+    }
+
+    #[test]
+    fn test_handle_insert_mode_enter_save_ignores_later_fields() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        assert_eq!(app.state.config.editing.enter_behavior, EnterBehavior::Save);
+        let task = create_test_task("test", "Original Title");
+        app.state.tasks = vec![task.clone()];
+
+        app.state.mode = Mode::Insert;
+        app.state.input_buffer = "Updated Title".to_string();
+        app.state.editing_task = Some(task);
+        app.state.editing_field = 0;
+
+        handler.handle_event(create_key_event(KeyCode::Enter), &mut app);
+        assert_eq!(app.state.mode, Mode::Normal);
+        assert_eq!(app.state.tasks[0].title, "Updated Title");
+    }
+
     #[test]
     fn test_handle_insert_mode_enter_without_editing_task() {
         let handler = EventHandler::new();
@@ -356,6 +951,202 @@ mod tests {
         assert!(app.state.input_buffer.is_empty());
     }
 
+    #[test]
+    fn test_handle_insert_mode_select_on_edit_replaces_text() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        app.state.tasks = vec![create_test_task("1", "Old title")];
+        app.state.config.behavior.select_on_edit = true;
+        app.cursor_first_task();
+
+        handler.handle_event(create_key_event(KeyCode::Char('i')), &mut app);
+        assert_eq!(app.state.input_buffer, "Old title");
+        assert!(app.state.select_pending);
+
+        handler.handle_event(create_key_event(KeyCode::Char('N')), &mut app);
+        handler.handle_event(create_key_event(KeyCode::Char('e')), &mut app);
+        handler.handle_event(create_key_event(KeyCode::Char('w')), &mut app);
+        assert_eq!(app.state.input_buffer, "New");
+        assert!(!app.state.select_pending);
+    }
+
+    #[test]
+    fn test_handle_insert_mode_backspace_cancels_selection() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        app.state.tasks = vec![create_test_task("1", "Old title")];
+        app.state.config.behavior.select_on_edit = true;
+        app.cursor_first_task();
+
+        handler.handle_event(create_key_event(KeyCode::Char('i')), &mut app);
+        handler.handle_event(create_key_event(KeyCode::Backspace), &mut app);
+        assert_eq!(app.state.input_buffer, "Old title");
+        assert!(!app.state.select_pending);
+
+        handler.handle_event(create_key_event(KeyCode::Char('!')), &mut app);
+        assert_eq!(app.state.input_buffer, "Old title!");
+    }
+
+    #[test]
+    fn test_handle_insert_mode_arrow_cancels_selection() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        app.state.tasks = vec![create_test_task("1", "Old title")];
+        app.state.config.behavior.select_on_edit = true;
+        app.cursor_first_task();
+
+        handler.handle_event(create_key_event(KeyCode::Char('i')), &mut app);
+        handler.handle_event(create_key_event(KeyCode::Right), &mut app);
+        handler.handle_event(create_key_event(KeyCode::Char('s')), &mut app);
+        assert_eq!(app.state.input_buffer, "Old titles");
+    }
+
+    #[test]
+    fn test_handle_insert_mode_without_select_on_edit_appends() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        app.state.tasks = vec![create_test_task("1", "Old")];
+        app.cursor_first_task();
+
+        handler.handle_event(create_key_event(KeyCode::Char('i')), &mut app);
+        assert!(!app.state.select_pending);
+        handler.handle_event(create_key_event(KeyCode::Char('!')), &mut app);
+        assert_eq!(app.state.input_buffer, "Old!");
+    }
+
+    #[test]
+    fn test_handle_insert_mode_types_at_the_caret() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        app.state.tasks = vec![create_test_task("1", "Buy milk")];
+        app.cursor_first_task();
+        let press = |app: &mut App<MemoryStorage>, keys: &[KeyCode]| {
+            for &key in keys {
+                handler.handle_event(create_key_event(key), app);
+            }
+        };
+
+        handler.handle_event(create_key_event(KeyCode::Char('i')), &mut app);
+        press(&mut app, &[KeyCode::Left; 4]);
+        press(
+            &mut app,
+            &"oat ".chars().map(KeyCode::Char).collect::<Vec<_>>(),
+        );
+        assert_eq!(app.state.input_buffer, "Buy oat milk");
+
+        // Backspace deletes before the caret, which stays put
+        press(&mut app, &[KeyCode::Backspace, KeyCode::Char('!')]);
+        assert_eq!(app.state.input_buffer, "Buy oat!milk");
+
+        // Home and End jump to the ends; moving past them stops there
+        press(
+            &mut app,
+            &[KeyCode::Home, KeyCode::Left, KeyCode::Char('>')],
+        );
+        assert_eq!(app.state.input_buffer, ">Buy oat!milk");
+        press(
+            &mut app,
+            &[KeyCode::End, KeyCode::Right, KeyCode::Char('.')],
+        );
+        assert_eq!(app.state.input_buffer, ">Buy oat!milk.");
+        press(&mut app, &[KeyCode::Home, KeyCode::Backspace]);
+        assert_eq!(app.state.input_buffer, ">Buy oat!milk.");
+
+        handler.handle_event(create_key_event(KeyCode::Enter), &mut app);
+        assert_eq!(app.state.tasks[0].title, ">Buy oat!milk.");
+        // The next edit starts with the caret at the end again
+        handler.handle_event(create_key_event(KeyCode::Char('i')), &mut app);
+        press(&mut app, &[KeyCode::Char('?')]);
+        assert_eq!(app.state.input_buffer, ">Buy oat!milk.?");
+    }
+
+    fn ctrl(c: char) -> Event {
+        Event::Key(KeyEvent {
+            code: KeyCode::Char(c),
+            modifiers: KeyModifiers::CONTROL,
+            kind: KeyEventKind::Press,
+            state: ratatui::crossterm::event::KeyEventState::NONE,
+        })
+    }
+
+    #[test]
+    fn test_handle_insert_mode_ctrl_w_deletes_words_before_the_caret() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        app.state.tasks = vec![create_test_task("1", "Buy oat milk  ")];
+        app.cursor_first_task();
+        handler.handle_event(create_key_event(KeyCode::Char('i')), &mut app);
+
+        // Trailing whitespace goes with the word before it
+        handler.handle_event(ctrl('w'), &mut app);
+        assert_eq!(app.state.input_buffer, "Buy oat ");
+        handler.handle_event(ctrl('w'), &mut app);
+        assert_eq!(app.state.input_buffer, "Buy ");
+
+        app.state.input_buffer = "Buy oat milk".to_string();
+        for _ in 0.."milk".len() {
+            handler.handle_event(create_key_event(KeyCode::Left), &mut app);
+        }
+        handler.handle_event(ctrl('w'), &mut app);
+        assert_eq!(app.state.input_buffer, "Buy milk");
+        handler.handle_event(ctrl('w'), &mut app);
+        handler.handle_event(ctrl('w'), &mut app);
+        assert_eq!(app.state.input_buffer, "milk");
+        assert_eq!(app.state.mode, Mode::Insert);
+    }
+
+    #[test]
+    fn test_handle_insert_mode_ctrl_u_clears_to_line_start() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        app.state.tasks = vec![create_test_task("1", "Call the bank")];
+        app.cursor_first_task();
+        handler.handle_event(create_key_event(KeyCode::Char('i')), &mut app);
+
+        for _ in 0.." bank".len() {
+            handler.handle_event(create_key_event(KeyCode::Left), &mut app);
+        }
+        handler.handle_event(ctrl('u'), &mut app);
+        assert_eq!(app.state.input_buffer, " bank");
+        handler.handle_event(create_key_event(KeyCode::Char('A')), &mut app);
+        assert_eq!(app.state.input_buffer, "A bank");
+
+        // Only the caret's line goes
+        app.state.input_buffer = "first\nsecond line".to_string();
+        handler.handle_event(create_key_event(KeyCode::End), &mut app);
+        handler.handle_event(ctrl('u'), &mut app);
+        assert_eq!(app.state.input_buffer, "first\n");
+    }
+
+    #[test]
+    fn test_paste_inserts_at_the_caret() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        app.state.tasks = vec![create_test_task("1", "Buy milk")];
+        app.cursor_first_task();
+        handler.handle_event(create_key_event(KeyCode::Char('i')), &mut app);
+        for _ in 0.."milk".len() {
+            handler.handle_event(create_key_event(KeyCode::Left), &mut app);
+        }
+
+        // A title is one line, so line breaks become spaces
+        handler.handle_event(Event::Paste("oat\r\nand soy\n".to_string()), &mut app);
+        assert_eq!(app.state.input_buffer, "Buy oat and soymilk");
+        handler.handle_event(create_key_event(KeyCode::Char(' ')), &mut app);
+        assert_eq!(app.state.input_buffer, "Buy oat and soy milk");
+
+        // The scratchpad keeps them
+        app.start_scratch_edit();
+        handler.handle_event(Event::Paste("one\r\ntwo".to_string()), &mut app);
+        assert_eq!(app.state.input_buffer, "one\ntwo");
+
+        // Nothing to paste into in Normal mode
+        app.state.mode = Mode::Normal;
+        app.clear_input_buffer();
+        handler.handle_event(Event::Paste("ignored".to_string()), &mut app);
+        assert_eq!(app.state.input_buffer, "");
+    }
+
     #[test]
     fn test_handle_insert_mode_tab() {
         let handler = EventHandler::new();
@@ -379,7 +1170,7 @@ mod tests {
 
         app.state.mode = Mode::Insert;
         app.state.editing_task = Some(create_test_task("test", "Test"));
-        app.state.editing_field = 3; // last field
+        app.state.editing_field = 5; // last field
         app.state.input_buffer = "test input".to_string();
 
         let event = create_key_event(KeyCode::Tab);
@@ -417,7 +1208,7 @@ mod tests {
         let event = create_key_event(KeyCode::BackTab);
         handler.handle_event(event, &mut app);
 
-        assert_eq!(app.state.editing_field, 3); // wraps to last field
+        assert_eq!(app.state.editing_field, 5); // wraps to last field
     }
 
     #[test]
@@ -466,6 +1257,103 @@ mod tests {
         // Test passes if no panic occurs
     }
 
+    #[test]
+    fn test_handle_enter_opens_detail_by_default() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        app.state.tasks = vec![create_test_task("1", "Task 1")];
+        app.cursor_first_task();
+
+        handler.handle_event(create_key_event(KeyCode::Enter), &mut app);
+        assert!(app.state.show_detail);
+        assert_eq!(app.state.mode, Mode::Normal);
+
+        handler.handle_event(create_key_event(KeyCode::Esc), &mut app);
+        assert!(!app.state.show_detail);
+    }
+
+    #[test]
+    fn test_handle_about_toggle() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+
+        handler.handle_event(create_key_event(KeyCode::Char('?')), &mut app);
+        assert!(app.state.show_about);
+        handler.handle_event(create_key_event(KeyCode::Esc), &mut app);
+        assert!(!app.state.show_about);
+    }
+
+    #[test]
+    fn test_handle_cell_popup_keys() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        app.state.tasks = vec![create_test_task("1", "Task 1")];
+        app.cursor_first_task();
+
+        handler.handle_event(create_key_event(KeyCode::Char('K')), &mut app);
+        assert_eq!(app.state.cell_popup, Some(0));
+        handler.handle_event(create_key_event(KeyCode::Char('K')), &mut app);
+        assert_eq!(app.state.cell_popup, Some(1));
+        handler.handle_event(create_key_event(KeyCode::Esc), &mut app);
+        assert_eq!(app.state.cell_popup, None);
+    }
+
+    #[test]
+    fn test_handle_enter_uses_configured_action() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        app.state.tasks = vec![create_test_task("1", "Task 1")];
+        app.state
+            .config
+            .keymap
+            .normal
+            .insert("Enter".to_string(), "complete_task".to_string());
+        app.cursor_first_task();
+
+        handler.handle_event(create_key_event(KeyCode::Enter), &mut app);
+        assert!(app.state.tasks[0].completed);
+        assert!(!app.state.show_detail);
+    }
+
+    #[test]
+    fn test_handle_complete_with_after_complete_next() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        let mut done = create_test_task("2", "Already done");
+        done.completed = true;
+        app.state.tasks = vec![
+            create_test_task("1", "Task 1"),
+            done,
+            create_test_task("3", "Task 3"),
+        ];
+        app.state.config.behavior.after_complete = crate::config::AfterComplete::Next;
+        app.cursor_first_task();
+
+        handler.handle_event(create_key_event(KeyCode::Char('!')), &mut app);
+        assert!(app.state.tasks[0].completed);
+        assert_eq!(app.cursor_task_index(), Some(2));
+
+        // Nothing incomplete below: the cursor stays put
+        handler.handle_event(create_key_event(KeyCode::Char('!')), &mut app);
+        assert!(app.state.tasks[2].completed);
+        assert_eq!(app.cursor_task_index(), Some(2));
+    }
+
+    #[test]
+    fn test_handle_uncomplete_does_not_move_cursor() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        let mut done = create_test_task("1", "Done");
+        done.completed = true;
+        app.state.tasks = vec![done, create_test_task("2", "Task 2")];
+        app.state.config.behavior.after_complete = crate::config::AfterComplete::Next;
+        app.cursor_first_task();
+
+        handler.handle_event(create_key_event(KeyCode::Char('!')), &mut app);
+        assert!(!app.state.tasks[0].completed);
+        assert_eq!(app.cursor_task_index(), Some(0));
+    }
+
     #[test]
     fn test_handle_task_selection_toggle() {
         let handler = EventHandler::new();
@@ -490,6 +1378,444 @@ mod tests {
         // Test passes if no panic occurs
     }
 
+    #[test]
+    fn test_handle_u_undoes_completion() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        app.state.tasks = vec![create_test_task("1", "Task 1")];
+        app.cursor_first_task();
+
+        handler.handle_event(create_key_event(KeyCode::Char('!')), &mut app);
+        assert!(app.state.tasks[0].completed);
+        handler.handle_event(create_key_event(KeyCode::Char('u')), &mut app);
+        assert!(!app.state.tasks[0].completed);
+    }
+
+    #[test]
+    fn test_handle_ctrl_r_redoes_undone_change() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        app.state.tasks = vec![create_test_task("1", "Task 1")];
+        app.cursor_first_task();
+
+        handler.handle_event(create_key_event(KeyCode::Char('!')), &mut app);
+        handler.handle_event(create_key_event(KeyCode::Char('u')), &mut app);
+        assert!(!app.state.tasks[0].completed);
+
+        let ctrl_r = Event::Key(KeyEvent {
+            code: KeyCode::Char('r'),
+            modifiers: KeyModifiers::CONTROL,
+            kind: KeyEventKind::Press,
+            state: ratatui::crossterm::event::KeyEventState::NONE,
+        });
+        handler.handle_event(ctrl_r.clone(), &mut app);
+        assert!(app.state.tasks[0].completed);
+        // The redo is used up
+        handler.handle_event(ctrl_r, &mut app);
+        assert_eq!(app.message.as_deref(), Some("Nothing to redo"));
+    }
+
+    #[test]
+    fn test_handle_alt_digits_set_urgency_filter() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        let mut overdue = create_test_task("1", "Overdue");
+        overdue.due = Some(SystemTime::now() - Duration::from_secs(60 * 60));
+        app.state.tasks = vec![overdue, create_test_task("2", "No date")];
+        let alt = |c| {
+            Event::Key(KeyEvent {
+                code: KeyCode::Char(c),
+                modifiers: KeyModifiers::ALT,
+                kind: KeyEventKind::Press,
+                state: ratatui::crossterm::event::KeyEventState::NONE,
+            })
+        };
+
+        for (key, urgency) in [
+            ('1', Urgency::Overdue),
+            ('2', Urgency::Today),
+            ('3', Urgency::Week),
+        ] {
+            handler.handle_event(alt(key), &mut app);
+            assert_eq!(app.state.urgency, Some(urgency));
+            assert_eq!(app.visible_task_indices(), vec![0]);
+        }
+
+        handler.handle_event(create_key_event(KeyCode::Char('\\')), &mut app);
+        assert_eq!(app.state.urgency, None);
+        assert_eq!(app.visible_task_indices(), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_handle_delete_asks_for_confirmation() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        app.state.tasks = vec![create_test_task("1", "Task 1")];
+        app.cursor_first_task();
+
+        handler.handle_event(create_key_event(KeyCode::Char('D')), &mut app);
+        assert_eq!(app.state.tasks.len(), 1);
+        assert!(app.pending_confirm.is_some());
+
+        handler.handle_event(create_key_event(KeyCode::Char('y')), &mut app);
+        assert!(app.state.tasks.is_empty());
+        assert!(app.pending_confirm.is_none());
+    }
+
+    #[test]
+    fn test_handle_unrelated_key_cancels_confirmation() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        app.state.tasks = vec![create_test_task("1", "Task 1")];
+        app.cursor_first_task();
+
+        for key in [KeyCode::Char('q'), KeyCode::Char('n'), KeyCode::Esc] {
+            handler.handle_event(create_key_event(KeyCode::Char('D')), &mut app);
+            handler.handle_event(create_key_event(key), &mut app);
+            assert!(app.pending_confirm.is_none());
+            assert!(app.message.is_none());
+            assert_eq!(app.state.tasks.len(), 1);
+        }
+        // The cancelling key is swallowed rather than acted on
+        assert!(!app.state.should_quit);
+    }
+
+    #[test]
+    fn test_handle_motion_during_confirmation_keeps_target() {
+        for other_keys in [ConfirmOtherKeys::Cancel, ConfirmOtherKeys::Ignore] {
+            let handler = EventHandler::new();
+            let mut app = create_test_app();
+            app.state.config.behavior.confirm_other_keys = other_keys;
+            app.state.tasks = vec![
+                create_test_task("1", "Task 1"),
+                create_test_task("2", "Task 2"),
+            ];
+            app.cursor_first_task();
+
+            handler.handle_event(create_key_event(KeyCode::Char('D')), &mut app);
+            handler.handle_event(create_key_event(KeyCode::Char('j')), &mut app);
+            assert_eq!(app.cursor_task_index(), Some(0), "{other_keys:?}");
+            assert_eq!(
+                app.pending_confirm.is_some(),
+                other_keys == ConfirmOtherKeys::Ignore
+            );
+
+            // Modified keys can't change the list under the prompt either
+            let alt_3 = Event::Key(KeyEvent {
+                code: KeyCode::Char('3'),
+                modifiers: KeyModifiers::ALT,
+                kind: KeyEventKind::Press,
+                state: ratatui::crossterm::event::KeyEventState::NONE,
+            });
+            if other_keys == ConfirmOtherKeys::Ignore {
+                handler.handle_event(alt_3, &mut app);
+                assert_eq!(app.state.urgency, None);
+                assert!(app.pending_confirm.is_some());
+            }
+
+            // With ignore, y still deletes the task the prompt was about
+            handler.handle_event(create_key_event(KeyCode::Char('y')), &mut app);
+            if other_keys == ConfirmOtherKeys::Ignore {
+                assert_eq!(app.state.tasks.len(), 1);
+                assert_eq!(app.state.tasks[0].id, "2");
+            } else {
+                assert_eq!(app.state.tasks.len(), 2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_handle_command_mode_switches_view() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+
+        handler.handle_event(create_key_event(KeyCode::Char(':')), &mut app);
+        assert_eq!(app.state.mode, Mode::Command);
+
+        for c in "someday".chars() {
+            handler.handle_event(create_key_event(KeyCode::Char(c)), &mut app);
+        }
+        handler.handle_event(create_key_event(KeyCode::Enter), &mut app);
+
+        assert_eq!(app.state.mode, Mode::Normal);
+        assert_eq!(app.state.view, crate::types::View::Someday);
+        assert!(app.state.input_buffer.is_empty());
+    }
+
+    #[test]
+    fn test_handle_command_mode_unknown_command_sets_message() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+
+        handler.handle_event(create_key_event(KeyCode::Char(':')), &mut app);
+        handler.handle_event(create_key_event(KeyCode::Char('z')), &mut app);
+        handler.handle_event(create_key_event(KeyCode::Enter), &mut app);
+
+        assert_eq!(app.state.mode, Mode::Normal);
+        assert!(app.get_error_message().is_some());
+    }
+
+    #[test]
+    fn test_handle_jump_to_urgent_tasks() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        let mut overdue = create_test_task("2", "Overdue");
+        overdue.due = Some(SystemTime::now() - std::time::Duration::from_secs(60));
+        app.state.tasks = vec![
+            create_test_task("1", "Task 1"),
+            overdue,
+            create_test_task("3", "Task 3"),
+        ];
+        app.cursor_first_task();
+
+        handler.handle_event(create_key_event(KeyCode::Char(']')), &mut app);
+        handler.handle_event(create_key_event(KeyCode::Char('d')), &mut app);
+        assert_eq!(app.task_list_state.selected(), Some(1));
+
+        app.cursor_last_task();
+        handler.handle_event(create_key_event(KeyCode::Char('[')), &mut app);
+        handler.handle_event(create_key_event(KeyCode::Char('d')), &mut app);
+        assert_eq!(app.task_list_state.selected(), Some(1));
+    }
+
+    #[test]
+    fn test_handle_copy_dates_sequences() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        let defer = SystemTime::UNIX_EPOCH + Duration::from_secs(86_400);
+        let mut task = create_test_task("1", "Task 1");
+        task.defer_until = Some(defer);
+        app.state.tasks.push(task);
+        app.cursor_first_task();
+
+        handler.handle_event(create_key_event(KeyCode::Char('=')), &mut app);
+        handler.handle_event(create_key_event(KeyCode::Char('d')), &mut app);
+        assert_eq!(app.state.tasks[0].due, Some(defer));
+
+        app.state.tasks[0].defer_until = None;
+        handler.handle_event(create_key_event(KeyCode::Char('=')), &mut app);
+        handler.handle_event(create_key_event(KeyCode::Char('f')), &mut app);
+        assert_eq!(app.state.tasks[0].defer_until, Some(defer));
+    }
+
+    #[test]
+    fn test_handle_gg_with_vi_keymap() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        app.state.config.keymap = Config::default()
+            .keymaps
+            .into_iter()
+            .find(|keymap| keymap.name == "vi")
+            .unwrap();
+        app.state.tasks = vec![
+            create_test_task("1", "Task 1"),
+            create_test_task("2", "Task 2"),
+        ];
+        app.task_list_state.select(Some(1));
+
+        handler.handle_event(create_key_event(KeyCode::Char('g')), &mut app);
+        assert_eq!(app.task_list_state.selected(), Some(1));
+        assert_eq!(app.pending_key, Some('g'));
+        handler.handle_event(create_key_event(KeyCode::Char('g')), &mut app);
+        assert_eq!(app.task_list_state.selected(), Some(0));
+
+        // `g` then a key with no `g` sequence does nothing at all
+        handler.handle_event(create_key_event(KeyCode::Char('g')), &mut app);
+        handler.handle_event(create_key_event(KeyCode::Char('x')), &mut app);
+        assert!(app.get_task_selection().is_empty());
+        assert_eq!(app.task_list_state.selected(), Some(0));
+        assert!(app.pending_key.is_none());
+    }
+
+    #[test]
+    fn test_handle_ctrl_bracket_escapes_with_vi_keymap() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        app.state.config.keymap = Config::default()
+            .keymaps
+            .into_iter()
+            .find(|keymap| keymap.name == "vi")
+            .unwrap();
+        app.state.mode = Mode::Insert;
+        app.state.input_buffer = "test input".to_string();
+        app.state.editing_task = Some(create_test_task("test", "Test"));
+
+        handler.handle_event(ctrl('['), &mut app);
+
+        assert_eq!(app.state.mode, Mode::Normal);
+        assert!(app.state.input_buffer.is_empty());
+        assert!(app.state.editing_task.is_none());
+    }
+
+    #[test]
+    fn test_handle_chords_bound_in_custom_keymap() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        app.state
+            .config
+            .keymap
+            .normal
+            .insert("Ctrl+q".to_string(), "quit".to_string());
+
+        // A bound chord runs its action
+        handler.handle_event(ctrl('q'), &mut app);
+        assert!(app.state.should_quit);
+
+        // Unbound chords keep their built-in meaning
+        app.state.mode = Mode::Insert;
+        app.state.input_buffer = "one two".to_string();
+        handler.handle_event(ctrl('w'), &mut app);
+        assert_eq!(app.state.input_buffer, "one ");
+    }
+
+    #[test]
+    fn test_key_name_prefixes_modifiers() {
+        assert_eq!(
+            key_name(KeyCode::Char('j'), KeyModifiers::NONE).as_deref(),
+            Some("j")
+        );
+        assert_eq!(
+            key_name(KeyCode::Char('G'), KeyModifiers::SHIFT).as_deref(),
+            Some("G")
+        );
+        assert_eq!(
+            key_name(KeyCode::Char('['), KeyModifiers::CONTROL).as_deref(),
+            Some("Ctrl+[")
+        );
+        assert_eq!(
+            key_name(KeyCode::Enter, KeyModifiers::ALT).as_deref(),
+            Some("Alt+Enter")
+        );
+        assert_eq!(
+            key_name(
+                KeyCode::Char('x'),
+                KeyModifiers::CONTROL | KeyModifiers::ALT
+            )
+            .as_deref(),
+            Some("Ctrl+Alt+x")
+        );
+        assert_eq!(key_name(KeyCode::F(1), KeyModifiers::CONTROL), None);
+    }
+
+    #[test]
+    fn test_leader_sequences_run_bound_actions() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        app.state.tasks.push(create_test_task("1", "Task 1"));
+        let sort_key = app.sort_key;
+
+        handler.handle_event(create_key_event(KeyCode::Char(' ')), &mut app);
+        assert!(app.leader_pending);
+        handler.handle_event(create_key_event(KeyCode::Char('s')), &mut app);
+        assert!(!app.leader_pending);
+        assert_ne!(app.sort_key, sort_key);
+
+        // Esc and unbound keys close the popup without doing anything else
+        for code in [KeyCode::Esc, KeyCode::Char('q')] {
+            handler.handle_event(create_key_event(KeyCode::Char(' ')), &mut app);
+            handler.handle_event(create_key_event(code), &mut app);
+            assert!(!app.leader_pending);
+            assert!(!app.state.should_quit);
+        }
+
+        // A custom leader replaces the default one
+        app.state.config.keymap.leader = ",".to_string();
+        handler.handle_event(create_key_event(KeyCode::Char(' ')), &mut app);
+        assert!(!app.leader_pending);
+        handler.handle_event(create_key_event(KeyCode::Char(',')), &mut app);
+        handler.handle_event(create_key_event(KeyCode::Char('p')), &mut app);
+        assert!(app.state.show_scratch);
+    }
+
+    #[test]
+    fn test_handle_remapped_keys() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        app.state.tasks = vec![
+            create_test_task("1", "Task 1"),
+            create_test_task("2", "Task 2"),
+        ];
+        app.cursor_first_task();
+        let normal = &mut app.state.config.keymap.normal;
+        normal.insert("t".to_string(), "move_down".to_string());
+        normal.insert("q".to_string(), "toggle_mark".to_string());
+        normal.insert("Q".to_string(), "quit".to_string());
+
+        handler.handle_event(create_key_event(KeyCode::Char('t')), &mut app);
+        assert_eq!(app.task_list_state.selected(), Some(1));
+        handler.handle_event(create_key_event(KeyCode::Char('q')), &mut app);
+        assert!(!app.state.should_quit);
+        assert_eq!(app.get_task_selection().len(), 1);
+        handler.handle_event(create_key_event(KeyCode::Char('Q')), &mut app);
+        assert!(app.state.should_quit);
+    }
+
+    #[test]
+    fn test_handle_vi_keymap_bindings() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        app.state.config.keymap = Config::default()
+            .keymaps
+            .into_iter()
+            .find(|keymap| keymap.name == "vi")
+            .unwrap();
+        app.state.tasks = vec![create_test_task("1", "Task 1")];
+        app.cursor_first_task();
+
+        handler.handle_event(create_key_event(KeyCode::Char('x')), &mut app);
+        assert!(app.state.tasks[0].completed);
+        assert!(app.get_task_selection().is_empty());
+
+        handler.handle_event(create_key_event(KeyCode::Char('d')), &mut app);
+        assert!(app.pending_confirm.is_none());
+        handler.handle_event(create_key_event(KeyCode::Char('d')), &mut app);
+        assert!(app.pending_confirm.is_some());
+    }
+
+    #[test]
+    fn test_handle_unfinished_sequence_is_noop() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        app.state.tasks.push(create_test_task("1", "Task 1"));
+
+        handler.handle_event(create_key_event(KeyCode::Char(']')), &mut app);
+        handler.handle_event(create_key_event(KeyCode::Char('q')), &mut app);
+
+        assert!(!app.state.should_quit);
+        assert!(app.pending_key.is_none());
+    }
+
+    #[test]
+    fn test_handle_someday_toggle() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        app.state.tasks.push(create_test_task("1", "Task 1"));
+        app.cursor_first_task();
+
+        handler.handle_event(create_key_event(KeyCode::Char('s')), &mut app);
+
+        assert!(app.state.tasks[0].someday);
+    }
+
+    #[test]
+    fn test_handle_c_cycles_task_status() {
+        let handler = EventHandler::new();
+        let mut app = create_test_app();
+        app.state.tasks = vec![create_test_task("1", "Write report")];
+        app.cursor_first_task();
+
+        handler.handle_event(create_key_event(KeyCode::Char('c')), &mut app);
+        assert_eq!(app.state.tasks[0].status(), TaskStatus::InProgress);
+        handler.handle_event(create_key_event(KeyCode::Char('c')), &mut app);
+        assert_eq!(app.state.tasks[0].status(), TaskStatus::Done);
+        handler.handle_event(create_key_event(KeyCode::Char('c')), &mut app);
+        assert_eq!(app.state.tasks[0].status(), TaskStatus::Todo);
+
+        app.state.config.ui.status_cycle = vec![TaskStatus::Todo, TaskStatus::Done];
+        handler.handle_event(create_key_event(KeyCode::Char('c')), &mut app);
+        assert_eq!(app.state.tasks[0].status(), TaskStatus::Done);
+    }
+
     #[test]
     fn test_handle_unknown_normal_key() {
         let handler = EventHandler::new();
@@ -38,7 +38,7 @@ impl HelpPanel {
         f.render_widget(help_paragraph, area);
     }
 
-    fn create_help_content(&self) -> Vec<Line> {
+    fn create_help_content(&self) -> Vec<Line<'_>> {
         vec![
             Line::from(""),
             Line::from(vec![Span::styled(
@@ -50,13 +50,45 @@ impl HelpPanel {
             Line::from(""),
             Line::from("  j/k     - Move up/down"),
             Line::from("  g/G     - Go to first/last"),
+            Line::from("  ]d/[d   - Next/previous overdue or due today"),
+            Line::from("  /       - Search titles and descriptions"),
+            Line::from("  n/N     - Next/previous search match"),
+            Line::from("  =d/=f   - Due from defer / defer from due"),
+            Line::from("  Alt+1/2/3 - Overdue / due today / this week"),
             Line::from("  !       - Toggle completion"),
+            Line::from("  c       - Cycle status (todo/doing/done)"),
+            Line::from("  p       - Cycle priority (low/med/high)"),
+            Line::from("  T       - Start/stop a timer on the task"),
+            Line::from("  t       - Filter by the task's tag (again: all)"),
+            Line::from("  >       - Make subtask of the task above"),
             Line::from("  x       - Toggle selection"),
-            Line::from("  D       - Delete task"),
+            Line::from("  s       - Toggle someday/maybe"),
+            Line::from("  D       - Delete task (y to confirm)"),
+            Line::from("  u       - Undo last change"),
+            Line::from("  Ctrl+R  - Redo"),
             Line::from("  o       - Open new task below"),
             Line::from("  O       - Open new task above"),
             Line::from("  i       - Edit current task"),
+            Line::from("  Enter   - Task details (configurable)"),
+            Line::from("  K       - Full title/description popup"),
+            Line::from("  :       - Command (:all, :today, :someday,"),
+            Line::from("            :unscheduled, :deferred, :review,"),
+            Line::from("            :done, :filter due:none,"),
+            Line::from("            :tag +add -remove, :energy low,"),
+            Line::from("            :setenergy high, :group,"),
+            Line::from("            :setproject x, :project x,"),
+            Line::from("            :wait, :after, :repeat weekly,"),
+            Line::from("            :skip, :remind 1h, :duefromdefer,"),
+            Line::from("            :deferfromdue -2, :shift-due +7d,"),
+            Line::from("            :shift-defer -1w;"),
+            Line::from("            Tab completes)"),
+            Line::from("  \\       - Clear view and all filters"),
             Line::from("  h       - Toggle help"),
+            Line::from("  ?       - About (version, file paths)"),
+            Line::from("  F       - Toggle focus mode"),
+            Line::from("  P / I   - Show scratchpad / write in it"),
+            Line::from("  Space   - Leader: shows keys that follow"),
+            Line::from("  S / r   - Cycle sort key / reverse sort"),
             Line::from("  q       - Quit"),
             Line::from(""),
             Line::from(vec![Span::styled(
@@ -67,10 +99,14 @@ impl HelpPanel {
             )]),
             Line::from(""),
             Line::from("  Type    - Edit current field in-place"),
-            Line::from("  Tab     - Next field (Title → Description → Due → Defer)"),
+            Line::from("  Tab     - Next field (Title → Desc → Due → Defer → Notes)"),
             Line::from("  S+Tab   - Previous field"),
             Line::from("  Enter   - Save task & return to Normal"),
-            Line::from("  Backsp  - Delete character"),
+            Line::from("  C-s     - Save task from any field"),
+            Line::from("  Backsp  - Delete character before the caret"),
+            Line::from("  ←/→     - Move the caret (Home/End: start/end)"),
+            Line::from("  C-w/C-u - Delete word / line before the caret"),
+            Line::from("  C-v     - Paste from the clipboard"),
             Line::from("  Esc     - Cancel & return to Normal"),
             Line::from(""),
             Line::from("Fields are highlighted in yellow when editing."),
@@ -85,10 +121,12 @@ impl HelpPanel {
             Line::from("  Relative: 2d, 1w, 3h, 30m"),
             Line::from("  Keywords: today, tomorrow, yesterday"),
             Line::from("  Weekdays: friday, next monday"),
+            Line::from("  Times:    noon, eod, evening, friday noon"),
             Line::from("  Absolute: 2024-12-25, 12-25"),
             Line::from("  (empty)  - Clear date"),
             Line::from(""),
-            Line::from("  Due dates default to 5pm, defer dates to 8am"),
+            Line::from("  Due dates default to 5pm, defer dates to 9am"),
+            Line::from("  ([time] due_hour and defer_hour)"),
             Line::from(""),
             Line::from(vec![Span::styled(
                 "🎨 Visual Highlights",
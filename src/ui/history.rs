@@ -0,0 +1,210 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::types::Task;
+
+/// One change to the task list, kept as the affected tasks before and after
+///
+/// Only tasks that were added, removed or edited are stored, along with the
+/// order of task IDs on either side so reverting also restores positions.
+#[derive(Debug, Clone)]
+pub struct Change {
+    /// Old versions of the tasks that were edited or removed
+    before: Vec<Task>,
+    /// New versions of the tasks that were edited or added
+    after: Vec<Task>,
+    order_before: Vec<String>,
+    order_after: Vec<String>,
+}
+
+impl Change {
+    /// The difference between two task lists, or `None` if they are the same
+    pub fn between(old: &[Task], new: &[Task]) -> Option<Self> {
+        let (old_by_id, new_by_id) = (by_id(old), by_id(new));
+        let before: Vec<Task> = old
+            .iter()
+            .filter(|task| new_by_id.get(task.id.as_str()) != Some(task))
+            .cloned()
+            .collect();
+        let after: Vec<Task> = new
+            .iter()
+            .filter(|task| old_by_id.get(task.id.as_str()) != Some(task))
+            .cloned()
+            .collect();
+        let order_before: Vec<String> = old.iter().map(|t| t.id.clone()).collect();
+        let order_after: Vec<String> = new.iter().map(|t| t.id.clone()).collect();
+
+        if before.is_empty() && after.is_empty() && order_before == order_after {
+            return None;
+        }
+        Some(Self {
+            before,
+            after,
+            order_before,
+            order_after,
+        })
+    }
+
+    /// Turn a list in the "after" state back into the "before" state
+    pub fn revert(&self, tasks: &mut Vec<Task>) {
+        Self::apply(tasks, &self.after, &self.before, &self.order_before);
+    }
+
+    /// Turn a list in the "before" state into the "after" state again
+    pub fn reapply(&self, tasks: &mut Vec<Task>) {
+        Self::apply(tasks, &self.before, &self.after, &self.order_after);
+    }
+
+    fn apply(tasks: &mut Vec<Task>, remove: &[Task], restore: &[Task], order: &[String]) {
+        tasks.retain(|task| !remove.iter().any(|r| r.id == task.id));
+        tasks.extend(restore.iter().cloned());
+        // Tasks missing from the recorded order keep their place at the end
+        let rank: HashMap<&str, usize> = order
+            .iter()
+            .enumerate()
+            .map(|(pos, id)| (id.as_str(), pos))
+            .collect();
+        tasks.sort_by_key(|task| rank.get(task.id.as_str()).copied().unwrap_or(order.len()));
+    }
+}
+
+fn by_id(tasks: &[Task]) -> HashMap<&str, &Task> {
+    tasks.iter().map(|t| (t.id.as_str(), t)).collect()
+}
+
+/// Bounded undo and redo stacks of task list changes
+#[derive(Debug, Clone)]
+pub struct History {
+    undo: VecDeque<Change>,
+    redo: VecDeque<Change>,
+    /// Most changes kept for undo; the oldest are dropped beyond it
+    depth: usize,
+}
+
+impl History {
+    /// Empty history that keeps at most `depth` changes (0 disables undo)
+    pub fn new(depth: usize) -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: VecDeque::new(),
+            depth,
+        }
+    }
+
+    /// Remember a new change; anything that could be redone is forgotten
+    pub fn record(&mut self, change: Change) {
+        self.redo.clear();
+        self.undo.push_back(change);
+        while self.undo.len() > self.depth {
+            self.undo.pop_front();
+        }
+    }
+
+    /// The change the next undo would revert
+    pub fn next_undo(&self) -> Option<&Change> {
+        self.undo.back()
+    }
+
+    /// The change the next redo would reapply
+    pub fn next_redo(&self) -> Option<&Change> {
+        self.redo.back()
+    }
+
+    /// Move the next undo onto the redo stack, once it has been reverted
+    pub fn mark_undone(&mut self) {
+        if let Some(change) = self.undo.pop_back() {
+            self.redo.push_back(change);
+        }
+    }
+
+    /// Move the next redo back onto the undo stack, once it has been reapplied
+    pub fn mark_redone(&mut self) {
+        if let Some(change) = self.redo.pop_back() {
+            self.undo.push_back(change);
+        }
+    }
+
+    /// Number of changes that can be undone
+    pub fn undo_len(&self) -> usize {
+        self.undo.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TaskSource;
+    use std::time::SystemTime;
+
+    fn titles(tasks: &[Task]) -> Vec<&str> {
+        tasks.iter().map(|t| t.title.as_str()).collect()
+    }
+
+    fn tasks(titles: &[&str]) -> Vec<Task> {
+        titles
+            .iter()
+            .map(|title| Task::new(title, TaskSource::Tui, SystemTime::UNIX_EPOCH))
+            .collect()
+    }
+
+    #[test]
+    fn test_change_reverts_edits_additions_and_removals() {
+        let old = tasks(&["A", "B", "C"]);
+        let mut new = old.clone();
+        new[0].title = "A edited".to_string();
+        new.swap_remove(1);
+        new.push(Task::new("D", TaskSource::Tui, SystemTime::UNIX_EPOCH));
+        let change = Change::between(&old, &new).unwrap();
+
+        let mut list = new.clone();
+        change.revert(&mut list);
+        assert_eq!(list, old);
+
+        change.reapply(&mut list);
+        assert_eq!(list, new);
+        assert_eq!(titles(&list), vec!["A edited", "C", "D"]);
+    }
+
+    #[test]
+    fn test_no_change_is_not_recorded() {
+        let old = tasks(&["A", "B"]);
+        assert!(Change::between(&old, &old).is_none());
+
+        let mut reordered = old.clone();
+        reordered.swap(0, 1);
+        assert!(Change::between(&old, &reordered).is_some());
+    }
+
+    #[test]
+    fn test_history_depth_is_capped() {
+        let mut history = History::new(2);
+        let mut list = tasks(&["A"]);
+        for title in ["B", "C", "D"] {
+            let old = list.clone();
+            list[0].title = title.to_string();
+            history.record(Change::between(&old, &list).unwrap());
+        }
+        assert_eq!(history.undo_len(), 2);
+
+        for _ in 0..2 {
+            history.next_undo().unwrap().revert(&mut list);
+            history.mark_undone();
+        }
+        assert!(history.next_undo().is_none());
+        // The change to "B" fell off the bottom
+        assert_eq!(titles(&list), vec!["B"]);
+    }
+
+    #[test]
+    fn test_recording_clears_redo() {
+        let mut history = History::new(10);
+        let old = tasks(&["A"]);
+        let mut new = old.clone();
+        new[0].completed = true;
+        history.record(Change::between(&old, &new).unwrap());
+        history.mark_undone();
+        assert!(history.next_redo().is_some());
+
+        history.record(Change::between(&new, &old).unwrap());
+        assert!(history.next_redo().is_none());
+    }
+}
@@ -16,7 +16,23 @@ impl LayoutManager {
             Constraint::Length(1), // Status
         ]);
 
-        let [title_area, main_area, status_area] = main_layout.areas(area);
+        let [title_area, main_area, status_area] = if app_state.focus_mode {
+            // The list takes the whole screen; title and status get no room
+            let empty = Rect { height: 0, ..area };
+            [empty, area, empty]
+        } else {
+            main_layout.areas(area)
+        };
+
+        // The scratchpad takes a column on the right of the task list
+        let (main_area, scratch_area) = if app_state.show_scratch && !app_state.focus_mode {
+            let [list, scratch] =
+                Layout::horizontal([Constraint::Min(20), Constraint::Percentage(35)])
+                    .areas(main_area);
+            (list, Some(scratch))
+        } else {
+            (main_area, None)
+        };
 
         let help_area = if app_state.show_help {
             Some(self.calculate_floating_help(area))
@@ -24,27 +40,57 @@ impl LayoutManager {
             None
         };
 
+        let detail_area = if app_state.show_detail {
+            Some(self.calculate_floating_detail(area))
+        } else {
+            None
+        };
+
+        let about_area = if app_state.show_about {
+            Some(self.calculate_floating(area, 60, 9))
+        } else {
+            None
+        };
+
+        let cell_popup_area = if app_state.cell_popup.is_some() {
+            Some(self.calculate_floating(area, 50, 8))
+        } else {
+            None
+        };
+
         MainLayout {
             title: title_area,
             main: main_area,
             status: status_area,
             help: help_area,
+            detail: detail_area,
+            cell_popup: cell_popup_area,
+            about: about_area,
+            scratch: scratch_area,
         }
     }
 
     fn calculate_floating_help(&self, area: Rect) -> Rect {
+        self.calculate_floating(area, 50, 20)
+    }
+
+    fn calculate_floating_detail(&self, area: Rect) -> Rect {
+        self.calculate_floating(area, 60, 14)
+    }
+
+    fn calculate_floating(&self, area: Rect, width: u16, height: u16) -> Rect {
         // Create a centered floating panel
-        let help_width = 50.min(area.width.saturating_sub(4));
-        let help_height = 20.min(area.height.saturating_sub(4));
+        let width = width.min(area.width.saturating_sub(4));
+        let height = height.min(area.height.saturating_sub(4));
 
-        let x = (area.width.saturating_sub(help_width)) / 2;
-        let y = (area.height.saturating_sub(help_height)) / 2;
+        let x = (area.width.saturating_sub(width)) / 2;
+        let y = (area.height.saturating_sub(height)) / 2;
 
         Rect {
             x: area.x + x,
             y: area.y + y,
-            width: help_width,
-            height: help_height,
+            width,
+            height,
         }
     }
 }
@@ -54,6 +100,10 @@ pub struct MainLayout {
     pub main: Rect,
     pub status: Rect,
     pub help: Option<Rect>,
+    pub detail: Option<Rect>,
+    pub cell_popup: Option<Rect>,
+    pub about: Option<Rect>,
+    pub scratch: Option<Rect>,
 }
 
 impl Default for LayoutManager {
@@ -131,6 +181,39 @@ mod tests {
         assert!(help_area.height <= 20);
     }
 
+    #[test]
+    fn test_calculate_main_layout_with_detail() {
+        let manager = LayoutManager::new();
+        let mut app_state = create_test_app_state();
+        let area = Rect::new(0, 0, 80, 24);
+
+        assert!(manager
+            .calculate_main_layout(area, &app_state)
+            .detail
+            .is_none());
+
+        app_state.show_detail = true;
+        let detail_area = manager
+            .calculate_main_layout(area, &app_state)
+            .detail
+            .unwrap();
+        assert_eq!(detail_area, Rect::new(10, 5, 60, 14));
+    }
+
+    #[test]
+    fn test_calculate_main_layout_focus_mode() {
+        let manager = LayoutManager::new();
+        let mut app_state = create_test_app_state();
+        app_state.focus_mode = true;
+        let area = Rect::new(0, 0, 80, 24);
+
+        let layout = manager.calculate_main_layout(area, &app_state);
+
+        assert_eq!(layout.main, area);
+        assert_eq!(layout.title.height, 0);
+        assert_eq!(layout.status.height, 0);
+    }
+
     #[test]
     fn test_calculate_main_layout_small_area() {
         let manager = LayoutManager::new();
@@ -246,6 +329,10 @@ mod tests {
             main,
             status,
             help,
+            detail: None,
+            cell_popup: None,
+            about: None,
+            scratch: None,
         };
 
         assert_eq!(layout.title, title);
@@ -265,6 +352,10 @@ mod tests {
             main,
             status,
             help: None,
+            detail: None,
+            cell_popup: None,
+            about: None,
+            scratch: None,
         };
 
         assert_eq!(layout.title, title);
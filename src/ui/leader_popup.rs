@@ -0,0 +1,106 @@
+use ratatui::{
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+    Frame,
+};
+
+/// Which-key style popup listing the keys that can follow the leader key
+pub struct LeaderPopup;
+
+impl LeaderPopup {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Draw the popup in the bottom right corner of `area`, above the status bar
+    pub fn render(&self, f: &mut Frame, area: Rect, bindings: &[(&str, &str)]) {
+        let popup_area = self.popup_area(area, bindings);
+        // Clear the background area to create floating effect
+        f.render_widget(Clear, popup_area);
+
+        let popup_paragraph = Paragraph::new(self.create_content(bindings))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(" Leader ")
+                    .title_style(
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                    .border_style(Style::default().fg(Color::Cyan))
+                    .style(Style::default().bg(Color::DarkGray)),
+            )
+            .style(Style::default().fg(Color::White).bg(Color::DarkGray));
+
+        f.render_widget(popup_paragraph, popup_area);
+    }
+
+    /// Just big enough for one line per binding, shrunk to fit `area`
+    fn popup_area(&self, area: Rect, bindings: &[(&str, &str)]) -> Rect {
+        let widest = self
+            .create_content(bindings)
+            .iter()
+            .map(Line::width)
+            .max()
+            .unwrap_or(0);
+        let width = (widest as u16 + 2).max(12).min(area.width);
+        let height = (bindings.len() as u16 + 2).min(area.height.saturating_sub(1));
+        Rect {
+            x: area.right() - width,
+            y: area.bottom().saturating_sub(height + 1).max(area.y),
+            width,
+            height,
+        }
+    }
+
+    /// One line per binding: the key to press, then what it does
+    fn create_content(&self, bindings: &[(&str, &str)]) -> Vec<Line<'static>> {
+        bindings
+            .iter()
+            .map(|(key, action)| {
+                Line::from(vec![
+                    Span::styled(
+                        format!(" {key} "),
+                        Style::default()
+                            .fg(Color::Yellow)
+                            .add_modifier(Modifier::BOLD),
+                    ),
+                    Span::raw(format!(" {} ", action.replace('_', " "))),
+                ])
+            })
+            .collect()
+    }
+}
+
+impl Default for LeaderPopup {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_lists_keys_and_actions() {
+        let popup = LeaderPopup::new();
+        let lines = popup.create_content(&[("r", "reverse_sort"), ("s", "cycle_sort")]);
+        let text: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+        assert_eq!(text, vec![" r  reverse sort ", " s  cycle sort "]);
+    }
+
+    #[test]
+    fn test_popup_sits_above_status_bar_and_fits() {
+        let popup = LeaderPopup::new();
+        let bindings = [("s", "cycle_sort"), ("p", "toggle_scratch")];
+        let area = popup.popup_area(Rect::new(0, 0, 80, 24), &bindings);
+        assert_eq!(area, Rect::new(59, 19, 21, 4));
+
+        let tiny = popup.popup_area(Rect::new(0, 0, 10, 3), &bindings);
+        assert!(tiny.width <= 10 && tiny.bottom() <= 3);
+    }
+}
@@ -0,0 +1,262 @@
+//! Minimal markdown rendering for task descriptions and notes
+//!
+//! Understands just enough markdown to make long notes readable in the
+//! detail pane: `**bold**`, `*italics*` (or `_italics_`), `- bullet` lists
+//! and `[links](https://...)`. Anything else, including markers that are
+//! never closed, is shown as written.
+
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+
+/// Glyph replacing the `-`, `*` or `+` that starts a list item
+const BULLET: &str = "• ";
+
+/// Render `text` as styled lines wrapped to `width` characters
+///
+/// Wrapped list items are indented to line up under the item's text; words
+/// longer than a whole line are split.
+pub fn to_lines(text: &str, width: usize) -> Vec<Line<'static>> {
+    if width == 0 {
+        return Vec::new();
+    }
+    text.lines()
+        .flat_map(|line| wrap_line(line, width))
+        .collect()
+}
+
+/// A character with the style it is drawn in
+type StyledChar = (char, Style);
+
+fn wrap_line(line: &str, width: usize) -> Vec<Line<'static>> {
+    let (prefix, content) = split_bullet(line);
+    let indent = prefix.chars().count();
+    let indent = if indent < width { indent } else { 0 };
+
+    let mut lines = Vec::new();
+    let mut current: Vec<StyledChar> = prefix.chars().map(|c| (c, Style::default())).collect();
+    let mut has_words = false;
+    for (space_style, word) in words(&parse_inline(content)) {
+        if has_words && current.len() + 1 + word.len() > width {
+            lines.push(std::mem::replace(
+                &mut current,
+                vec![(' ', Style::default()); indent],
+            ));
+        } else if has_words {
+            current.push((' ', space_style));
+        }
+        current.extend(word);
+        has_words = true;
+        // Hard-split words longer than the whole line
+        while current.len() > width {
+            let rest = current.split_off(width);
+            lines.push(std::mem::replace(
+                &mut current,
+                vec![(' ', Style::default()); indent],
+            ));
+            current.extend(rest);
+        }
+    }
+    lines.push(current);
+    lines.into_iter().map(to_line).collect()
+}
+
+/// Split a list item into its bullet (keeping nesting indentation) and text
+fn split_bullet(line: &str) -> (String, &str) {
+    let trimmed = line.trim_start();
+    let nesting = line.len() - trimmed.len();
+    ["- ", "* ", "+ "]
+        .iter()
+        .find_map(|marker| trimmed.strip_prefix(marker))
+        .map(|content| (format!("{}{BULLET}", " ".repeat(nesting)), content))
+        .unwrap_or((String::new(), line))
+}
+
+/// Split styled text into words, each with the style of the space before it
+fn words(chars: &[StyledChar]) -> Vec<(Style, Vec<StyledChar>)> {
+    let mut words = Vec::new();
+    let mut word = Vec::new();
+    let mut space_style = Style::default();
+    for &(c, style) in chars {
+        if c.is_whitespace() {
+            if !word.is_empty() {
+                words.push((space_style, std::mem::take(&mut word)));
+            }
+            space_style = style;
+        } else {
+            word.push((c, style));
+        }
+    }
+    if !word.is_empty() {
+        words.push((space_style, word));
+    }
+    words
+}
+
+/// Apply bold, italic and link markup, dropping the markers
+fn parse_inline(text: &str) -> Vec<StyledChar> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = Vec::new();
+    let mut bold = false;
+    let mut italic: Option<char> = None;
+    let mut i = 0;
+    while i < chars.len() {
+        let mut style = Style::default();
+        if bold {
+            style = style.add_modifier(Modifier::BOLD);
+        }
+        if italic.is_some() {
+            style = style.add_modifier(Modifier::ITALIC);
+        }
+
+        if chars[i..].starts_with(&['*', '*']) && (bold || find(&chars, i + 2, "**").is_some()) {
+            bold = !bold;
+            i += 2;
+        } else if let Some(marker) = italic_marker(&chars, i, italic) {
+            italic = if italic.is_some() { None } else { Some(marker) };
+            i += 1;
+        } else if let Some((label, url, end)) = link(&chars, i) {
+            let link_style = style.fg(Color::Cyan).add_modifier(Modifier::UNDERLINED);
+            out.extend(label.chars().map(|c| (c, link_style)));
+            if label != url {
+                out.extend(format!(" ({url})").chars().map(|c| (c, style)));
+            }
+            i = end;
+        } else {
+            out.push((chars[i], style));
+            i += 1;
+        }
+    }
+    out
+}
+
+/// The italic marker at `i`, if it opens (and is later closed) or closes italics
+///
+/// `_` only counts at word edges, so names like `snake_case` are left alone.
+fn italic_marker(chars: &[char], i: usize, open: Option<char>) -> Option<char> {
+    let c = chars[i];
+    if c != '*' && c != '_' {
+        return None;
+    }
+    let before = i.checked_sub(1).map(|j| chars[j]);
+    let after = chars.get(i + 1).copied();
+    match open {
+        Some(marker) => {
+            let at_edge = c == '*' || !after.is_some_and(char::is_alphanumeric);
+            (c == marker && at_edge && before.is_some_and(|b| !b.is_whitespace())).then_some(c)
+        }
+        None => {
+            let at_edge = c == '*' || !before.is_some_and(char::is_alphanumeric);
+            let opens = at_edge && after.is_some_and(|a| !a.is_whitespace() && a != c);
+            (opens && find(chars, i + 1, &c.to_string()).is_some()).then_some(c)
+        }
+    }
+}
+
+/// A `[label](url)` link starting at `i`: its label, url and the index after it
+fn link(chars: &[char], i: usize) -> Option<(String, String, usize)> {
+    if chars[i] != '[' {
+        return None;
+    }
+    let label_end = find(chars, i + 1, "](")?;
+    let url_end = find(chars, label_end + 2, ")")?;
+    let label: String = chars[i + 1..label_end].iter().collect();
+    let url: String = chars[label_end + 2..url_end].iter().collect();
+    (!label.is_empty() && !url.is_empty()).then_some((label, url, url_end + 1))
+}
+
+/// Index of the next occurrence of `pattern` in `chars` at or after `from`
+fn find(chars: &[char], from: usize, pattern: &str) -> Option<usize> {
+    let pattern: Vec<char> = pattern.chars().collect();
+    (from..chars.len()).find(|&j| chars[j..].starts_with(&pattern))
+}
+
+/// Group runs of equally styled characters into spans
+fn to_line(chars: Vec<StyledChar>) -> Line<'static> {
+    let mut spans: Vec<Span<'static>> = Vec::new();
+    for (c, style) in chars {
+        match spans.last_mut() {
+            Some(span) if span.style == style => span.content.to_mut().push(c),
+            _ => spans.push(Span::styled(c.to_string(), style)),
+        }
+    }
+    Line::from(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bold() -> Style {
+        Style::default().add_modifier(Modifier::BOLD)
+    }
+
+    #[test]
+    fn test_bold_and_italic_spans() {
+        let lines = to_lines("Ship **before Friday**, *really*", 80);
+        assert_eq!(
+            lines,
+            vec![Line::from(vec![
+                Span::raw("Ship "),
+                Span::styled("before Friday", bold()),
+                Span::raw(", "),
+                Span::styled("really", Style::default().add_modifier(Modifier::ITALIC)),
+            ])]
+        );
+    }
+
+    #[test]
+    fn test_bullets_wrap_under_their_text() {
+        let lines = to_lines("Steps:\n- call **Ann** back\n  * then email", 14);
+        assert_eq!(
+            lines,
+            vec![
+                Line::from("Steps:"),
+                Line::from(vec![Span::raw("• call "), Span::styled("Ann", bold()),]),
+                Line::from("  back"),
+                Line::from("  • then email"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_links_show_label_and_url() {
+        let lines = to_lines(
+            "See [the spec](https://x.io) or [https://y.io](https://y.io)",
+            80,
+        );
+        let link = Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::UNDERLINED);
+        assert_eq!(
+            lines[0].spans,
+            vec![
+                Span::raw("See "),
+                Span::styled("the spec", link),
+                Span::raw(" (https://x.io) or "),
+                Span::styled("https://y.io", link),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unclosed_markers_and_snake_case_stay_literal() {
+        for text in [
+            "2 * 3 = 6",
+            "**not bold",
+            "a snake_case_name",
+            "[label](no end",
+        ] {
+            assert_eq!(to_lines(text, 80), vec![Line::from(text)], "{text:?}");
+        }
+    }
+
+    #[test]
+    fn test_long_words_are_split() {
+        let lines = to_lines("abcdefghij", 4);
+        let text: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+        assert_eq!(text, vec!["abcd", "efgh", "ij"]);
+        assert!(to_lines("text", 0).is_empty());
+    }
+}
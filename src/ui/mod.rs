@@ -13,27 +13,44 @@
 
 use chrono::{DateTime, Local};
 use ratatui::crossterm::event;
-use ratatui::layout::Constraint;
+use ratatui::layout::{Alignment, Constraint, Layout, Rect};
 use ratatui::style::{Color, Modifier, Style};
-use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Cell, Padding, Row, Table};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Cell, ListState, Padding, Row, Table};
 use ratatui::Frame;
 use std::collections::HashSet;
-use std::time::SystemTime;
+use std::io::IsTerminal;
+use std::time::{Duration, SystemTime};
 use thiserror::Error;
 
+use crate::config::{ColorScheme, CreatedFormat, EditField};
 use crate::storage::{self, Db};
-use crate::types::{AppState, Task};
+
+/// How long the event loop waits for input before running periodic housekeeping
+const TICK_RATE: Duration = Duration::from_millis(250);
+
+/// Width of the project column, which appears once any task has a project
+const PROJECT_COLUMN_WIDTH: u16 = 12;
+
+/// Width of the tags column; longer tag lists are cut with the truncation indicator
+const TAGS_COLUMN_WIDTH: u16 = 14;
+use crate::types::{AppState, Priority, SortKey, Task, View};
 
 /// Format an optional timestamp for display in the UI
 ///
 /// Converts an optional SystemTime to a human-readable string:
-/// - Some(time) -> relative time format (e.g., "2h ago", "in 3d")
+/// - Some(time) -> relative time format (e.g., "2h ago", "in 3d"), followed by
+///   the date in `date_format` when given (e.g., "in 3d (18/01/2024)")
 /// - None -> "-" to indicate no date set
-fn format_date(time: Option<SystemTime>) -> String {
-    match time {
-        Some(t) => format_time_relative(t),
-        None => "-".to_string(),
+pub fn format_date(time: Option<SystemTime>, now: SystemTime, date_format: Option<&str>) -> String {
+    match (time, date_format) {
+        (Some(t), Some(date_format)) => format!(
+            "{} ({})",
+            format_time_relative(t, now),
+            DateTime::<Local>::from(t).format(date_format)
+        ),
+        (Some(t), None) => format_time_relative(t, now),
+        (None, _) => "-".to_string(),
     }
 }
 
@@ -43,128 +60,317 @@ fn format_date(time: Option<SystemTime>) -> String {
 /// strings, making it easier for users to understand when tasks are due or
 /// when they were created. Handles both past and future dates gracefully.
 ///
+/// The unit is the largest that still reads naturally: days up to two
+/// weeks, then weeks up to about two months, then months up to a year,
+/// then years.
+///
 /// # Arguments
 /// * `time` - The timestamp to format
+/// * `now` - The reference time the result is relative to
 ///
 /// # Returns
-/// A string representation like "2d ago", "3h ago", "in 1d", or "now"
-fn format_time_relative(time: SystemTime) -> String {
-    let now = SystemTime::now();
-    match now.duration_since(time) {
-        Ok(duration) => {
-            // Time is in the past
-            let secs = duration.as_secs();
-            let days = secs / 86400;
-            let hours = (secs % 86400) / 3600;
-            let minutes = (secs % 3600) / 60;
-
-            if days > 0 {
-                format!("{days}d ago")
-            } else if hours > 0 {
-                format!("{hours}h ago")
-            } else if minutes > 0 {
-                format!("{minutes}m ago")
-            } else {
-                "now".to_string()
-            }
-        }
-        Err(_) => {
-            // Time is in the future
-            match time.duration_since(now) {
-                Ok(duration) => {
-                    let secs = duration.as_secs();
-                    let days = secs / 86400;
-                    let hours = (secs % 86400) / 3600;
-                    let minutes = (secs % 3600) / 60;
-
-                    if days > 0 {
-                        format!("in {days}d")
-                    } else if hours > 0 {
-                        format!("in {hours}h")
-                    } else if minutes > 0 {
-                        format!("in {minutes}m")
-                    } else {
-                        "now".to_string()
-                    }
-                }
-                Err(_) => "Invalid".to_string(),
-            }
-        }
+/// A string representation like "2d ago", "3h ago", "in 2mo", "3y ago", or "now"
+fn format_time_relative(time: SystemTime, now: SystemTime) -> String {
+    let (duration, past) = match now.duration_since(time) {
+        Ok(duration) => (duration, true),
+        Err(_) => match time.duration_since(now) {
+            Ok(duration) => (duration, false),
+            Err(_) => return "Invalid".to_string(),
+        },
+    };
+    match largest_unit(duration.as_secs()) {
+        Some(amount) if past => format!("{amount} ago"),
+        Some(amount) => format!("in {amount}"),
+        None => "now".to_string(),
     }
 }
 
+/// A number of seconds in its largest sensible unit ("2d", "3w", "5mo"), or None under a minute
+fn largest_unit(secs: u64) -> Option<String> {
+    const MINUTE: u64 = 60;
+    const HOUR: u64 = 60 * MINUTE;
+    const DAY: u64 = 24 * HOUR;
+    const WEEK: u64 = 7 * DAY;
+    const MONTH: u64 = 30 * DAY;
+    const YEAR: u64 = 365 * DAY;
+
+    let amount = if secs >= YEAR {
+        format!("{}y", secs / YEAR)
+    } else if secs >= 60 * DAY {
+        format!("{}mo", secs / MONTH)
+    } else if secs >= 2 * WEEK {
+        format!("{}w", secs / WEEK)
+    } else if secs >= DAY {
+        format!("{}d", secs / DAY)
+    } else if secs >= HOUR {
+        format!("{}h", secs / HOUR)
+    } else if secs >= MINUTE {
+        format!("{}m", secs / MINUTE)
+    } else {
+        return None;
+    };
+    Some(amount)
+}
+
 /// Format task creation timestamp for display
 ///
-/// Uses a hybrid approach to balance usefulness and readability:
+/// The default hybrid format balances usefulness and readability:
 /// - Recent tasks (< 24h): Show relative time ("2h ago", "30m ago")
-/// - Older tasks: Show absolute date ("2024-01-15")
+/// - Older tasks: Show absolute date in `date_format` ("2024-01-15")
 ///
 /// This provides immediate context for recent activity while keeping
-/// older entries compact and dateable.
+/// older entries compact and dateable. The relative format counts days
+/// for older tasks ("3d ago") and the absolute format always shows the date.
 ///
 /// # Arguments
 /// * `time` - The creation timestamp to format
+/// * `now` - The reference time used to decide between relative and absolute
+/// * `format` - Which of the formats above to use
+/// * `date_format` - strftime-style format for absolute dates (`ui.date_format`)
 ///
 /// # Returns
 /// A formatted string suitable for display in the task list
-fn format_created_at(time: SystemTime) -> String {
-    let now = SystemTime::now();
+fn format_created_at(
+    time: SystemTime,
+    now: SystemTime,
+    format: CreatedFormat,
+    date_format: &str,
+) -> String {
     let datetime = DateTime::<Local>::from(time);
+    let absolute = datetime.format(date_format).to_string();
+
+    // Creation times in the future (clock changes) are shown as dates
+    let Ok(duration) = now.duration_since(time) else {
+        return absolute;
+    };
+    let secs = duration.as_secs();
+    let days = secs / 86400;
+    let hours = (secs % 86400) / 3600;
+    let minutes = (secs % 3600) / 60;
+
+    match format {
+        CreatedFormat::Absolute => absolute,
+        CreatedFormat::Hybrid if days > 0 => absolute,
+        CreatedFormat::Relative if days > 0 => format!("{days}d ago"),
+        _ if hours > 0 => format!("{hours}h ago"),
+        _ if minutes > 0 => format!("{minutes}m ago"),
+        _ => "now".to_string(),
+    }
+}
 
-    match now.duration_since(time) {
-        Ok(duration) => {
-            let secs = duration.as_secs();
-            let days = secs / 86400;
-            let hours = (secs % 86400) / 3600;
-            let minutes = (secs % 3600) / 60;
-
-            // For tasks created within the last day, show relative time
-            if days == 0 {
-                if hours > 0 {
-                    format!("{hours}h ago")
-                } else if minutes > 0 {
-                    format!("{minutes}m ago")
-                } else {
-                    "now".to_string()
-                }
-            } else {
-                // For older tasks, show the actual date
-                datetime.format("%Y-%m-%d").to_string()
+/// First list row to show so the `selected` row keeps `margin` rows of context
+///
+/// Like vim's `scrolloff`: the list only scrolls once the cursor gets within
+/// `margin` rows of the top or bottom of the `viewport`, and the margin
+/// shrinks near the ends of the list and in viewports too small for it.
+fn scroll_offset(
+    selected: usize,
+    offset: usize,
+    viewport: usize,
+    total: usize,
+    margin: usize,
+) -> usize {
+    if viewport == 0 || total == 0 {
+        return 0;
+    }
+    let selected = selected.min(total - 1);
+    let margin = margin.min((viewport - 1) / 2);
+    let max_offset = total.saturating_sub(viewport);
+    let mut offset = offset.min(max_offset);
+    if selected < offset + margin {
+        offset = selected.saturating_sub(margin);
+    } else if selected + margin >= offset + viewport {
+        offset = selected + margin + 1 - viewport;
+    }
+    offset.min(max_offset)
+}
+
+/// Cap `text` at `max_chars` characters, ending in `indicator` when cut
+///
+/// A limit of zero leaves the text untouched. The indicator counts towards
+/// the limit and is itself cut if it is wider than the limit.
+fn truncate_chars(text: &str, max_chars: usize, indicator: &str) -> String {
+    if max_chars == 0 || text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let keep = max_chars.saturating_sub(indicator.chars().count());
+    text.chars()
+        .take(keep)
+        .chain(indicator.chars())
+        .take(max_chars)
+        .collect()
+}
+
+/// Fit `text` into `area`, either wrapped across its lines or cut to one line
+///
+/// This is the one place that decides how task text is shortened: the task
+/// list truncates while the detail pane wraps. Text that does not fit ends
+/// in `indicator` (`ui.truncation_indicator`).
+fn render_text(area: Rect, text: &str, wrap: bool, indicator: &str) -> Text<'static> {
+    let width = area.width as usize;
+    let height = area.height as usize;
+    if width == 0 || height == 0 {
+        return Text::default();
+    }
+    if !wrap {
+        return Text::from(truncate_chars(&text.replace('\n', " "), width, indicator));
+    }
+
+    let mut lines: Vec<String> = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let line_len = line.chars().count();
+            if line_len > 0 && line_len + 1 + word.chars().count() > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+            // Hard-split words longer than the whole line
+            while line.chars().count() > width {
+                lines.push(line.chars().take(width).collect());
+                line = line.chars().skip(width).collect();
             }
         }
-        Err(_) => datetime.format("%Y-%m-%d").to_string(),
+        lines.push(line);
+    }
+
+    if lines.len() > height {
+        lines.truncate(height);
+        if let Some(last) = lines.last_mut() {
+            let keep = width.saturating_sub(indicator.chars().count());
+            *last = last
+                .chars()
+                .take(keep)
+                .chain(indicator.chars())
+                .take(width)
+                .collect();
+        }
+    }
+    Text::from(lines.into_iter().map(Line::from).collect::<Vec<_>>())
+}
+
+/// A field being edited: its text in `style`, with the caret shown reversed
+///
+/// The caret sits on the character it is before, or on a trailing space at the end.
+fn edit_line(input: &str, caret_from_end: usize, style: Style) -> Line<'static> {
+    let chars: Vec<char> = input.chars().collect();
+    let caret = chars.len().saturating_sub(caret_from_end);
+    let before: String = chars[..caret].iter().collect();
+    let at = chars.get(caret).map_or(" ".to_string(), char::to_string);
+    let after: String = chars.iter().skip(caret + 1).collect();
+    Line::from(vec![
+        Span::styled(before, style),
+        Span::styled(at, style.add_modifier(Modifier::REVERSED)),
+        Span::styled(after, style),
+    ])
+}
+
+/// Split one line of `text` into spans, styling each case-insensitive occurrence of `query`
+fn highlight_matches(text: &str, query: &str, style: Style) -> Line<'static> {
+    let fold = |c: char| c.to_lowercase().next().unwrap_or(c);
+    let needle: Vec<char> = query.chars().map(fold).collect();
+    if needle.is_empty() {
+        return Line::from(text.to_string());
+    }
+    let chars: Vec<char> = text.chars().collect();
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let candidate = &chars[i..(i + needle.len()).min(chars.len())];
+        if candidate.len() == needle.len()
+            && candidate.iter().zip(&needle).all(|(&c, &n)| fold(c) == n)
+        {
+            if !plain.is_empty() {
+                spans.push(Span::raw(std::mem::take(&mut plain)));
+            }
+            spans.push(Span::styled(candidate.iter().collect::<String>(), style));
+            i += needle.len();
+        } else {
+            plain.push(chars[i]);
+            i += 1;
+        }
+    }
+    if !plain.is_empty() {
+        spans.push(Span::raw(plain));
+    }
+    Line::from(spans)
+}
+
+/// Parse a `#rrggbb` color from a color scheme into a terminal color
+///
+/// Returns `None` for anything else, so callers can fall back to a built-in color.
+fn parse_hex_color(hex: &str) -> Option<Color> {
+    let digits = hex.strip_prefix('#')?;
+    if digits.len() != 6 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).ok();
+    Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// A color from the active scheme, or `fallback` when it isn't a valid `#rrggbb`
+fn scheme_color(hex: &str, fallback: Color) -> Color {
+    parse_hex_color(hex).unwrap_or(fallback)
+}
+
+/// The "Pri" column cell; high priority stands out in the scheme's `accent` color
+fn priority_cell(priority: Priority, colors: &ColorScheme) -> Cell<'static> {
+    let cell = Cell::from(priority.label());
+    match priority {
+        Priority::High => cell.style(
+            Style::default()
+                .fg(scheme_color(&colors.accent, Color::Yellow))
+                .add_modifier(Modifier::BOLD),
+        ),
+        Priority::Medium | Priority::Low => cell,
     }
 }
 
 /// Determine the visual style for a task based on its scheduling status
 ///
 /// This function implements visual priority cues to help users quickly identify
-/// task urgency and scheduling states:
+/// task urgency and scheduling states, in the colors of the active scheme:
 ///
-/// - **Deferred tasks**: Dimmed (dark gray) until defer date passes
-/// - **Overdue tasks**: Bold red text for immediate attention
-/// - **Due today**: Bold red text for high urgency
+/// - **Completed tasks**: The scheme's `completed` color
+/// - **Someday tasks**: Default styling, since they carry no date urgency
+/// - **Deferred tasks**: The scheme's `deferred` color until defer date passes
+/// - **Overdue tasks**: Bold, in the scheme's `overdue` color
+/// - **Due today**: Bold, in the scheme's `overdue` color
 /// - **Due within 24h**: Bold yellow text for moderate urgency
 /// - **Normal tasks**: Default styling
 ///
 /// The styling follows a traffic light pattern (red = urgent, yellow = soon)
-/// with additional dimming for deferred items.
+/// with distinct colors for finished and deferred items.
 ///
 /// # Arguments
 /// * `task` - The task to determine styling for
+/// * `now` - The reference time for due/defer comparisons
+/// * `colors` - The active color scheme (`[colors]`)
 ///
 /// # Returns
 /// A ratatui Style object with appropriate colors and modifiers
-fn get_task_highlight_style(task: &Task) -> Style {
-    let now = SystemTime::now();
+fn get_task_highlight_style(task: &Task, now: SystemTime, colors: &ColorScheme) -> Style {
+    if task.completed {
+        return Style::default().fg(scheme_color(&colors.completed, Color::DarkGray));
+    }
 
-    // Check if task is deferred (should be dimmed)
-    if let Some(defer_until) = task.defer_until {
-        if now < defer_until {
-            return Style::default().fg(Color::DarkGray);
-        }
+    // Someday/maybe tasks are parked and never highlighted as urgent
+    if task.someday {
+        return Style::default();
     }
 
+    // Check if task is deferred (should be set apart)
+    if task.is_deferred(now) {
+        return Style::default().fg(scheme_color(&colors.deferred, Color::DarkGray));
+    }
+    let overdue = Style::default()
+        .fg(scheme_color(&colors.overdue, Color::Red))
+        .add_modifier(Modifier::BOLD);
+
     // Check due date highlighting
     if let Some(due_date) = task.due {
         let time_until_due = due_date.duration_since(now);
@@ -174,8 +380,8 @@ fn get_task_highlight_style(task: &Task) -> Style {
                 let hours_until_due = duration.as_secs() / 3600;
 
                 if hours_until_due == 0 {
-                    // Due today - strong highlight (red text)
-                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+                    // Due today - strong highlight
+                    overdue
                 } else if hours_until_due <= 24 {
                     // Due within 24 hours - subtle highlight (yellow text)
                     Style::default()
@@ -187,8 +393,8 @@ fn get_task_highlight_style(task: &Task) -> Style {
                 }
             }
             Err(_) => {
-                // Overdue - strong red highlight
-                Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+                // Overdue - strong highlight
+                overdue
             }
         }
     } else {
@@ -198,16 +404,27 @@ fn get_task_highlight_style(task: &Task) -> Style {
 }
 
 // Sub-modules providing specialized UI functionality
+pub mod about_panel; // Version and file location overlay
 pub mod app; // Core application state management and business logic
+pub mod cell_popup; // Full content of a clipped cell
+pub mod clipboard; // System clipboard access for pasting
+pub mod detail_panel; // Task detail overlay
 pub mod events; // Keyboard input processing and event handling
 pub mod help_panel; // Help overlay system
+pub mod history; // Undo and redo of task list changes
 
 pub mod layout; // Terminal layout management and responsive design
+pub mod leader_popup; // Keys that can follow the leader key
+pub mod markdown; // Styled description and notes in the detail pane
 
+use about_panel::AboutPanel;
 use app::App;
+use cell_popup::CellPopup;
+use detail_panel::DetailPanel;
 use events::EventHandler;
 use help_panel::HelpPanel;
 use layout::LayoutManager;
+use leader_popup::LeaderPopup;
 
 /// Main UI coordinator combining all interface components
 ///
@@ -215,6 +432,8 @@ use layout::LayoutManager;
 /// terminal interface. It combines:
 /// - App: Core application logic and state management
 /// - HelpPanel: Context-sensitive help system
+/// - DetailPanel: Full view of the selected task
+/// - AboutPanel: Version, file locations and task counts
 /// - LayoutManager: Responsive terminal layout
 /// - EventHandler: Input processing and command routing
 ///
@@ -224,10 +443,20 @@ pub struct Ui<D: Db> {
     app: App<D>,
     /// Help system for displaying contextual assistance
     help_panel: HelpPanel,
+    /// Detail overlay for the task under the cursor
+    detail_panel: DetailPanel,
+    /// Popup with the full content of a clipped cell
+    cell_popup: CellPopup,
+    /// About overlay for reporting issues with exact context
+    about_panel: AboutPanel,
+    /// Which-key popup shown after the leader key
+    leader_popup: LeaderPopup,
     /// Terminal layout management for responsive design
     layout_manager: LayoutManager,
     /// Input processing and event routing
     event_handler: EventHandler,
+    /// Scroll position of the list grouped by tag, whose lines include headers
+    grouped_list_state: ListState,
 }
 
 impl<D: Db> Ui<D> {
@@ -235,22 +464,45 @@ impl<D: Db> Ui<D> {
         Self {
             app: App::new(app_state),
             help_panel: HelpPanel::new(),
+            detail_panel: DetailPanel::new(),
+            cell_popup: CellPopup::new(),
+            about_panel: AboutPanel::new(),
+            leader_popup: LeaderPopup::new(),
             layout_manager: LayoutManager::new(),
             event_handler: EventHandler::new(),
+            grouped_list_state: ListState::default(),
         }
     }
 
+    /// Run the interactive UI until the user quits
+    ///
+    /// Fails with [`UiError::NotATerminal`] when stdout is not a terminal
+    /// (e.g. `wimm | cat` or CI), before the terminal is touched.
     pub fn run(&mut self) -> Result<(), UiError> {
+        self.run_if_terminal(std::io::stdout().is_terminal())
+    }
+
+    fn run_if_terminal(&mut self, is_terminal: bool) -> Result<(), UiError> {
+        if !is_terminal {
+            return Err(UiError::NotATerminal);
+        }
         let mut terminal = ratatui::init();
+        // Pasted text arrives as one event instead of a stream of keys
+        ratatui::crossterm::execute!(std::io::stdout(), event::EnableBracketedPaste)?;
 
         while !self.app.state.should_quit {
             terminal.draw(|f| self.draw(f))?;
-            let event = event::read()?;
-            self.event_handler.handle_event(event, &mut self.app);
+            if event::poll(TICK_RATE)? {
+                let event = event::read()?;
+                self.event_handler.handle_event(event, &mut self.app);
+            }
+            self.app.tick();
         }
 
+        let flushed = self.app.shutdown();
+        let _ = ratatui::crossterm::execute!(std::io::stdout(), event::DisableBracketedPaste);
         ratatui::restore();
-        Ok(())
+        Ok(flushed?)
     }
 
     fn draw(&mut self, f: &mut Frame) {
@@ -262,7 +514,15 @@ impl<D: Db> Ui<D> {
         self.render_title(f, layout.title);
 
         // Render main task list
-        self.render_task_list(f, layout.main);
+        if self.app.state.group_by_tag {
+            self.render_grouped(f, layout.main);
+        } else {
+            self.render_task_list(f, layout.main);
+        }
+
+        if let Some(scratch_area) = layout.scratch {
+            self.render_scratch(f, scratch_area);
+        }
 
         // Render status bar
         self.render_status(f, layout.status);
@@ -272,6 +532,49 @@ impl<D: Db> Ui<D> {
             self.render_error_status(f, layout.status, message);
         }
 
+        let indicator = &self.app.state.config.ui.truncation_indicator;
+
+        // Render detail panel for the task under the cursor if open
+        if let Some(detail_area) = layout.detail {
+            if let Some(task) = self
+                .app
+                .cursor_task_index()
+                .map(|i| &self.app.state.tasks[i])
+            {
+                self.detail_panel.render(
+                    f,
+                    detail_area,
+                    task,
+                    self.app.now(),
+                    &self.app.state.config.ui,
+                );
+            }
+        }
+
+        // Render the full content of the focused cell if requested
+        if let Some(popup_area) = layout.cell_popup {
+            if let Some((label, value)) = self.app.cell_popup_content() {
+                self.cell_popup
+                    .render(f, popup_area, label, &value, indicator);
+            }
+        }
+
+        if let Some(about_area) = layout.about {
+            let config_path = crate::config::Config::config_path().ok();
+            self.about_panel.render(
+                f,
+                about_area,
+                config_path.as_deref(),
+                self.app.state.store.location(),
+                &self.app.state.tasks,
+            );
+        }
+
+        if self.app.leader_pending {
+            let bindings = self.app.state.config.keymap.leader_bindings();
+            self.leader_popup.render(f, f.area(), &bindings);
+        }
+
         // Render help panel if visible
         if let Some(help_area) = layout.help {
             self.help_panel.render(f, help_area);
@@ -293,72 +596,355 @@ impl<D: Db> Ui<D> {
             crate::types::Mode::Normal => "NORMAL".to_string(),
             crate::types::Mode::Insert => {
                 if self.app.state.editing_task.is_some() {
-                    let field_name = match self.app.state.editing_field {
-                        0 => "Title",
-                        1 => "Description",
-                        2 => "Due Date",
-                        3 => "Defer Until",
-                        _ => "Unknown",
-                    };
-                    format!("INSERT - Editing: {field_name}")
+                    let editing_field = self.app.state.editing_field;
+                    let field_name = self.app.field_label(editing_field);
+                    if self.app.edit_field(editing_field) == EditField::Notes {
+                        // Notes have no column in the list, so show the input here
+                        format!(
+                            "INSERT - Editing: {field_name}: {}",
+                            self.app.state.input_buffer
+                        )
+                    } else {
+                        format!("INSERT - Editing: {field_name}")
+                    }
                 } else {
                     "INSERT".to_string()
                 }
             }
+            crate::types::Mode::Command => format!("COMMAND :{}", self.app.state.input_buffer),
+            crate::types::Mode::Scratch => "SCRATCH (Esc to keep)".to_string(),
+            crate::types::Mode::Search => format!("SEARCH /{}", self.app.state.input_buffer),
         };
 
-        let status = format!("Mode: {mode_text}");
+        let mut status = format!("Mode: {mode_text}");
+        // Recomputed on every draw, so the elapsed time keeps counting
+        if let Some(timer) = self.app.timer_status() {
+            status.push_str(&format!("  {timer}"));
+        }
         let status_paragraph = Paragraph::new(status).alignment(Alignment::Left);
         f.render_widget(status_paragraph, area);
     }
 
+    /// Block title for the task list: count, active view and filter, overdue tally
+    fn list_title(&self, task_count: usize) -> String {
+        let view = self.app.state.view;
+        let query = &self.app.state.query;
+        let overdue_count = self.app.overdue_count();
+        let waiting_count = self.app.waiting_too_long_count();
+
+        let mut title = format!(" Tasks ({task_count}) ");
+        if view != View::All {
+            title.push_str(&format!("[{}] ", view.name()));
+        }
+        if let Some(tag) = &query.tag {
+            title.push_str(&format!("[tag:{tag}] "));
+        }
+        let rest = crate::filter::Query {
+            tag: None,
+            ..query.clone()
+        };
+        if !rest.is_empty() {
+            title.push_str(&format!("[filter: {rest}] "));
+        }
+        if !self.app.state.search.is_empty() {
+            title.push_str(&format!("[search: {}] ", self.app.state.search));
+        }
+        if let Some(urgency) = self.app.state.urgency {
+            title.push_str(&format!("[{}] ", urgency.name()));
+        }
+        if self.app.sort_key != SortKey::Manual || self.app.sort_desc {
+            let arrow = if self.app.sort_desc { "↓" } else { "↑" };
+            title.push_str(&format!("[sort: {} {arrow}] ", self.app.sort_key.name()));
+        }
+        if overdue_count > 0 {
+            title.push_str(&format!("- {overdue_count} overdue "));
+        }
+        if waiting_count > 0 {
+            let days = self.app.state.config.ui.waiting_nudge_days;
+            title.push_str(&format!("- {waiting_count} waiting {days}+ days "));
+        }
+        title
+    }
+
+    /// Render the visible tasks under tag headers, marking the row under the cursor
+    ///
+    /// Scrolls like the plain list, keeping `ui.scroll_off` lines around the cursor.
+    fn render_grouped(&mut self, f: &mut Frame, area: ratatui::layout::Rect) {
+        use ratatui::widgets::List;
+
+        if !self.app.state.tasks.is_empty() && self.app.cursor_task_index().is_none() {
+            self.app.cursor_first_task();
+        }
+
+        let now = self.app.now();
+        let groups = self.app.group_by_tag();
+        let task_count = self.app.visible_task_indices().len();
+        let row_count: usize = groups.iter().map(|(_, indices)| indices.len()).sum();
+        // Clamped here as the table would, since no table holds the cursor rows
+        let cursor_row = self
+            .app
+            .task_list_state
+            .selected()
+            .map(|row| row.min(row_count.saturating_sub(1)));
+        self.app.task_list_state.select(cursor_row);
+        let colors = &self.app.state.config.colors;
+
+        let mut lines: Vec<Line> = Vec::new();
+        let mut cursor_line = None;
+        let mut row = 0;
+        for (tag, indices) in &groups {
+            lines.push(Line::from(Span::styled(
+                format!("{tag} ({})", indices.len()),
+                Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            )));
+            for &i in indices {
+                let task = &self.app.state.tasks[i];
+                let marker = if cursor_row == Some(row) {
+                    cursor_line = Some(lines.len());
+                    "> "
+                } else {
+                    "  "
+                };
+                row += 1;
+                let status = if task.completed { "[x]" } else { "[ ]" };
+                lines.push(Line::from(Span::styled(
+                    format!(
+                        "{marker}{status} {}  {}",
+                        task.title,
+                        format_date(task.due, now, None)
+                    ),
+                    get_task_highlight_style(task, now, colors),
+                )));
+            }
+            lines.push(Line::from(""));
+        }
+
+        let line_count = lines.len();
+        let mut list = List::new(lines);
+        let mut inner = area;
+        if !self.app.state.focus_mode {
+            let block = Block::bordered()
+                .border_style(
+                    Style::default().fg(scheme_color(colors.border.as_str(), Color::Reset)),
+                )
+                .padding(Padding::uniform(1))
+                .title(Line::from(self.list_title(task_count)));
+            inner = block.inner(area);
+            list = list.block(block);
+        }
+
+        let scroll_off = self.app.state.config.ui.scroll_off;
+        let list_state = &mut self.grouped_list_state;
+        list_state.select(cursor_line);
+        if let Some(selected) = cursor_line {
+            *list_state.offset_mut() = scroll_offset(
+                selected,
+                list_state.offset(),
+                inner.height as usize,
+                line_count,
+                scroll_off,
+            );
+        }
+        f.render_stateful_widget(list, area, list_state);
+    }
+
     fn render_task_list(&mut self, f: &mut Frame, area: ratatui::layout::Rect) {
         // Auto-select first item if nothing is selected and tasks exist
         if !self.app.state.tasks.is_empty() && self.app.cursor_task_index().is_none() {
             self.app.cursor_first_task();
         }
 
-        let header = Row::new(vec![
+        let mut columns = self.app.state.config.columns_for(self.app.state.view);
+        if self.app.state.editing_task.is_some() {
+            // Fields edited in place need their columns, whatever the view hides
+            columns.description = true;
+            columns.due = true;
+            columns.defer_until = true;
+        }
+        let created_format = self.app.state.config.ui.created_format;
+        let date_format = &self.app.state.config.ui.date_format;
+        // The project column only takes space once some task has a project
+        let show_project_column =
+            columns.project && self.app.state.tasks.iter().any(|t| t.project.is_some());
+        // Likewise for tags, or while tags can be typed in
+        let editing_tags = self.app.state.editing_task.is_some()
+            && self.app.edit_fields().contains(&EditField::Tags);
+        let show_tags_column = columns.tags
+            && (editing_tags || self.app.state.tasks.iter().any(|t| !t.tags.is_empty()));
+
+        let mut header_cells = vec![
             Cell::from("Status").style(Style::default().add_modifier(Modifier::BOLD)),
+            Cell::from(""),
             Cell::from("Title").style(Style::default().add_modifier(Modifier::BOLD)),
-            Cell::from("Description").style(Style::default().add_modifier(Modifier::BOLD)),
-            Cell::from("Created").style(Style::default().add_modifier(Modifier::BOLD)),
-            Cell::from("Due").style(Style::default().add_modifier(Modifier::BOLD)),
-            Cell::from("Defer Until").style(Style::default().add_modifier(Modifier::BOLD)),
-        ]);
+        ];
+        if columns.description {
+            header_cells.push(
+                Cell::from("Description").style(Style::default().add_modifier(Modifier::BOLD)),
+            );
+        }
+        if columns.priority {
+            header_cells
+                .push(Cell::from("Pri").style(Style::default().add_modifier(Modifier::BOLD)));
+        }
+        if columns.created {
+            header_cells
+                .push(Cell::from("Created").style(Style::default().add_modifier(Modifier::BOLD)));
+        }
+        if columns.due {
+            header_cells
+                .push(Cell::from("Due").style(Style::default().add_modifier(Modifier::BOLD)));
+        }
+        if columns.days {
+            header_cells.push(
+                Cell::from(Line::from("Days").alignment(Alignment::Right))
+                    .style(Style::default().add_modifier(Modifier::BOLD)),
+            );
+        }
+        if columns.defer_until {
+            header_cells.push(
+                Cell::from("Defer Until").style(Style::default().add_modifier(Modifier::BOLD)),
+            );
+        }
+        if show_tags_column {
+            header_cells
+                .push(Cell::from("Tags").style(Style::default().add_modifier(Modifier::BOLD)));
+        }
+        if show_project_column {
+            header_cells
+                .push(Cell::from("Project").style(Style::default().add_modifier(Modifier::BOLD)));
+        }
+        let header = Row::new(header_cells);
+
+        let mut widths = vec![
+            Constraint::Length(5),      // Status column
+            Constraint::Length(2),      // Defer glyph column
+            Constraint::Percentage(25), // Title column
+        ];
+        if columns.description {
+            widths.push(Constraint::Percentage(30)); // Description column
+        }
+        if columns.priority {
+            widths.push(Constraint::Length(4)); // Priority column
+        }
+        if columns.created {
+            widths.push(Constraint::Length(10)); // Created column
+        }
+        if columns.due {
+            widths.push(Constraint::Length(10)); // Due column
+        }
+        if columns.days {
+            widths.push(Constraint::Length(5)); // Days until due column
+        }
+        if columns.defer_until {
+            widths.push(Constraint::Length(12)); // Defer Until column
+        }
+        if show_tags_column {
+            widths.push(Constraint::Length(TAGS_COLUMN_WIDTH)); // Tags column
+        }
+        if show_project_column {
+            widths.push(Constraint::Length(PROJECT_COLUMN_WIDTH)); // Project column
+        }
+
+        // Cell areas for one row, so long text is cut to the column width.
+        // The table indents rows by the "> " highlight symbol.
+        // Focus mode drops the border so the list fills the screen
+        let focus_mode = self.app.state.focus_mode;
+        let colors = self.app.state.config.colors.clone();
+        let block = Block::bordered()
+            .border_style(Style::default().fg(scheme_color(&colors.border, Color::Reset)))
+            .padding(Padding::uniform(1));
+        let inner = if focus_mode { area } else { block.inner(area) };
+        let row_area = Rect {
+            x: inner.x + 2,
+            width: inner.width.saturating_sub(2),
+            height: 1,
+            ..inner
+        };
+        let column_areas = Layout::horizontal(widths.clone())
+            .spacing(1)
+            .split(row_area);
+        let title_area = column_areas[2];
+        let description_area = if columns.description {
+            column_areas[3]
+        } else {
+            Rect::default()
+        };
 
         // Get necessary data before borrowing self.app mutably
         let current_selection = self.app.cursor_task_index();
         let is_editing_task = self.app.state.editing_task.is_some();
-        let editing_field = self.app.state.editing_field;
+        let editing_field = self.app.edit_field(self.app.state.editing_field);
         let input_buffer = self.app.state.input_buffer.clone();
-        let task_count = self.app.state.tasks.len();
+        let caret_from_end = self.app.state.caret_from_end;
+        // Selected text is shown reversed until typing replaces it
+        let edit_style = if self.app.state.select_pending {
+            Style::default().bg(Color::Black).fg(Color::Yellow)
+        } else {
+            Style::default().bg(Color::Yellow).fg(Color::Black)
+        };
         let editing_task = self.app.state.editing_task.clone();
+        let visible = self.app.visible_task_indices();
+        let task_count = visible.len();
+        let defer_glyph = self.app.state.config.ui.defer_glyph.clone();
+        let desc_preview_chars = self.app.state.config.ui.desc_preview_chars;
+        let indicator = self.app.state.config.ui.truncation_indicator.clone();
+        let search = self.app.state.search.clone();
+        let search_style = Style::default()
+            .fg(Color::Black)
+            .bg(scheme_color(&colors.accent, Color::Yellow));
+        // Cut to the column first, so the highlight covers what is shown
+        let searched = |area: Rect, text: &str| {
+            let shown = render_text(area, text, false, &indicator);
+            let shown = shown.lines.first().map(Line::to_string).unwrap_or_default();
+            Cell::from(highlight_matches(&shown, &search, search_style))
+        };
+        let waiting_nudge_days = self.app.state.config.ui.waiting_nudge_days;
+        let completion_flashing: HashSet<String> = self
+            .app
+            .state
+            .tasks
+            .iter()
+            .filter(|task| self.app.completion_feedback(&task.id).is_some())
+            .map(|task| task.id.clone())
+            .collect();
+        let now = self.app.now();
 
         // Clone the tasks to avoid borrowing issues
         let tasks = self.app.state.tasks.clone();
-        let selected_tasks: HashSet<usize> = self.app.get_task_selection().clone();
+        let selected_tasks: HashSet<String> = self.app.get_task_selection().clone();
 
-        let rows: Vec<Row> = tasks
+        let rows: Vec<Row> = visible
             .iter()
-            .enumerate()
-            .map(|(i, task)| {
+            .map(|&i| {
+                let task = &tasks[i];
                 let is_selected = current_selection == Some(i);
                 let is_editing = is_editing_task && is_selected;
 
-                let status_cell = Cell::from(if task.completed { "[x]" } else { "[ ]" });
+                let status = if task.completed {
+                    "[x]"
+                } else if task.in_progress {
+                    "[-]"
+                } else if task.someday {
+                    "[~]"
+                } else if task.waiting_since.is_some() {
+                    "[w]"
+                } else {
+                    "[ ]"
+                };
+                let energy = task.energy.map(|e| e.glyph()).unwrap_or("");
+                let status_cell = Cell::from(format!("{status}{energy}"));
 
-                let title_cell = if is_editing && is_selected && editing_field == 0 {
+                // Same predicate get_task_highlight_style uses for dimming, so the
+                // deferred state stays visible without colors
+                let defer_cell = Cell::from(if task.is_deferred(now) {
+                    defer_glyph.as_str()
+                } else {
+                    ""
+                });
+
+                let title_cell = if is_editing && is_selected && editing_field == EditField::Title {
                     // Currently editing title - show input buffer with highlight
-                    let display_text = if input_buffer.is_empty() {
-                        " "
-                    } else {
-                        &input_buffer
-                    };
-                    Cell::from(Line::from(vec![Span::styled(
-                        display_text,
-                        Style::default().bg(Color::Yellow).fg(Color::Black),
-                    )]))
+                    Cell::from(edit_line(&input_buffer, caret_from_end, edit_style))
                 } else if is_editing && is_selected {
                     // Show the current title from editing task
                     if let Some(ref editing_task) = editing_task {
@@ -367,84 +953,118 @@ impl<D: Db> Ui<D> {
                         Cell::from(task.title.clone())
                     }
                 } else {
-                    Cell::from(task.title.clone())
+                    searched(title_area, &task.title)
                 };
 
-                let description_cell = if is_editing && is_selected && editing_field == 1 {
-                    // Currently editing description - show input buffer with highlight
-                    let display_text = if input_buffer.is_empty() {
-                        " "
+                let description_cell =
+                    if is_editing && is_selected && editing_field == EditField::Description {
+                        // Currently editing description - show input buffer with highlight
+                        Cell::from(edit_line(&input_buffer, caret_from_end, edit_style))
+                    } else if is_editing && is_selected {
+                        // Show the current description from editing task
+                        if let Some(ref editing_task) = editing_task {
+                            Cell::from(editing_task.description.clone())
+                        } else {
+                            Cell::from(task.description.clone())
+                        }
                     } else {
-                        &input_buffer
+                        let preview =
+                            truncate_chars(&task.description, desc_preview_chars, &indicator);
+                        searched(description_area, &preview)
                     };
-                    Cell::from(Line::from(vec![Span::styled(
-                        display_text,
-                        Style::default().bg(Color::Yellow).fg(Color::Black),
-                    )]))
+
+                let due_cell = if is_editing && is_selected && editing_field == EditField::Due {
+                    Cell::from(edit_line(&input_buffer, caret_from_end, edit_style))
                 } else if is_editing && is_selected {
-                    // Show the current description from editing task
                     if let Some(ref editing_task) = editing_task {
-                        Cell::from(editing_task.description.clone())
+                        Cell::from(format_date(editing_task.due, now, None))
                     } else {
-                        Cell::from(task.description.clone())
+                        Cell::from(format_date(task.due, now, None))
                     }
                 } else {
-                    Cell::from(task.description.clone())
+                    Cell::from(format_date(task.due, now, None))
                 };
 
-                let created_cell = Cell::from(format_created_at(task.created_at));
-
-                let due_cell = if is_editing && is_selected && editing_field == 2 {
-                    let display_text = if input_buffer.is_empty() {
-                        " "
+                let defer_until_cell =
+                    if is_editing && is_selected && editing_field == EditField::DeferUntil {
+                        Cell::from(edit_line(&input_buffer, caret_from_end, edit_style))
+                    } else if is_editing && is_selected {
+                        if let Some(ref editing_task) = editing_task {
+                            Cell::from(format_date(editing_task.defer_until, now, None))
+                        } else {
+                            Cell::from(format_date(task.defer_until, now, None))
+                        }
                     } else {
-                        &input_buffer
+                        Cell::from(format_date(task.defer_until, now, None))
                     };
-                    Cell::from(Line::from(vec![Span::styled(
-                        display_text,
-                        Style::default().bg(Color::Yellow).fg(Color::Black),
-                    )]))
-                } else if is_editing && is_selected {
-                    if let Some(ref editing_task) = editing_task {
-                        Cell::from(format_date(editing_task.due))
-                    } else {
-                        Cell::from(format_date(task.due))
-                    }
+
+                let tags_cell = if is_editing && is_selected && editing_field == EditField::Tags {
+                    Cell::from(edit_line(&input_buffer, caret_from_end, edit_style))
                 } else {
-                    Cell::from(format_date(task.due))
+                    let tags = match editing_task {
+                        Some(ref editing_task) if is_editing => &editing_task.tags,
+                        _ => &task.tags,
+                    };
+                    Cell::from(truncate_chars(
+                        &tags.join(","),
+                        TAGS_COLUMN_WIDTH as usize,
+                        &indicator,
+                    ))
                 };
 
-                let defer_cell = if is_editing && is_selected && editing_field == 3 {
-                    let display_text = if input_buffer.is_empty() {
-                        " "
-                    } else {
-                        &input_buffer
-                    };
-                    Cell::from(Line::from(vec![Span::styled(
-                        display_text,
-                        Style::default().bg(Color::Yellow).fg(Color::Black),
-                    )]))
-                } else if is_editing && is_selected {
-                    if let Some(ref editing_task) = editing_task {
-                        Cell::from(format_date(editing_task.defer_until))
-                    } else {
-                        Cell::from(format_date(task.defer_until))
-                    }
+                let base_style = if completion_flashing.contains(&task.id) {
+                    Style::default()
+                        .fg(Color::Black)
+                        .bg(Color::Green)
+                        .add_modifier(Modifier::BOLD)
+                } else if task.is_waiting_too_long(now, waiting_nudge_days) {
+                    // Nudge to chase up stalled delegated tasks
+                    Style::default().fg(Color::Magenta)
                 } else {
-                    Cell::from(format_date(task.defer_until))
+                    get_task_highlight_style(task, now, &colors)
                 };
 
-                let base_style = get_task_highlight_style(task);
-
-                Row::new(vec![
-                    status_cell,
-                    title_cell,
-                    description_cell,
-                    created_cell,
-                    due_cell,
-                    defer_cell,
-                ])
-                .style(if selected_tasks.contains(&i) {
+                let mut cells = vec![status_cell, defer_cell, title_cell];
+                if columns.description {
+                    cells.push(description_cell);
+                }
+                if columns.priority {
+                    cells.push(priority_cell(task.priority, &colors));
+                }
+                if columns.created {
+                    cells.push(Cell::from(format_created_at(
+                        task.created_at,
+                        now,
+                        created_format,
+                        date_format,
+                    )));
+                }
+                if columns.due {
+                    cells.push(due_cell);
+                }
+                if columns.days {
+                    let days = task
+                        .days_until_due(now)
+                        .map(|days| days.to_string())
+                        .unwrap_or_default();
+                    cells.push(Cell::from(Line::from(days).alignment(Alignment::Right)));
+                }
+                if columns.defer_until {
+                    cells.push(defer_until_cell);
+                }
+                if show_tags_column {
+                    cells.push(tags_cell);
+                }
+                if show_project_column {
+                    let project = task.project.as_deref().unwrap_or_default();
+                    cells.push(Cell::from(truncate_chars(
+                        project,
+                        PROJECT_COLUMN_WIDTH as usize,
+                        &indicator,
+                    )));
+                }
+
+                Row::new(cells).style(if selected_tasks.contains(&task.id) {
                     base_style.bg(Color::DarkGray)
                 } else {
                     base_style
@@ -452,26 +1072,70 @@ impl<D: Db> Ui<D> {
             })
             .collect();
 
-        let table = Table::new(
-            rows,
-            [
-                Constraint::Length(5),      // Status column
-                Constraint::Percentage(25), // Title column
-                Constraint::Percentage(30), // Description column
-                Constraint::Length(10),     // Created column
-                Constraint::Length(10),     // Due column
-                Constraint::Length(12),     // Defer Until column
-            ],
-        )
-        .header(header)
-        .block(
-            Block::bordered()
-                .padding(Padding::uniform(1))
-                .title(Line::from(format!(" Tasks ({task_count}) "))),
-        )
-        .highlight_symbol("> ");
+        let title = self.list_title(task_count);
+
+        let mut table = Table::new(rows, widths)
+            .header(header)
+            .highlight_symbol("> ");
+        if !focus_mode {
+            table = table.block(block.title(Line::from(title)));
+        }
+
+        // Rows below the header, scrolled to keep `ui.scroll_off` rows around the cursor
+        let viewport = inner.height.saturating_sub(1) as usize;
+        let scroll_off = self.app.state.config.ui.scroll_off;
+        let list_state = self.app.task_list_state();
+        if let Some(selected) = list_state.selected() {
+            *list_state.offset_mut() = scroll_offset(
+                selected,
+                list_state.offset(),
+                viewport,
+                task_count,
+                scroll_off,
+            );
+        }
 
         f.render_stateful_widget(table, area, self.app.task_list_state());
+
+        if let Some(message) = self.app.empty_list_message() {
+            use ratatui::widgets::Paragraph;
+
+            // Below the header row
+            let message_area = Rect {
+                y: inner.y + 1,
+                height: 1,
+                ..inner
+            }
+            .intersection(inner);
+            f.render_widget(
+                Paragraph::new(message).alignment(Alignment::Center),
+                message_area,
+            );
+        }
+    }
+
+    /// Scratchpad column beside the task list, showing the text being typed while editing
+    fn render_scratch(&self, f: &mut Frame, area: Rect) {
+        use ratatui::widgets::{Paragraph, Wrap};
+
+        let editing = self.app.state.mode == crate::types::Mode::Scratch;
+        let text = if editing {
+            format!("{}_", self.app.state.input_buffer)
+        } else if self.app.state.scratch.is_empty() {
+            "Press I to write something down".to_string()
+        } else {
+            self.app.state.scratch.clone()
+        };
+        let border_color = if editing {
+            Color::Yellow
+        } else {
+            scheme_color(&self.app.state.config.colors.border, Color::Gray)
+        };
+        let block = Block::bordered()
+            .title(" Scratch ")
+            .border_style(Style::default().fg(border_color));
+        let paragraph = Paragraph::new(text).block(block).wrap(Wrap { trim: false });
+        f.render_widget(paragraph, area);
     }
 
     fn render_error_status(&self, f: &mut Frame, area: ratatui::layout::Rect, message: &str) {
@@ -487,13 +1151,552 @@ impl<D: Db> Ui<D> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::time::Duration;
+    use crate::storage::MemoryStorage;
+    use crate::types::{Priority, TaskSource};
+    use chrono::TimeZone;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    fn create_test_task(id: &str, title: &str) -> Task {
+        Task {
+            id: id.to_string(),
+            title: title.to_string(),
+            description: String::new(),
+            completed: false,
+            created_at: SystemTime::now(),
+            due: None,
+            defer_until: None,
+            someday: false,
+            tags: Vec::new(),
+            energy: None,
+            source: TaskSource::Tui,
+            url: None,
+            notes: String::new(),
+            project: None,
+            waiting_since: None,
+            in_progress: false,
+            subtasks: Vec::new(),
+            defer_until_task: None,
+            recurrence: None,
+            modified_at: SystemTime::UNIX_EPOCH,
+            reminder_lead: None,
+            next_occurrence_id: None,
+            completed_at: None,
+            priority: Priority::Medium,
+        }
+    }
+
+    /// Draw the whole UI into a test buffer and return it as one string per row
+    fn render_lines(ui: &mut Ui<MemoryStorage>, width: u16, height: u16) -> Vec<String> {
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        terminal.draw(|f| ui.draw(f)).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..height)
+            .map(|y| (0..width).map(|x| buffer[(x, y)].symbol()).collect())
+            .collect()
+    }
+
+    fn ui_with_tasks(tasks: Vec<Task>) -> Ui<MemoryStorage> {
+        Ui::new(AppState {
+            tasks,
+            ..AppState::default()
+        })
+    }
+
+    #[test]
+    fn test_render_scratchpad_beside_list() {
+        let mut ui = ui_with_tasks(vec![create_test_task("1", "Task")]);
+        ui.app.state.scratch = "call mum".to_string();
+
+        let lines = render_lines(&mut ui, 100, 12);
+        assert!(!lines.iter().any(|l| l.contains("call mum")));
+
+        ui.app.state.show_scratch = true;
+        let lines = render_lines(&mut ui, 100, 12);
+        let row = lines.iter().find(|l| l.contains("call mum")).unwrap();
+        assert!(row.find("call mum").unwrap() > 60);
+        assert!(lines.iter().any(|l| l.contains("Task")));
+    }
+
+    #[test]
+    fn test_run_without_terminal_is_an_error() {
+        let mut ui = ui_with_tasks(vec![create_test_task("1", "Task")]);
+        let result = ui.run_if_terminal(false);
+        assert!(matches!(result, Err(UiError::NotATerminal)));
+        assert!(result.unwrap_err().to_string().contains("not a terminal"));
+        assert!(!ui.app.state.should_quit);
+    }
+
+    #[test]
+    fn test_render_defer_glyph_only_for_future_deferred_tasks() {
+        let mut deferred = create_test_task("1", "Later");
+        deferred.defer_until = Some(SystemTime::now() + Duration::from_secs(60 * 60));
+        let mut past_defer = create_test_task("2", "Ready");
+        past_defer.defer_until = Some(SystemTime::now() - Duration::from_secs(60 * 60));
+        let mut ui = ui_with_tasks(vec![deferred, past_defer]);
+
+        let lines = render_lines(&mut ui, 100, 12);
+        let later = lines.iter().find(|l| l.contains("Later")).unwrap();
+        let ready = lines.iter().find(|l| l.contains("Ready")).unwrap();
+        assert!(later.contains('⏸'));
+        assert!(!ready.contains('⏸'));
+    }
+
+    #[test]
+    fn test_render_hides_created_column() {
+        let mut ui = ui_with_tasks(vec![create_test_task("1", "Fresh")]);
+        let lines = render_lines(&mut ui, 120, 12);
+        assert!(lines.iter().any(|l| l.contains("Created")));
+        assert!(lines.iter().any(|l| l.contains("now")));
+
+        ui.app.state.config.ui.show_created_column = false;
+        let lines = render_lines(&mut ui, 120, 12);
+        assert!(!lines.iter().any(|l| l.contains("Created")));
+        assert!(!lines.iter().any(|l| l.contains("now")));
+    }
+
+    #[test]
+    fn test_render_tags_column_and_tag_filter_title() {
+        let mut task = create_test_task("1", "Quarterly report");
+        task.tags = vec!["work".to_string(), "q3".to_string()];
+        let mut ui = ui_with_tasks(vec![create_test_task("2", "Untagged")]);
+        let lines = render_lines(&mut ui, 140, 12);
+        assert!(!lines.iter().any(|l| l.contains("Tags")));
+
+        ui.app.state.tasks.push(task);
+        let lines = render_lines(&mut ui, 140, 12);
+        assert!(lines.iter().any(|l| l.contains("Tags")));
+        assert!(lines.iter().any(|l| l.contains("work,q3")));
+
+        ui.app.task_list_state.select(Some(1));
+        ui.app.toggle_tag_filter().unwrap();
+        let lines = render_lines(&mut ui, 140, 12);
+        assert!(lines.iter().any(|l| l.contains("Tasks (1) [tag:work]")));
+        assert!(!lines.iter().any(|l| l.contains("[filter:")));
+    }
+
+    #[test]
+    fn test_edit_line_marks_the_caret() {
+        let style = Style::default().bg(Color::Yellow);
+        let caret = style.add_modifier(Modifier::REVERSED);
+        assert_eq!(
+            edit_line("abc", 1, style),
+            Line::from(vec![
+                Span::styled("ab", style),
+                Span::styled("c", caret),
+                Span::styled("", style),
+            ])
+        );
+        // At the end the caret is a trailing space, also for empty text
+        assert_eq!(
+            edit_line("abc", 0, style).spans[1],
+            Span::styled(" ", caret)
+        );
+        assert_eq!(edit_line("", 0, style).spans[1], Span::styled(" ", caret));
+        assert_eq!(
+            edit_line("abc", 9, style).spans[1],
+            Span::styled("a", caret)
+        );
+    }
+
+    #[test]
+    fn test_highlight_matches_styles_each_occurrence() {
+        let style = Style::default().bg(Color::Yellow);
+        assert_eq!(
+            highlight_matches("Pay rent, then PAY tax", "pay", style),
+            Line::from(vec![
+                Span::styled("Pay", style),
+                Span::raw(" rent, then "),
+                Span::styled("PAY", style),
+                Span::raw(" tax"),
+            ])
+        );
+        assert_eq!(
+            highlight_matches("Pay rent", "", style),
+            Line::from("Pay rent")
+        );
+        assert_eq!(highlight_matches("Pa", "pay", style), Line::from("Pa"));
+    }
+
+    #[test]
+    fn test_render_search_title_and_highlight() {
+        let mut ui = ui_with_tasks(vec![
+            create_test_task("1", "Quarterly report"),
+            create_test_task("2", "Walk the dog"),
+        ]);
+        ui.app.state.search = "report".to_string();
+        let mut terminal = Terminal::new(TestBackend::new(140, 12)).unwrap();
+        terminal.draw(|f| ui.draw(f)).unwrap();
+        let buffer = terminal.backend().buffer();
+        let lines: Vec<String> = (0..12)
+            .map(|y| (0..140).map(|x| buffer[(x, y)].symbol()).collect())
+            .collect();
+        assert!(lines
+            .iter()
+            .any(|l| l.contains("Tasks (1) [search: report]")));
+        assert!(!lines.iter().any(|l| l.contains("Walk the dog")));
+
+        let (row, line) = lines
+            .iter()
+            .enumerate()
+            .find(|(_, l)| l.contains("Quarterly report") && !l.contains("Description"))
+            .unwrap();
+        let column = line[..line.find("report").unwrap()].chars().count() as u16;
+        let accent = scheme_color(&ui.app.state.config.colors.accent, Color::Yellow);
+        assert_eq!(buffer[(column, row as u16)].bg, accent);
+        assert_ne!(buffer[(column - 2, row as u16)].bg, accent);
+    }
+
+    #[test]
+    fn test_render_priority_column() {
+        let mut task = create_test_task("1", "Call the bank");
+        task.priority = Priority::High;
+        let mut ui = ui_with_tasks(vec![task, create_test_task("2", "Water plants")]);
+
+        let lines = render_lines(&mut ui, 120, 12);
+        assert!(lines.iter().any(|l| l.contains("Pri")));
+        assert!(lines.iter().any(|l| l.contains("HIGH")));
+        assert!(lines.iter().any(|l| l.contains("med")));
+
+        let colors = ColorScheme {
+            accent: "#123456".to_string(),
+            ..ColorScheme::default()
+        };
+        let high = Cell::from("HIGH").style(
+            Style::default()
+                .fg(Color::Rgb(0x12, 0x34, 0x56))
+                .add_modifier(Modifier::BOLD),
+        );
+        assert_eq!(priority_cell(Priority::High, &colors), high);
+        assert_eq!(priority_cell(Priority::Low, &colors), Cell::from("low"));
+
+        ui.app.state.config.columns.insert(
+            View::All,
+            crate::config::ColumnOverrides {
+                priority: Some(false),
+                ..Default::default()
+            },
+        );
+        let lines = render_lines(&mut ui, 120, 12);
+        assert!(!lines.iter().any(|l| l.contains("HIGH")));
+    }
+
+    #[test]
+    fn test_render_applies_view_column_override() {
+        let mut task = create_test_task("1", "Learn piano");
+        task.someday = true;
+        let mut ui = ui_with_tasks(vec![task]);
+        ui.app.state.config.columns.insert(
+            View::Someday,
+            crate::config::ColumnOverrides {
+                due: Some(false),
+                defer_until: Some(false),
+                ..Default::default()
+            },
+        );
+
+        let lines = render_lines(&mut ui, 120, 12);
+        assert!(lines.iter().any(|l| l.contains("Defer Until")));
+
+        ui.app.set_view(View::Someday);
+        let lines = render_lines(&mut ui, 120, 12);
+        assert!(lines.iter().any(|l| l.contains("Learn piano")));
+        assert!(!lines.iter().any(|l| l.contains("Defer Until")));
+        assert!(!lines.iter().any(|l| l.contains(" Due ")));
+        assert!(lines.iter().any(|l| l.contains("Description")));
+    }
+
+    #[test]
+    fn test_render_custom_defer_glyph() {
+        let mut deferred = create_test_task("1", "Later");
+        deferred.defer_until = Some(SystemTime::now() + Duration::from_secs(60 * 60));
+        let mut ui = ui_with_tasks(vec![deferred]);
+        ui.app.state.config.ui.defer_glyph = "z".to_string();
+
+        let lines = render_lines(&mut ui, 100, 12);
+        let later = lines.iter().find(|l| l.contains("Later")).unwrap();
+        assert!(later.contains('z'));
+        assert!(!later.contains('⏸'));
+    }
+
+    #[test]
+    fn test_render_today_celebration_only_when_nothing_actionable() {
+        let mut later = create_test_task("1", "Later");
+        later.due = Some(SystemTime::now() + Duration::from_secs(3 * 24 * 60 * 60));
+        let mut ui = ui_with_tasks(vec![later]);
+        ui.app.set_view(View::Today);
+
+        let lines = render_lines(&mut ui, 100, 12);
+        assert!(lines.iter().any(|l| l.contains("All clear for today")));
+
+        ui.app.state.tasks[0].due = Some(SystemTime::now());
+        let lines = render_lines(&mut ui, 100, 12);
+        assert!(!lines.iter().any(|l| l.contains("All clear for today")));
+
+        let mut ui = ui_with_tasks(vec![]);
+        ui.app.set_view(View::Today);
+        let lines = render_lines(&mut ui, 100, 12);
+        assert!(!lines.iter().any(|l| l.contains("All clear for today")));
+        assert!(lines.iter().any(|l| l.contains("No tasks yet")));
+    }
+
+    #[test]
+    fn test_render_days_until_due_column() {
+        let day = Duration::from_secs(24 * 60 * 60);
+        let mut overdue = create_test_task("1", "Late");
+        overdue.due = Some(SystemTime::now() - 2 * day);
+        let undated = create_test_task("2", "Whenever");
+        let mut ui = ui_with_tasks(vec![overdue, undated]);
+        ui.app.state.config.ui.show_days_column = true;
+
+        let lines = render_lines(&mut ui, 120, 12);
+        let header = lines.iter().find(|l| l.contains("Title")).unwrap();
+        let days_end = header.find("Days").unwrap() + "Days".len();
+        let late = lines.iter().find(|l| l.contains("Late")).unwrap();
+        let whenever = lines.iter().find(|l| l.contains("Whenever")).unwrap();
+        let days_cell = |line: &str| -> String {
+            line.chars()
+                .skip(header[..days_end].chars().count() - 4)
+                .take(4)
+                .collect()
+        };
+        assert_eq!(days_cell(late), "  -2");
+        assert_eq!(days_cell(whenever), "    ");
+    }
+
+    #[test]
+    fn test_render_days_column_hidden_by_default() {
+        let mut ui = ui_with_tasks(vec![create_test_task("1", "Task")]);
+        let lines = render_lines(&mut ui, 120, 12);
+        assert!(!lines.iter().any(|l| l.contains("Days")));
+    }
+
+    #[test]
+    fn test_render_energy_indicator() {
+        let mut task = create_test_task("1", "Tired");
+        task.energy = Some(crate::types::Energy::Low);
+        let mut ui = ui_with_tasks(vec![task]);
+
+        let lines = render_lines(&mut ui, 100, 12);
+        let row = lines.iter().find(|l| l.contains("Tired")).unwrap();
+        assert!(row.contains("[ ]▁"));
+    }
+
+    #[test]
+    fn test_render_focus_mode_hides_chrome() {
+        let mut ui = ui_with_tasks(vec![create_test_task("1", "Only")]);
+        ui.app.state.focus_mode = true;
+
+        let lines = render_lines(&mut ui, 100, 12);
+        assert!(lines[0].contains("Title"));
+        assert!(!lines.iter().any(|l| l.contains("Wimm Task Manager")));
+        assert!(!lines.iter().any(|l| l.contains("Mode:")));
+        assert!(!lines.iter().any(|l| l.contains("Tasks (1)")));
+    }
+
+    #[test]
+    fn test_render_about_overlay() {
+        let mut ui = ui_with_tasks(vec![create_test_task("1", "Task")]);
+        ui.app.state.show_about = true;
+
+        let lines = render_lines(&mut ui, 80, 20);
+        let version = format!("wimm {}", env!("CARGO_PKG_VERSION"));
+        assert!(lines.iter().any(|l| l.contains(&version)));
+        assert!(lines.iter().any(|l| l.contains("1 total, 1 open")));
+    }
+
+    #[test]
+    fn test_render_grouped_by_tag() {
+        let mut work = create_test_task("1", "Report");
+        work.tags = vec!["work".to_string()];
+        let mut ui = ui_with_tasks(vec![work, create_test_task("2", "Nap")]);
+        ui.app.execute_command("group").unwrap();
+
+        let lines = render_lines(&mut ui, 60, 14);
+        let position = |needle: &str| lines.iter().position(|l| l.contains(needle)).unwrap();
+        assert!(position("work (1)") < position("Report"));
+        assert!(position("Report") < position("Untagged (1)"));
+        assert!(position("Untagged (1)") < position("Nap"));
+        assert!(lines[position("Report")].contains("> [ ] Report"));
+    }
+
+    #[test]
+    fn test_render_grouped_scrolls_with_the_cursor() {
+        let tasks = (0..30)
+            .map(|i| {
+                let mut task = create_test_task(&i.to_string(), &format!("Task {i:02}"));
+                task.tags = vec![if i < 15 { "home" } else { "work" }.to_string()];
+                task
+            })
+            .collect();
+        let mut ui = ui_with_tasks(tasks);
+        ui.app.state.config.ui.scroll_off = 2;
+        ui.app.execute_command("group").unwrap();
+
+        let lines = render_lines(&mut ui, 60, 14);
+        assert!(lines.iter().any(|l| l.contains("home (15)")));
+        assert!(lines.iter().any(|l| l.contains("> [ ] Task 00")));
+
+        // The cursor row in the second group stays in view with its margin
+        ui.app.task_list_state.select(Some(20));
+        let lines = render_lines(&mut ui, 60, 14);
+        let cursor = lines
+            .iter()
+            .position(|l| l.contains("> [ ] Task 20"))
+            .unwrap();
+        assert!(lines[cursor + 2].contains("Task 22"));
+        assert!(!lines.iter().any(|l| l.contains("home (15)")));
+
+        // Past the last row, the cursor settles on the last task
+        ui.app.task_list_state.select(Some(100));
+        let lines = render_lines(&mut ui, 60, 14);
+        assert!(lines.iter().any(|l| l.contains("> [ ] Task 29")));
+        assert_eq!(ui.app.task_list_state.selected(), Some(29));
+    }
+
+    #[test]
+    fn test_render_text_truncates_to_one_line() {
+        let area = Rect::new(0, 0, 8, 3);
+        assert_eq!(render_text(area, "short", false, "…"), Text::from("short"));
+        assert_eq!(
+            render_text(area, "much longer title", false, "…"),
+            Text::from("much lo…")
+        );
+        assert_eq!(
+            render_text(area, "two\nlines", false, "…"),
+            Text::from("two lin…")
+        );
+    }
+
+    #[test]
+    fn test_render_text_wraps_within_area() {
+        let area = Rect::new(0, 0, 10, 3);
+        assert_eq!(
+            render_text(area, "wrap this long title", true, "…"),
+            Text::from(vec![Line::from("wrap this"), Line::from("long title")])
+        );
+        assert_eq!(
+            render_text(area, "abcdefghijklmno", true, "…"),
+            Text::from(vec![Line::from("abcdefghij"), Line::from("klmno")])
+        );
+        assert_eq!(
+            render_text(
+                Rect::new(0, 0, 10, 2),
+                "one two three four five six",
+                true,
+                "…"
+            ),
+            Text::from(vec![Line::from("one two"), Line::from("three fou…")])
+        );
+        assert_eq!(
+            render_text(Rect::new(0, 0, 0, 2), "hidden", true, "…"),
+            Text::default()
+        );
+    }
+
+    #[test]
+    fn test_render_truncates_long_title_with_ellipsis() {
+        let mut ui = ui_with_tasks(vec![create_test_task(
+            "1",
+            "A very long title that cannot possibly fit in the title column",
+        )]);
+
+        let lines = render_lines(&mut ui, 100, 12);
+        let row = lines.iter().find(|l| l.contains("A very long")).unwrap();
+        assert!(row.contains('…'));
+        assert!(!row.contains("title column"));
+    }
+
+    #[test]
+    fn test_render_uses_configured_truncation_indicator() {
+        let mut ui = ui_with_tasks(vec![create_test_task(
+            "1",
+            "A very long title that cannot possibly fit in the title column",
+        )]);
+        ui.app.state.config.ui.truncation_indicator = ">>".to_string();
+
+        let lines = render_lines(&mut ui, 100, 12);
+        let row = lines.iter().find(|l| l.contains("A very long")).unwrap();
+        assert!(row.contains(">>"));
+        assert!(!row.contains('…'));
+    }
+
+    #[test]
+    fn test_render_cell_popup_shows_clipped_title() {
+        let mut ui = ui_with_tasks(vec![create_test_task(
+            "1",
+            "A very long title that cannot possibly fit in the title column",
+        )]);
+        ui.app.cursor_first_task();
+        ui.app.cycle_cell_popup();
+
+        let lines = render_lines(&mut ui, 100, 16);
+        assert!(lines.iter().any(|l| l.contains(" Title ")));
+        assert!(lines.iter().any(|l| l.contains("title column")));
+    }
+
+    #[test]
+    fn test_truncate_chars() {
+        assert_eq!(truncate_chars("short", 10, "…"), "short");
+        assert_eq!(truncate_chars("exactly", 7, "…"), "exactly");
+        assert_eq!(truncate_chars("a longer line", 5, "…"), "a lo…");
+        assert_eq!(truncate_chars("ünïcödé", 4, "…"), "ünï…");
+        assert_eq!(truncate_chars("no cap at zero", 0, "…"), "no cap at zero");
+        assert_eq!(truncate_chars("a longer line", 6, "..."), "a l...");
+        assert_eq!(truncate_chars("a longer line", 2, "..."), "..");
+        assert_eq!(truncate_chars("a longer line", 4, ""), "a lo");
+    }
+
+    #[test]
+    fn test_render_caps_description_preview() {
+        let mut task = create_test_task("1", "Long");
+        task.description = "abcdefghijklmnopqrstuvwxyz".to_string();
+        let mut ui = ui_with_tasks(vec![task]);
+        ui.app.state.config.ui.desc_preview_chars = 8;
+
+        let lines = render_lines(&mut ui, 160, 12);
+        let row = lines.iter().find(|l| l.contains("Long")).unwrap();
+        assert!(row.contains("abcdefg…"));
+        assert!(!row.contains("abcdefgh"));
+    }
+
+    #[test]
+    fn test_scroll_offset_keeps_margin() {
+        // 20 rows in a 10-row viewport with a margin of 3
+        let offset = |selected, current| scroll_offset(selected, current, 10, 20, 3);
+
+        // No scrolling while the cursor is inside the margins
+        assert_eq!(offset(0, 0), 0);
+        assert_eq!(offset(6, 0), 0);
+        // Moving into the bottom margin scrolls one row at a time
+        assert_eq!(offset(7, 0), 1);
+        assert_eq!(offset(12, 1), 6);
+        // Moving into the top margin scrolls back up
+        assert_eq!(offset(8, 6), 5);
+        assert_eq!(offset(2, 5), 0);
+        // The margin gives way at the ends of the list
+        assert_eq!(offset(19, 5), 10);
+        assert_eq!(offset(18, 10), 10);
+        assert_eq!(offset(1, 0), 0);
+        // A cursor past the end (select_last) is clamped to the last row
+        assert_eq!(offset(usize::MAX, 0), 10);
+    }
+
+    #[test]
+    fn test_scroll_offset_small_viewport_and_list() {
+        // The margin shrinks to fit the viewport
+        assert_eq!(scroll_offset(3, 0, 3, 10, 5), 2);
+        assert_eq!(scroll_offset(5, 0, 1, 10, 5), 5);
+        // A list that fits never scrolls, and no margin means plain scrolling
+        assert_eq!(scroll_offset(4, 0, 10, 5, 3), 0);
+        assert_eq!(scroll_offset(9, 0, 5, 20, 0), 5);
+        assert_eq!(scroll_offset(0, 0, 0, 20, 3), 0);
+    }
 
     #[test]
     fn test_format_created_at_recent() {
         let now = SystemTime::now();
         let two_hours_ago = now - Duration::from_secs(2 * 60 * 60);
-        let result = format_created_at(two_hours_ago);
+        let result = format_created_at(two_hours_ago, now, CreatedFormat::Hybrid, "%Y-%m-%d");
         assert_eq!(result, "2h ago");
     }
 
@@ -501,24 +1704,141 @@ mod tests {
     fn test_format_created_at_old() {
         let now = SystemTime::now();
         let two_days_ago = now - Duration::from_secs(2 * 24 * 60 * 60);
-        let result = format_created_at(two_days_ago);
+        let result = format_created_at(two_days_ago, now, CreatedFormat::Hybrid, "%Y-%m-%d");
         // Should show actual date for tasks older than 1 day
         assert!(result.contains("-"));
         assert!(!result.contains("ago"));
     }
 
+    #[test]
+    fn test_format_created_at_relative() {
+        let now = SystemTime::now();
+        let two_days_ago = now - Duration::from_secs(2 * 24 * 60 * 60);
+        let five_minutes_ago = now - Duration::from_secs(5 * 60);
+        assert_eq!(
+            format_created_at(two_days_ago, now, CreatedFormat::Relative, "%Y-%m-%d"),
+            "2d ago"
+        );
+        assert_eq!(
+            format_created_at(five_minutes_ago, now, CreatedFormat::Relative, "%Y-%m-%d"),
+            "5m ago"
+        );
+    }
+
+    #[test]
+    fn test_format_created_at_absolute() {
+        let now = SystemTime::now();
+        let two_hours_ago = now - Duration::from_secs(2 * 60 * 60);
+        let expected = DateTime::<Local>::from(two_hours_ago)
+            .format("%Y-%m-%d")
+            .to_string();
+        assert_eq!(
+            format_created_at(two_hours_ago, now, CreatedFormat::Absolute, "%Y-%m-%d"),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_custom_date_formats() {
+        let created: SystemTime = Local.with_ymd_and_hms(2024, 3, 5, 12, 0, 0).unwrap().into();
+        let now = created + Duration::from_secs(10 * 24 * 60 * 60);
+        assert_eq!(
+            format_created_at(created, now, CreatedFormat::Absolute, "%d/%m/%Y"),
+            "05/03/2024"
+        );
+        assert_eq!(
+            format_created_at(created, now, CreatedFormat::Hybrid, "%b %d"),
+            "Mar 05"
+        );
+        assert_eq!(
+            format_date(Some(created), now, Some("%d/%m/%Y")),
+            "10d ago (05/03/2024)"
+        );
+        assert_eq!(format_date(None, now, Some("%b %d")), "-");
+    }
+
     #[test]
     fn test_format_time_relative() {
         let now = SystemTime::now();
         let one_hour_ago = now - Duration::from_secs(60 * 60);
-        let result = format_time_relative(one_hour_ago);
+        let result = format_time_relative(one_hour_ago, now);
         assert_eq!(result, "1h ago");
 
         let future_time = now + Duration::from_secs(2 * 24 * 60 * 60);
-        let result = format_time_relative(future_time);
+        let result = format_time_relative(future_time, now);
         assert!(result.starts_with("in ") && result.contains("d"));
     }
 
+    #[test]
+    fn test_format_time_relative_long_intervals() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(2_000_000_000);
+        let day = Duration::from_secs(24 * 60 * 60);
+        let second = Duration::from_secs(1);
+        let cases = [
+            (second * 59, "now"),
+            (second * 60, "1m"),
+            (day - second, "23h"),
+            (day, "1d"),
+            (day * 14 - second, "13d"),
+            (day * 14, "2w"),
+            (day * 60 - second, "8w"),
+            (day * 60, "2mo"),
+            (day * 365 - second, "12mo"),
+            (day * 365, "1y"),
+            (day * 400, "1y"),
+            (day * 365 * 3, "3y"),
+        ];
+        for (offset, amount) in cases {
+            let (ago, ahead) = if amount == "now" {
+                ("now".to_string(), "now".to_string())
+            } else {
+                (format!("{amount} ago"), format!("in {amount}"))
+            };
+            assert_eq!(format_time_relative(now - offset, now), ago, "{offset:?}");
+            assert_eq!(format_time_relative(now + offset, now), ahead, "{offset:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse_hex_color() {
+        assert_eq!(parse_hex_color("#ff8000"), Some(Color::Rgb(255, 128, 0)));
+        assert_eq!(parse_hex_color("#0A0b0C"), Some(Color::Rgb(10, 11, 12)));
+        for bad in ["", "ff8000", "#ff800", "#ff80000", "#gg0000", "#ff80é"] {
+            assert_eq!(parse_hex_color(bad), None, "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn test_task_styles_follow_color_scheme() {
+        let now = SystemTime::now();
+        let colors = ColorScheme {
+            completed: "#111111".to_string(),
+            overdue: "#222222".to_string(),
+            deferred: "#333333".to_string(),
+            ..ColorScheme::default()
+        };
+        let mut task = Task::new("Pay rent", crate::types::TaskSource::Tui, now);
+        task.due = Some(now - Duration::from_secs(60));
+        let style = get_task_highlight_style(&task, now, &colors);
+        assert_eq!(style.fg, Some(Color::Rgb(0x22, 0x22, 0x22)));
+
+        task.defer_until = Some(now + Duration::from_secs(60));
+        let style = get_task_highlight_style(&task, now, &colors);
+        assert_eq!(style.fg, Some(Color::Rgb(0x33, 0x33, 0x33)));
+
+        task.completed = true;
+        let style = get_task_highlight_style(&task, now, &colors);
+        assert_eq!(style.fg, Some(Color::Rgb(0x11, 0x11, 0x11)));
+
+        // A color that doesn't parse falls back to the built-in one
+        let broken = ColorScheme {
+            completed: "grey".to_string(),
+            ..colors
+        };
+        let style = get_task_highlight_style(&task, now, &broken);
+        assert_eq!(style.fg, Some(Color::DarkGray));
+    }
+
     #[test]
     fn test_get_task_highlight_style_normal() {
         let task = Task {
@@ -529,9 +1849,26 @@ mod tests {
             created_at: SystemTime::now(),
             due: None,
             defer_until: None,
+            someday: false,
+            tags: Vec::new(),
+            energy: None,
+            source: TaskSource::Tui,
+            url: None,
+            notes: String::new(),
+            project: None,
+            waiting_since: None,
+            in_progress: false,
+            subtasks: Vec::new(),
+            defer_until_task: None,
+            recurrence: None,
+            modified_at: SystemTime::UNIX_EPOCH,
+            reminder_lead: None,
+            next_occurrence_id: None,
+            completed_at: None,
+            priority: Priority::Medium,
         };
 
-        let style = get_task_highlight_style(&task);
+        let style = get_task_highlight_style(&task, SystemTime::now(), &ColorScheme::default());
         assert_eq!(style, Style::default());
     }
 
@@ -546,10 +1883,27 @@ mod tests {
             created_at: SystemTime::now(),
             due: None,
             defer_until: Some(future_time),
+            someday: false,
+            tags: Vec::new(),
+            energy: None,
+            source: TaskSource::Tui,
+            url: None,
+            notes: String::new(),
+            project: None,
+            waiting_since: None,
+            in_progress: false,
+            subtasks: Vec::new(),
+            defer_until_task: None,
+            recurrence: None,
+            modified_at: SystemTime::UNIX_EPOCH,
+            reminder_lead: None,
+            next_occurrence_id: None,
+            completed_at: None,
+            priority: Priority::Medium,
         };
 
-        let style = get_task_highlight_style(&task);
-        assert_eq!(style.fg, Some(Color::DarkGray));
+        let style = get_task_highlight_style(&task, SystemTime::now(), &ColorScheme::default());
+        assert_eq!(style.fg, Some(Color::Rgb(255, 255, 0)));
     }
 
     #[test]
@@ -563,9 +1917,26 @@ mod tests {
             created_at: SystemTime::now(),
             due: Some(due_in_12_hours),
             defer_until: None,
+            someday: false,
+            tags: Vec::new(),
+            energy: None,
+            source: TaskSource::Tui,
+            url: None,
+            notes: String::new(),
+            project: None,
+            waiting_since: None,
+            in_progress: false,
+            subtasks: Vec::new(),
+            defer_until_task: None,
+            recurrence: None,
+            modified_at: SystemTime::UNIX_EPOCH,
+            reminder_lead: None,
+            next_occurrence_id: None,
+            completed_at: None,
+            priority: Priority::Medium,
         };
 
-        let style = get_task_highlight_style(&task);
+        let style = get_task_highlight_style(&task, SystemTime::now(), &ColorScheme::default());
         assert_eq!(style.fg, Some(Color::Yellow));
         assert!(style.add_modifier.contains(Modifier::BOLD));
     }
@@ -581,16 +1952,35 @@ mod tests {
             created_at: SystemTime::now(),
             due: Some(past_time),
             defer_until: None,
+            someday: false,
+            tags: Vec::new(),
+            energy: None,
+            source: TaskSource::Tui,
+            url: None,
+            notes: String::new(),
+            project: None,
+            waiting_since: None,
+            in_progress: false,
+            subtasks: Vec::new(),
+            defer_until_task: None,
+            recurrence: None,
+            modified_at: SystemTime::UNIX_EPOCH,
+            reminder_lead: None,
+            next_occurrence_id: None,
+            completed_at: None,
+            priority: Priority::Medium,
         };
 
-        let style = get_task_highlight_style(&task);
-        assert_eq!(style.fg, Some(Color::Red));
+        let style = get_task_highlight_style(&task, SystemTime::now(), &ColorScheme::default());
+        assert_eq!(style.fg, Some(Color::Rgb(255, 0, 0)));
         assert!(style.add_modifier.contains(Modifier::BOLD));
     }
 }
 
 #[derive(Debug, Error)]
 pub enum UiError {
+    #[error("not a terminal; use CLI subcommands (see `wimm --help`)")]
+    NotATerminal,
     #[error("Terminal IO error: {0}")]
     IoError(#[from] std::io::Error),
     #[error("DB error: {0}")]
@@ -5,7 +5,7 @@ use std::collections::HashMap;
 use std::time::SystemTime;
 use tempfile::TempDir;
 use wimm::storage::{Db, MemoryStorage, SledStorage};
-use wimm::types::{AppState, Mode, Task};
+use wimm::types::{AppState, Mode, Priority, Task, TaskSource};
 use wimm::ui::app::App;
 use wimm::ui::events::EventHandler;
 use wimm::ui::help_panel::HelpPanel;
@@ -20,6 +20,23 @@ fn create_test_task(id: &str, title: &str) -> Task {
         created_at: SystemTime::now(),
         due: None,
         defer_until: None,
+        someday: false,
+        tags: Vec::new(),
+        energy: None,
+        source: TaskSource::Tui,
+        url: None,
+        notes: String::new(),
+        project: None,
+        waiting_since: None,
+        in_progress: false,
+        subtasks: Vec::new(),
+        defer_until_task: None,
+        recurrence: None,
+        modified_at: SystemTime::UNIX_EPOCH,
+        reminder_lead: None,
+        next_occurrence_id: None,
+        completed_at: None,
+        priority: Priority::Medium,
     }
 }
 